@@ -1,32 +1,180 @@
 use rand::seq::SliceRandom;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::keccak;
 use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
 
 declare_id!("9CW2nv7psxCDH8Qr2XQGnHxveTYtMU6mHLzD2FXfG4kc");
 
+pub const MAX_DAILY_GAMES: u8 = 10;
+pub const MAX_LEADERBOARD_SIZE: usize = 8;
+/// Leaderboard size a competition starts with, until `set_leaderboard_size`
+/// changes it.
+pub const DEFAULT_LEADERBOARD_SIZE: u8 = 3;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Fixed-point scale of multipliers stored or emitted as integers, and of
+/// leaderboard scores: `MULTIPLIER_SCALE` is a 1.0x multiplier.
+pub const MULTIPLIER_SCALE: u64 = BPS_DENOMINATOR;
+pub const MAX_BULK_CLOSE: usize = 20;
+pub const MAX_NICKNAME_LEN: usize = 16;
+pub const MAX_BANNED_PLAYERS: usize = 50;
+pub const MAX_CLAIM_BATCH: usize = 5;
+
+pub const MAX_JOKERS: usize = 2;
+pub const JOKER_VALUE: u8 = 15;
+pub const JOKER_SUIT: &str = "Joker";
+pub const STANDARD_DECK_SIZE: u8 = 52;
+pub const IPFS_CID_LEN: usize = 46;
+
+/// Total returned for a correct leaderboard position prediction, as a multiple of the stake.
+pub const POSITION_BET_PAYOUT_MULTIPLE: u64 = 3;
+
+/// Side-bet score for correctly predicting the outcome of the next main bet.
+pub const STREAK_SIDE_BET_PAYOUT: i64 = 2;
+
+/// Every this many consecutive correct side bets earns the streak bonus.
+pub const SIDE_BET_STREAK_MILESTONE: u8 = 3;
+pub const DEFAULT_SIDE_BET_STREAK_BONUS: u8 = 5;
+
+/// Bounds of `CompetitionConfig::prize_claim_window_hours`: one day to one year.
+pub const MIN_PRIZE_CLAIM_WINDOW_HOURS: u16 = 24;
+pub const MAX_PRIZE_CLAIM_WINDOW_HOURS: u16 = 8760;
+/// Upper bound and suggested value of `CompetitionConfig::dispute_window_hours`.
+pub const MAX_DISPUTE_WINDOW_HOURS: u8 = 72;
+pub const DEFAULT_DISPUTE_WINDOW_HOURS: u8 = 24;
+/// Latest accepted competition timestamp, 3000-01-01 UTC. Far enough out for
+/// any real competition while keeping window math like
+/// `finalized_timestamp + claim_window` clear of overflow.
+pub const MAX_COMPETITION_TIMESTAMP: i64 = 32_503_680_000;
+
+/// Most games whose scores add up to a player's competition score.
+pub const MAX_ROUNDS_PER_COMPETITION: u8 = 10;
+
+pub const MAX_GAME_TAGS: usize = 3;
+
+pub const MAX_ADJUSTMENT_REASON_LEN: usize = 64;
+pub const MAX_SPECIAL_PRIZE_REASON_LEN: usize = 64;
+
+pub const MAX_GROUP_MEMBERS: usize = 10;
+/// Groups kept on a competition's `GroupLeaderboard`, one per prize position.
+pub const MAX_GROUP_LEADERBOARD_SIZE: usize = 3;
+
+/// Bits of `CompetitionConfig::event_emission_mask` for the per-game events. Bit 0 is
+/// reserved for `CardDrawn`; the deck commitment is carried by
+/// `RandomnessReceived`. Admin and payout events are always emitted.
+pub const EVENT_BIT_BET_PLACED: u8 = 1;
+pub const EVENT_BIT_HOT_CARD_ACTIVATED: u8 = 2;
+pub const EVENT_BIT_SIDE_BET_STREAK_BONUS: u8 = 3;
+pub const EVENT_BIT_GAME_STARTED: u8 = 4;
+pub const EVENT_BIT_RANDOMNESS_RECEIVED: u8 = 5;
+pub const EVENT_BIT_GAME_OVER: u8 = 6;
+pub const EVENT_BIT_SCORE_SUBMITTED: u8 = 7;
+
+/// Minimum seconds between two randomness deliveries accepted for a player.
+pub const MIN_RANDOMNESS_INTERVAL: i64 = 10;
+
+/// Size of a Switchboard ECVRF proof: gamma (32) + c (16) + s (32).
+pub const VRF_PROOF_LEN: usize = 80;
+
+/// Layout version stored right after the discriminator of every program
+/// account. Bump it whenever an account layout changes; accounts still on an
+/// older layout are rejected until migrated with `upgrade_account_layout`.
+pub const LAYOUT_VERSION: u8 = 1;
+
+/// Version byte `BetOutcome` serializes with.
+pub const BET_OUTCOME_VERSION: u8 = 1;
+
+/// Discriminator plus layout version byte.
+pub const VERSIONED_HEADER_LEN: usize = 8 + 1;
+
+/// Upper bound on player accounts per `submit_scores_batch` call. Deserializing
+/// and writing back ten player accounts stays well inside the default 200k
+/// compute unit budget.
+pub const MAX_SCORE_BATCH: usize = 10;
+
+/// Upper bound on the extra accounts a caller may forward to the game-over hook.
+pub const MAX_HOOK_ACCOUNTS: usize = 4;
+
+/// Anchor discriminator of `on_game_over` (`sha256("global:on_game_over")[..8]`),
+/// the instruction every hook program must expose.
+pub const GAME_OVER_HOOK_DISCRIMINATOR: [u8; 8] = [230, 216, 79, 184, 92, 93, 230, 168];
+
+/// Anchor discriminator of `record_event` (`sha256("global:record_event")[..8]`),
+/// the instruction every analytics program must expose.
+pub const ANALYTICS_RECORD_EVENT_DISCRIMINATOR: [u8; 8] = [32, 2, 109, 205, 6, 116, 72, 229];
+
+/// Metaplex Token Metadata program, which holds winner NFT metadata.
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+/// Tag of Token Metadata's `CreateMetadataAccountV3` instruction.
+pub const CREATE_METADATA_ACCOUNT_V3_TAG: u8 = 33;
+pub const MAX_NFT_URI_BASE_LEN: usize = 64;
+pub const WINNER_NFT_SYMBOL: &str = "DEGAME";
+
+/// `event_type` values reported to the analytics program.
+pub const ANALYTICS_EVENT_JOIN: u8 = 1;
+pub const ANALYTICS_EVENT_SCORE_SUBMIT: u8 = 2;
+pub const ANALYTICS_EVENT_CLAIM: u8 = 3;
+
+/// Bits of `EventFilter::event_types`, also hashed into each event's
+/// `filter_tag`.
+pub const FILTER_EVENT_BET_PLACED: u8 = 1 << 0;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+/// Tag of `ComputeBudgetInstruction::SetComputeUnitLimit`.
+pub const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
+pub const BASE_COMPUTE_UNITS: u32 = 200_000;
+/// Deepest hard-mode start allowed: half the deck.
+pub const MAX_DECK_OFFSET: u8 = 26;
+/// Deck index of the second hot card, the middle of a standard deck.
+pub const HOT_CARD_MIDDLE_INDEX: usize = 26;
+/// Bounds on each entry of the `BetWindowTable`.
+pub const MIN_BET_WINDOW_SECONDS: u32 = 5;
+pub const MAX_BET_WINDOW_SECONDS: u32 = 300;
+/// Extra units a dynamic-odds bet needs per card still in the deck.
+pub const COMPUTE_UNITS_PER_REMAINING_CARD: u32 = 1_000;
+
 #[program]
 pub mod pixel_card_game {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, start_time: i64, end_time: i64, entry_fee: u64) -> Result<()> {
-        if start_time >= end_time {
-            return err!(ErrorCode::InvalidStartTime);
-        }
-        if entry_fee == 0 {
-            return err!(ErrorCode::InvalidEntryFee);
-        }
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        start_time: i64,
+        end_time: i64,
+        competition_index: u32,
+        game_tags: Vec<GameTag>,
+        config: CompetitionConfigArgs,
+    ) -> Result<()> {
+        check_competition_times(start_time, end_time)?;
+        let entry_fee = config.entry_fee;
+        let competition_config: &mut CompetitionConfig = &mut ctx.accounts.config;
+        *competition_config = CompetitionConfig::new(config)?;
 
         let state = &mut ctx.accounts.state;
         state.admin = ctx.accounts.admin.key();
-        state.entry_fee = entry_fee;
+        state.config = ctx.accounts.config.key();
         state.start_time = start_time;
         state.end_time = end_time;
-        state.leaderboard_size = 3;
+        state.leaderboard_size = DEFAULT_LEADERBOARD_SIZE;
         state.finalized = false;
         state.pool = 0;
         state.finalized_timestamp = 0;
+        state.competition_index = competition_index;
+        state.game_tags = Vec::new();
+        for tag in game_tags {
+            state.add_tag(tag)?;
+            emit!(GameTagged { state: state.key(), tag });
+        }
 
         emit!(GameInitialized {
             admin: state.admin,
@@ -38,402 +186,5883 @@ pub mod pixel_card_game {
         Ok(())
     }
 
-    pub fn request_randomness(ctx: Context<RequestRandomness>, seed: u64) -> Result<()> {
-        let switchboard_vrf_program = ctx.accounts.switchboard_vrf_program.to_account_info();
-        let vrf_account = &ctx.accounts.vrf_account;
+    /// Creates the signer's player account, bound to them for good; every
+    /// instruction acting on it requires that authority.
+    pub fn create_player(ctx: Context<CreatePlayer>) -> Result<()> {
+        ctx.accounts.player.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
 
-        let instruction = Instruction {
-            program_id: switchboard_vrf_program.key(),
-            accounts: vec![
-                AccountMeta::new_readonly(vrf_account.key(), false),
-                AccountMeta::new(ctx.accounts.admin.key(), true),
-            ],
-            data: seed.to_le_bytes().to_vec(),
-        };
+    /// Commits the player to the keccak256 hash of a seed of their choosing,
+    /// which is mixed into the VRF output so the admin alone cannot pick the
+    /// deck. The commitment is fixed once the VRF output arrives.
+    pub fn commit_deck_seed(ctx: Context<CommitDeckSeed>, seed_hash: [u8; 32]) -> Result<()> {
+        let player = &mut ctx.accounts.player;
 
-        invoke(
-            &instruction,
-            &[switchboard_vrf_program, vrf_account.to_account_info(), ctx.accounts.admin.to_account_info()]
-        )?;
+        if player.deck_commitment.is_some() || player.vrf_output.is_some() {
+            return err!(ErrorCode::RandomnessAlreadyReceived);
+        }
+
+        player.seed_hash = Some(seed_hash);
+        player.player_seed = None;
+        Ok(())
+    }
+
+    /// Requests the VRF output for the player's current game. The request is
+    /// tagged with the start of their `commit_deck_seed` hash; the seed itself
+    /// stays secret until `reveal_deck_seed`.
+    pub fn request_randomness(ctx: Context<RequestRandomness>) -> Result<()> {
+        let seed_hash = ctx.accounts.player.seed_hash.ok_or(ErrorCode::SeedNotCommitted)?;
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&seed_hash[..8]);
+        let seed = u64::from_le_bytes(seed);
+
+        let source = &ctx.accounts.config.randomness_source;
+        let randomness_program = ctx.accounts.randomness_program.to_account_info();
+
+        if let Some(program_id) = source.program_id() {
+            if randomness_program.key() != program_id {
+                return err!(ErrorCode::InvalidRandomnessProgram);
+            }
+        }
+
+        let accounts = [
+            ctx.accounts.randomness_account.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+        ];
+
+        if let Some(instruction) = build_randomness_instruction(source, seed, &accounts) {
+            invoke(
+                &instruction,
+                &[randomness_program, accounts[0].clone(), accounts[1].clone()]
+            )?;
+        }
 
         emit!(RandomnessRequested { seed });
         Ok(())
     }
 
-    pub fn receive_randomness(ctx: Context<ReceiveRandomness>, randomness: u64) -> Result<()> {
+    /// Stores the VRF output for the current game with its proof and alpha,
+    /// so auditors can verify the shuffle independently. The player must have
+    /// committed their seed first; the deck is dealt when they reveal it.
+    pub fn receive_randomness(
+        ctx: Context<ReceiveRandomness>,
+        randomness: u64,
+        proof: [u8; VRF_PROOF_LEN],
+        alpha: [u8; 32],
+    ) -> Result<()> {
+        let player = &mut ctx.accounts.player;
+
+        if player.deck_commitment.is_some() || player.vrf_output.is_some() {
+            return err!(ErrorCode::RandomnessAlreadyReceived);
+        }
+        if player.seed_hash.is_none() {
+            return err!(ErrorCode::SeedNotCommitted);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if now - player.last_randomness_at < MIN_RANDOMNESS_INTERVAL {
+            return err!(ErrorCode::RandomnessRateLimited);
+        }
+        player.last_randomness_at = now;
+
+        player.vrf_output = Some(randomness);
+        player.randomness_proof = Some(RandomnessProof { proof, alpha });
+        Ok(())
+    }
+
+    /// Reveals the player's seed against their `commit_deck_seed` hash and
+    /// deals the game. The deck seed, the VRF output XOR'd with the revealed
+    /// seed, fixes which cards are in the deck and the first one showing;
+    /// every later card is drawn as a bet settles.
+    pub fn reveal_deck_seed(ctx: Context<RevealDeckSeed>, seed: u64) -> Result<()> {
         let player = &mut ctx.accounts.player;
 
-        if player.randomness.is_some() {
+        if player.deck_commitment.is_some() {
             return err!(ErrorCode::RandomnessAlreadyReceived);
         }
+        verify_seed_commitment(seed, player.seed_hash)?;
+        let randomness = player.vrf_output.ok_or(ErrorCode::RandomnessNotReceived)?;
+        player.player_seed = Some(seed);
 
-        player.randomness = Some(randomness);
-        player.deck = shuffle_deck(randomness);
+        let deck_seed = randomness ^ seed;
+        let deck_commitment = commit_seed(deck_seed);
+        player.deck_commitment = Some(deck_commitment);
+        player.hot_values =
+            hot_values(&shuffle_deck(deck_seed, ctx.accounts.config.joker_probability_bps));
+        player.status = GameStatus::Active;
 
-        emit!(RandomnessReceived { randomness });
+        if ctx.accounts.config.emits(EVENT_BIT_RANDOMNESS_RECEIVED) {
+            emit!(RandomnessReceived { deck_commitment });
+        }
         Ok(())
     }
 
-    pub fn start_game(ctx: Context<StartGame>, game_id: u64) -> Result<()> {
+    /// Deals the deck shuffled from `seed` without VRF or a player seed, so
+    /// whole games can be scripted in tests. Fails with `TestRngDisabled`
+    /// unless built with the `test-rng` feature.
+    pub fn inject_test_randomness(ctx: Context<InjectTestRandomness>, seed: u64) -> Result<()> {
+        #[cfg(feature = "test-rng")]
+        {
+            let now = Clock::get()?.unix_timestamp;
+            let deck = shuffle_deck(seed, ctx.accounts.config.joker_probability_bps);
+            inject_test_seed(&mut ctx.accounts.player, seed, now)?;
+            ctx.accounts.player.hot_values = hot_values(&deck);
+            Ok(())
+        }
+        #[cfg(not(feature = "test-rng"))]
+        {
+            let _ = (ctx, seed);
+            err!(ErrorCode::TestRngDisabled)
+        }
+    }
+
+    /// Starts a new game, unless the round's ban list lists the player. A
+    /// non-zero `deck_offset`,
+    /// up to the config's `max_deck_offset`, starts a hard-mode game that
+    /// many cards into the deck.
+    pub fn start_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StartGame<'info>>,
+        game_id: u64,
+        deck_offset: u8,
+    ) -> Result<()> {
+        ctx.accounts.state.check_entries_open()?;
+        if !ctx.accounts.blacklist_entry.data_is_empty() {
+            return err!(ErrorCode::Blacklisted);
+        }
+        if deck_offset > ctx.accounts.config.max_deck_offset {
+            return err!(ErrorCode::InvalidDeckOffset);
+        }
+        ensure_not_banned(&ctx.accounts.ban_list, &ctx.accounts.player.authority)?;
+        let entry_fee = ctx.accounts.config.entry_fee;
+        let max_pool_size = ctx.accounts.config.max_pool_size;
+        let (pool_share, jackpot_share) =
+            split_entry_fee(entry_fee, ctx.accounts.config.jackpot_contribution_bps)?;
+        let pool = pool_with_deposit(ctx.accounts.state.pool, pool_share, max_pool_size)?;
+        let jackpot = ctx
+            .accounts
+            .state
+            .jackpot
+            .checked_add(jackpot_share)
+            .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+
         let player = &mut ctx.accounts.player;
 
-        if player.daily_games >= 10 {
+        if player.daily_games >= MAX_DAILY_GAMES {
             return err!(ErrorCode::DailyLimitReached);
         }
+        check_game_id(player, game_id)?;
+        check_entry_cap(&ctx.accounts.state, player, ctx.accounts.config.max_entries)?;
+        // Winnings are paid from the pool they were won in, so they must be
+        // cashed out before the player moves to another competition.
+        if player.state != ctx.accounts.state.key() && player.pending_winnings > 0 {
+            return err!(ErrorCode::WinningsUnpaid);
+        }
+
+        // A first-place winner sits out the next `post_win_cooldown_competitions`
+        // competitions, unless this competition's admin waived it.
+        if let Some(last_win) = player.last_win_competition_index {
+            if ctx.accounts.state.competition_index.saturating_sub(last_win)
+                <= u32::from(ctx.accounts.config.post_win_cooldown_competitions)
+                && player.cooldown_waived_in != Some(ctx.accounts.state.key())
+            {
+                return err!(ErrorCode::PlayerInCooldown);
+            }
+        }
 
         player.daily_games += 1;
+        player.total_games += 1;
+        if player.total_games == 1 {
+            let state = &mut ctx.accounts.state;
+            state.participants = state
+                .participants
+                .checked_add(1)
+                .ok_or_else(|| overflow(ArithmeticOp::CounterAdd))?;
+        }
         player.start_time = Clock::get()?.unix_timestamp;
         player.game_id = game_id;
+        player.enter_competition(ctx.accounts.state.key(), ctx.accounts.state.competition_index);
+        player.competition_games = player.competition_games.saturating_add(1);
         player.finished = false;
-        player.multiplier = 1.0;
+        player.status = GameStatus::AwaitingRandomness;
+        player.multiplier = ctx.accounts.config.starting_multiplier_bps as f64 / BPS_DENOMINATOR as f64;
+        player.peak_multiplier = multiplier_to_bps(player.multiplier);
+        player.side_bets_placed = 0;
+        player.bets_placed = 0;
+        player.correct_bets = 0;
+        player.score_submitted = false;
+        // Every game is dealt from a fresh seed, so revealing the previous
+        // game's seed never exposes upcoming cards.
+        player.deck_commitment = None;
+        // Hard mode deals from `deck_offset` cards into the shuffled deck.
+        player.deck_offset = deck_offset;
+        player.cards_dealt = deck_offset;
+        player.dealt_mask = (1 << deck_offset) - 1;
+        player.current_card = deck_offset;
+        player.pending_bet = None;
+        player.side_bet_streak = 0;
+        player.revealed_seed = None;
+        player.pending_streak_bet = None;
+        player.seed_hash = None;
+        player.player_seed = None;
+        player.vrf_output = None;
+        player.deck_stats_hash = [0; 8];
+        player.jackpot_claimed = false;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.state.to_account_info(),
+                },
+            ),
+            entry_fee,
+        )?;
+        ctx.accounts.state.pool = pool;
+        ctx.accounts.state.jackpot = jackpot;
+        if pool == max_pool_size {
+            emit!(PoolFull {
+                current_pool: pool,
+                max_pool: max_pool_size,
+            });
+        }
 
-        emit!(GameStarted { player: player.key(), game_id });
+        let player = &ctx.accounts.player;
+        if ctx.accounts.config.emits(EVENT_BIT_GAME_STARTED) {
+            emit!(GameStarted { player: player.key(), game_id });
+        }
+
+        record_analytics(
+            &ctx.accounts.config,
+            ctx.accounts.analytics_program.as_ref(),
+            ANALYTICS_EVENT_JOIN,
+            ctx.accounts.authority.key(),
+            game_id,
+        )?;
         Ok(())
     }
 
-    pub fn place_bet(ctx: Context<PlaceBet>, bet_type: BetType, side_bet: Option<SideBetType>) -> Result<()> {
+    /// Places a bet on the card after the current one. The card is drawn when
+    /// the bet settles, from the hash of the slot the bet lands in, so nobody
+    /// can know it when betting. `bet_amount` lamports are wagered from
+    /// `player_wallet` into the pool; a correct bet credits
+    /// `bet_amount * multiplier_gain` to the player's pending winnings, paid
+    /// out by `submit_score` or `cashout`.
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        bet_type: BetType,
+        side_bet: Option<SideBetType>,
+        bet_amount: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let state = &mut ctx.accounts.state;
         let player = &mut ctx.accounts.player;
 
-        if Clock::get()?.unix_timestamp - player.start_time > 60 {
-            return err!(ErrorCode::BetTimeExpired);
+        if player.finished {
+            return err!(ErrorCode::GameOver);
+        }
+        if player.pending_bet.is_some() {
+            return err!(ErrorCode::BetPending);
         }
+        let (seed, deck) = deck_in_play(player, config)?;
 
-        let outcome = resolve_bet(player, &bet_type, side_bet)?;
+        // Holding back the first bet keeps a validator that sees the
+        // randomness land from betting on it in the same slot.
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        if player.bets_placed == 0
+            && !bet_delay_elapsed(player.last_randomness_at, now, config.min_bet_delay)
+        {
+            return err!(ErrorCode::TooSoonToBet);
+        }
 
-        if !outcome.correct {
-            player.finished = true;
-            emit!(GameOver {
+        // A lapsed bet window ends the game instead of leaving it open, so it
+        // succeeds without taking the wager.
+        if bet_window_lapsed(player, &deck, &config.bet_window_table, now) {
+            if let BetTimeoutPolicy::ForfeitMultiplier = config.bet_timeout_policy {
+                player.multiplier = 0.0;
+                player.peak_multiplier = 0;
+            }
+            end_game(player, config, seed, GameEndReason::BetTimeExpired);
+            return Ok(());
+        }
+
+        // A bet compares the current card against the next one, so the last
+        // card can never be bet on. The game finishes cleanly instead of
+        // erroring, keeping the multiplier and side-bet score earned so far.
+        if deck.len() < player.cards_dealt as usize + 2 {
+            // Surviving the whole deck with a positive side-bet score earns the
+            // configured completion bonus on top of it.
+            if player.side_bet_score > 0 {
+                let bonus = i64::from(config.deck_completion_bonus);
+                player.side_bet_score += bonus;
+                player.total_side_bet_score += bonus;
+            }
+
+            end_game(player, config, seed, GameEndReason::DeckExhausted);
+            return Ok(());
+        }
+
+        // Side bets outside the configured multiplier band are dropped rather
+        // than failing the main bet.
+        let side_bet = side_bet.filter(|_| side_bet_eligible(player.multiplier, config));
+        if let Some(side_bet) = &side_bet {
+            // Rejected here, since a bet that can't be resolved would sit
+            // pending until its slot hash ages out.
+            validate_side_bet(side_bet, deck.len() - player.cards_dealt as usize - 1)?;
+            if player.side_bets_placed >= config.max_side_bets_per_game {
+                return err!(ErrorCode::SideBetLimitReached);
+            }
+            player.side_bets_placed += 1;
+        }
+
+        if bet_amount > config.max_bet_amount {
+            return err!(ErrorCode::BetAmountTooLarge);
+        }
+        if bet_amount > 0 {
+            let pool = pool_with_deposit(state.pool, bet_amount, config.max_pool_size)?;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.player_wallet.to_account_info(),
+                        to: state.to_account_info(),
+                    },
+                ),
+                bet_amount,
+            )?;
+            state.pool = pool;
+
+            if pool == config.max_pool_size {
+                emit!(PoolFull {
+                    current_pool: pool,
+                    max_pool: config.max_pool_size,
+                });
+            }
+        }
+
+        player.pending_bet = Some(PendingBet {
+            config: config.key(),
+            bet_type,
+            side_bet,
+            bet_amount,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Settles the player's pending bet on the hash of the slot it was placed
+    /// in. Anyone may settle it from the next slot on. Once that hash has
+    /// left the `SlotHashes` sysvar the bet is forfeited as lost, so holding
+    /// back a losing settlement gains nothing.
+    pub fn settle_bet(ctx: Context<SettleBet>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let player = &mut ctx.accounts.player;
+
+        let bet = player.pending_bet.clone().ok_or(ErrorCode::NoPendingBet)?;
+        if Clock::get()?.slot <= bet.slot {
+            return err!(ErrorCode::BetNotSettleable);
+        }
+        let seed = player.deck_seed().ok_or(ErrorCode::RandomnessNotReceived)?;
+
+        // Dynamic odds scan the rest of the deck, which can outrun the default
+        // budget; the transaction has to raise it before this instruction.
+        if config.dynamic_odds {
+            let previous = get_instruction_relative(-1, &ctx.accounts.instructions)
+                .map_err(|_| ErrorCode::ComputeBudgetMustBeFirst)?;
+            check_compute_budget(&previous, required_compute_units(player.cards_dealt))?;
+        }
+
+        player.pending_bet = None;
+        player.bets_placed = player.bets_placed.saturating_add(1);
+        player.total_bets = player.total_bets.saturating_add(1);
+        player.competition_bets = player.competition_bets.saturating_add(1);
+
+        let slot_hash = slot_hash_at(&ctx.accounts.slot_hashes.try_borrow_data()?, bet.slot);
+        let Some(slot_hash) = slot_hash else {
+            emit!(BetForfeited {
                 player: player.key(),
-                game_id: player.game_id,
-                final_multiplier: player.multiplier,
-                side_bet_score: player.side_bet_score,
+                slot: bet.slot,
             });
-            return err!(ErrorCode::GameOver);
+            end_game(player, config, seed, GameEndReason::LostBet);
+            return Ok(());
+        };
+
+        let base = shuffle_deck(seed, config.joker_probability_bps);
+        let draw = draw_seed(&slot_hash, &player.key(), player.cards_dealt);
+        let order = arrange_deck(base.len(), player.dealt_mask, player.current_card, Some(draw));
+        let deck: Vec<Card> = order.iter().map(|&index| base[index].clone()).collect();
+
+        let bet_type = bet.bet_type;
+        let outcome = resolve_bet(
+            player,
+            &deck,
+            &bet_type,
+            bet.side_bet,
+            config.dynamic_odds,
+            config.side_bet_card,
+        )?;
+        player.record_deal(&order);
+
+        if !outcome.correct {
+            emit_spectator_update(player, bet_type);
+            end_game(player, config, seed, GameEndReason::LostBet);
+            return Ok(());
         }
 
+        player.correct_bets = player.correct_bets.saturating_add(1);
+        let hot_card = outcome.hot_card.filter(|_| config.emits(EVENT_BIT_HOT_CARD_ACTIVATED));
+        if let Some(card_value) = hot_card {
+            emit!(HotCardActivated {
+                player: player.key(),
+                card_value,
+                normal_gain: multiplier_to_bps(outcome.multiplier_gain / 2.0),
+                boosted_gain: multiplier_to_bps(outcome.multiplier_gain),
+            });
+        }
+        credit_winnings(player, bet.bet_amount, outcome.multiplier_gain)?;
         player.multiplier *= outcome.multiplier_gain;
+        if player.multiplier > player.best_multiplier {
+            player.best_multiplier = player.multiplier;
+        }
+
+        let multiplier_bps = multiplier_to_bps(player.multiplier);
+        if multiplier_bps > player.peak_multiplier {
+            player.peak_multiplier = multiplier_bps;
+        }
 
         if let Some(side_bet_result) = outcome.side_bet_result {
             player.side_bet_score += side_bet_result;
+            player.total_side_bet_score += side_bet_result;
+        }
+        if let Some(streak) = outcome.side_bet_streak_milestone {
+            let bonus = config.side_bet_streak_bonus;
+            player.side_bet_score += i64::from(bonus);
+            player.total_side_bet_score += i64::from(bonus);
+            if config.emits(EVENT_BIT_SIDE_BET_STREAK_BONUS) {
+                emit!(SideBetStreakBonus { player: player.key(), streak, bonus: bonus.into() });
+            }
+        }
+
+        if config.emits(EVENT_BIT_BET_PLACED) {
+            emit!(bet_placed_event(player, player.key(), bet_type, &outcome)?);
+        }
+        emit_spectator_update(player, bet_type);
+
+        // Zero leaves the game length bounded only by the deck.
+        if config.max_bets_per_game > 0 && player.bets_placed >= config.max_bets_per_game {
+            end_game(player, config, seed, GameEndReason::BetLimitReached);
+        }
+
+        Ok(())
+    }
+
+    pub fn submit_score(ctx: Context<SubmitScore>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let player = &mut ctx.accounts.player;
+
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if !player.finished {
+            return err!(ErrorCode::GameNotFinished);
+        }
+        if player.score_submitted {
+            return err!(ErrorCode::ScoreAlreadySubmitted);
+        }
+
+        let config = &ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        let game_score = calculate_total_score(player, state, config, now)?;
+        let score = record_round_score(player, state, config, game_score)?;
+        player.score_submitted = true;
+
+        state.upsert_entry(LeaderboardEntry {
+            player: ctx.accounts.authority.key(),
+            nickname: player.nickname.clone(),
+            score,
+            side_bet_score: player.side_bet_score,
+            claimed: false,
+            finished_at: now,
+        });
+        state.rank_leaderboard();
+
+        if let Some(group) = ctx.accounts.group.as_mut() {
+            let group_key = group.key();
+            let group_leaderboard = ctx
+                .accounts
+                .group_leaderboard
+                .as_mut()
+                .ok_or(ErrorCode::GroupLeaderboardMissing)?;
+            add_group_score(
+                group,
+                group_leaderboard,
+                group_key,
+                &ctx.accounts.authority.key(),
+                game_score,
+            )?;
+        }
+
+        settle_winnings(state, player, &ctx.accounts.authority.to_account_info())?;
+
+        if config.emits(EVENT_BIT_SCORE_SUBMITTED) {
+            emit!(ScoreSubmitted {
+                player: ctx.accounts.authority.key(),
+                game_id: player.game_id,
+                nickname: player.nickname.clone(),
+                score,
+            });
+        }
+
+        record_analytics(
+            config,
+            ctx.accounts.analytics_program.as_ref(),
+            ANALYTICS_EVENT_SCORE_SUBMIT,
+            ctx.accounts.authority.key(),
+            score,
+        )?;
+        Ok(())
+    }
+
+    /// Pays out the signer's pending wager winnings without submitting a score.
+    pub fn cashout(ctx: Context<Cashout>) -> Result<()> {
+        // Once finalized the pool belongs to the leaderboard's prizes.
+        if ctx.accounts.state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+
+        settle_winnings(
+            &mut ctx.accounts.state,
+            &mut ctx.accounts.player,
+            &ctx.accounts.authority.to_account_info(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Pays the whole jackpot to a player whose finished game ended above the
+    /// config's `jackpot_threshold_bps` multiplier, and starts it again from
+    /// zero. Each game can win it once.
+    pub fn claim_jackpot(ctx: Context<ClaimJackpot>) -> Result<()> {
+        let threshold = ctx.accounts.config.jackpot_threshold_bps;
+        let state = &mut ctx.accounts.state;
+        let player = &mut ctx.accounts.player;
+
+        if !player.finished
+            || player.status == GameStatus::Refunded
+            || player.jackpot_claimed
+            || threshold == 0
+            || multiplier_to_bps(player.multiplier) <= threshold
+        {
+            return err!(ErrorCode::JackpotNotWon);
+        }
+        let amount = state.jackpot;
+        if amount == 0 {
+            return err!(ErrorCode::JackpotEmpty);
         }
 
-        emit!(BetPlaced {
+        state.jackpot = 0;
+        player.jackpot_claimed = true;
+
+        **state.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(JackpotClaimed {
+            state: state.key(),
             player: player.key(),
-            game_id: player.game_id,
-            bet_type,
-            multiplier_gain: outcome.multiplier_gain,
-            side_bet_result: outcome.side_bet_result,
+            amount,
         });
+        Ok(())
+    }
+
+    /// Returns the entry fee of a game whose randomness never arrived within
+    /// the config's `randomness_timeout_seconds`, taking back the jackpot's
+    /// share while the jackpot still holds it. The game ends without a score.
+    pub fn refund_failed_game(ctx: Context<RefundFailedGame>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let state = &mut ctx.accounts.state;
+        let player = &mut ctx.accounts.player;
+
+        // Once finalized the pool belongs to the leaderboard's prizes.
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if player.status != GameStatus::AwaitingRandomness
+            || player.vrf_output.is_some()
+            || config.randomness_timeout_seconds == 0
+        {
+            return err!(ErrorCode::GameNotRefundable);
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if now - player.start_time < i64::from(config.randomness_timeout_seconds) {
+            return err!(ErrorCode::RandomnessTimeoutPending);
+        }
+
+        let amount = config.entry_fee;
+        let (_, jackpot_share) = split_entry_fee(amount, config.jackpot_contribution_bps)?;
+        let from_jackpot = jackpot_share.min(state.jackpot);
+        state.pool = state
+            .pool
+            .checked_sub(amount - from_jackpot)
+            .ok_or(ErrorCode::InsufficientPool)?;
+        state.jackpot -= from_jackpot;
+        player.finished = true;
+        player.status = GameStatus::Refunded;
+        player.score_submitted = true;
 
+        **state.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(GameRefunded {
+            player: player.key(),
+            game_id: player.game_id,
+            amount,
+        });
         Ok(())
     }
 
-    pub fn finalize_leaderboard(ctx: Context<FinalizeLeaderboard>) -> Result<()> {
+    /// Submits the scores of up to `MAX_SCORE_BATCH` player accounts passed as
+    /// remaining accounts. Accounts that cannot be submitted are skipped and
+    /// reported through `ScoreSkipped` instead of failing the whole batch.
+    /// Only games started in this competition by a known authority can be
+    /// submitted, since the leaderboard entry is paid out to that wallet.
+    pub fn submit_scores_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitScoresBatch<'info>>,
+    ) -> Result<()> {
         let state = &mut ctx.accounts.state;
 
         if ctx.accounts.admin.key() != state.admin {
             return err!(ErrorCode::Unauthorized);
         }
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if ctx.remaining_accounts.len() > MAX_SCORE_BATCH {
+            return err!(ErrorCode::BatchTooLarge);
+        }
 
-        let leaderboard_size = state.leaderboard_size.into();
-        state.leaderboard.sort_by(|a, b| b.score.cmp(&a.score));
-        state.leaderboard.truncate(leaderboard_size);
+        let config = &ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        let mut submitted: u8 = 0;
+        for account_info in ctx.remaining_accounts {
+            let skip_reason = if !account_info.is_writable {
+                Some(ScoreSkipReason::InvalidAccount)
+            } else {
+                match Account::<VersionedAccount<Player>>::try_from(account_info) {
+                    Err(_) => Some(ScoreSkipReason::InvalidAccount),
+                    Ok(player) if player.state != state.key() => {
+                        Some(ScoreSkipReason::OtherCompetition)
+                    }
+                    Ok(player) if !player.finished => Some(ScoreSkipReason::GameNotFinished),
+                    Ok(player) if player.score_submitted => Some(ScoreSkipReason::AlreadySubmitted),
+                    Ok(player) if player.authority == Pubkey::default() => {
+                        Some(ScoreSkipReason::UnknownAuthority)
+                    }
+                    Ok(player)
+                        if side_bet_points(player.side_bet_score, config.score_formula.side_bet_weight)
+                            .is_err() =>
+                    {
+                        Some(ScoreSkipReason::NegativeScore)
+                    }
+                    Ok(mut player) => {
+                        let game_score = calculate_total_score(&player, state, config, now)?;
+                        let score = record_round_score(&mut player, state, config, game_score)?;
+                        player.score_submitted = true;
+                        player.exit(&crate::ID)?;
 
-        state.finalized = true;
-        state.finalized_timestamp = Clock::get()?.unix_timestamp;
+                        state.upsert_entry(LeaderboardEntry {
+                            player: player.authority,
+                            nickname: player.nickname.clone(),
+                            score,
+                            side_bet_score: player.side_bet_score,
+                            claimed: false,
+                            finished_at: now,
+                        });
+                        submitted += 1;
 
-        emit!(LeaderboardFinalized {
-            timestamp: state.finalized_timestamp,
-            leaderboard: state.leaderboard.clone(),
+                        if config.emits(EVENT_BIT_SCORE_SUBMITTED) {
+                            emit!(ScoreSubmitted {
+                                player: player.authority,
+                                game_id: player.game_id,
+                                nickname: player.nickname.clone(),
+                                score,
+                            });
+                        }
+                        None
+                    }
+                }
+            };
+
+            if let Some(reason) = skip_reason {
+                emit!(ScoreSkipped {
+                    account: account_info.key(),
+                    reason,
+                });
+            }
+        }
+
+        state.rank_leaderboard();
+
+        emit!(ScoresBatchSubmitted {
+            submitted,
+            skipped: ctx.remaining_accounts.len() as u8 - submitted,
         });
 
         Ok(())
     }
 
-    pub fn claim_prize(ctx: Context<ClaimPrize>, position: u8) -> Result<()> {
-        let state = &mut ctx.accounts.state;
+    /// Notifies the configured hook program that a game has ended. This runs as
+    /// its own instruction so that a failing or malicious hook can never revert
+    /// the game-end accounting already committed by `place_bet` or
+    /// `settle_bet`. Unless the config marks the hook required, a call that
+    /// fails before the hook runs is reported through `GameOverHookFailed` and
+    /// the hook is dropped.
+    pub fn dispatch_game_over_hook<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DispatchGameOverHook<'info>>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let player = &mut ctx.accounts.player;
+        let hook_program = ctx.accounts.hook_program.to_account_info();
 
-        if !state.finalized {
-            return err!(ErrorCode::PrizeWindowExpired);
+        if config.hook_program != Some(hook_program.key()) {
+            return err!(ErrorCode::InvalidHookProgram);
         }
 
-        if Clock::get()?.unix_timestamp > state.finalized_timestamp + 72 * 3600 {
-            return err!(ErrorCode::PrizeWindowExpired);
-        }
+        let reason = player.pending_hook.take().ok_or(ErrorCode::NoPendingHook)?;
 
-        if position as usize >= state.leaderboard.len() {
-            return err!(ErrorCode::NotOnLeaderboard);
+        if ctx.remaining_accounts.len() > MAX_HOOK_ACCOUNTS {
+            return err!(ErrorCode::TooManyHookAccounts);
         }
 
-        let prize_percentage: u64 = match position {
-            0 => 50_u64,
-            1 => 30_u64,
-            2 => 20_u64,
-            _ => return err!(ErrorCode::NotOnLeaderboard),
+        let args = GameOverHookArgs {
+            player: player.key(),
+            game_id: player.game_id,
+            final_multiplier: player.multiplier,
+            side_bet_score: player.side_bet_score,
+            reason,
+            final_score: calculate_score(player, config.peak_weight)?,
         };
+        let instruction = build_game_over_hook_instruction(
+            hook_program.key(),
+            &args,
+            ctx.remaining_accounts,
+        )?;
 
-        let amount = state
-            .pool
-            .checked_mul(prize_percentage)
-            .and_then(|total| total.checked_div(100))
-            .ok_or(ErrorCode::ArithmeticError)?;
+        let mut account_infos = vec![hook_program, player.to_account_info()];
+        account_infos.extend(ctx.remaining_accounts.iter().cloned());
+
+        if let Err(error) = invoke(&instruction, &account_infos) {
+            if config.hook_required {
+                return Err(error.into());
+            }
+            emit!(GameOverHookFailed {
+                player: args.player,
+                game_id: args.game_id,
+            });
+        }
 
-        **ctx.accounts.state.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.player_wallet.to_account_info().try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
 
-        emit!(PrizeClaimed {
+    /// Registers the wallet that prizes won by the signer are paid out to.
+    /// Only the winner can set or change it, including after finalization.
+    pub fn set_payout_wallet(ctx: Context<SetPayoutWallet>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.payout_wallet.wallet = wallet;
+
+        emit!(PayoutWalletSet {
             player: ctx.accounts.player.key(),
-            position: position as usize,
-            prize: amount,
+            wallet,
         });
 
         Ok(())
     }
-}
 
-// Utility Functions
-pub fn shuffle_deck(randomness: u64) -> Vec<Card> {
-    let suits = ["Hearts", "Diamonds", "Clubs", "Spades"];
-    let values = 2..=14;
+    /// Records which events `subscriber` wants, so indexers can match them
+    /// against the `filter_tag` of emitted events.
+    pub fn register_event_filter(
+        ctx: Context<RegisterEventFilter>,
+        filter: EventFilter,
+    ) -> Result<()> {
+        let registration = &mut ctx.accounts.filter_registration;
+        registration.subscriber = ctx.accounts.subscriber.key();
+        registration.filter = filter;
+        Ok(())
+    }
 
-    let mut deck: Vec<Card> = suits
-        .iter()
-        .flat_map(|suit| values.clone().map(move |value| Card {
-            suit: suit.to_string(),
-            value,
-        }))
-        .collect();
+    /// Opts the player's game in or out of `SpectatorUpdate` events.
+    pub fn set_spectator_events(ctx: Context<SetSpectatorEvents>, enabled: bool) -> Result<()> {
+        let player = &mut ctx.accounts.player;
 
-    let mut rng = rand::rngs::StdRng::seed_from_u64(randomness);
-    deck.shuffle(&mut rng);
+        player.spectator_events = enabled;
+        Ok(())
+    }
 
-    deck
-}
+    pub fn subscribe_spectator(ctx: Context<SubscribeSpectator>) -> Result<()> {
+        ctx.accounts
+            .player
+            .add_spectator(ctx.accounts.config.max_spectators_per_game)?;
 
-pub fn resolve_bet(player: &mut Player, bet_type: &BetType, side_bet: Option<SideBetType>) -> Result<BetOutcome> {
-    if player.deck.is_empty() {
-        return err!(ErrorCode::GameOver);
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.player = ctx.accounts.player.key();
+        subscription.spectator = ctx.accounts.spectator.key();
+        Ok(())
     }
 
-    let current_card = player.deck.remove(0);
-    let next_card = player.deck.get(0).cloned().ok_or(ErrorCode::GameOver)?;
-
-    let outcome = match bet_type {
-        BetType::High => next_card.value > current_card.value,
-        BetType::Low => next_card.value < current_card.value,
-    };
+    /// Issues the caller's participation certificate for this competition,
+    /// once they have placed `min_bets_for_certificate` bets. Partner programs
+    /// can check for the certificate account to grant perks. A second call
+    /// fails, as the certificate account already exists.
+    pub fn issue_participation_certificate(ctx: Context<IssueCertificate>) -> Result<()> {
+        let certificate = participation_certificate(
+            &ctx.accounts.player,
+            &ctx.accounts.state.key(),
+            ctx.accounts.state.competition_index,
+            ctx.accounts.config.min_bets_for_certificate,
+            Clock::get()?.unix_timestamp,
+        )?;
 
-    let multiplier_gain = calculate_multiplier_gain(current_card.value, *bet_type);
+        emit!(CertificateIssued {
+            player: ctx.accounts.authority.key(),
+            best_score: certificate.best_score,
+            games_completed: certificate.games_completed,
+        });
+        ctx.accounts.certificate.set_inner(VersionedAccount(certificate));
+        Ok(())
+    }
 
-    let side_bet_result = if let Some(bet) = side_bet {
-        match bet {
-            SideBetType::Color { red } => {
-                let is_red = current_card.suit == "Hearts" || current_card.suit == "Diamonds";
-                if red == is_red {
-                    Some(1)
-                } else {
-                    Some(-1)
-                }
-            }
-            SideBetType::Parity { even } => {
-                let is_even = current_card.value % 2 == 0;
-                if even == is_even {
-                    Some(1)
-                } else {
-                    Some(-1)
-                }
+    /// Ends a subscription, returning its rent to the spectator.
+    pub fn unsubscribe_spectator(ctx: Context<UnsubscribeSpectator>) -> Result<()> {
+        let player = &mut ctx.accounts.player;
+        player.spectator_count = player.spectator_count.saturating_sub(1);
+        Ok(())
+    }
+
+    /// Registers a unique nickname for the player, releasing the record of the
+    /// previous nickname (if any) so it can be claimed again.
+    pub fn set_nickname(ctx: Context<SetNickname>, name: String) -> Result<()> {
+        validate_nickname(&name)?;
+
+        let player = &mut ctx.accounts.player;
+
+        // The old record is closed to the authority by its account constraint.
+        if !player.nickname.is_empty() && ctx.accounts.old_nickname.is_none() {
+            return err!(ErrorCode::NicknameRecordMissing);
+        }
+
+        ctx.accounts.nickname.owner = player.key();
+        player.nickname = name.clone();
+
+        emit!(NicknameSet {
+            player: player.key(),
+            nickname: name,
+        });
+
+        Ok(())
+    }
+
+    /// Waives the post-win cooldown of `player` for entering `state` only, so
+    /// an admin can't waive it for other competitions.
+    pub fn exempt_from_cooldown(ctx: Context<ExemptFromCooldown>, player: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        ctx.accounts.player_stats.cooldown_waived_in = Some(ctx.accounts.state.key());
+
+        emit!(CooldownWaived { player });
+        Ok(())
+    }
+
+    pub fn initialize_ban_list(ctx: Context<InitializeBanList>) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        ctx.accounts.ban_list.banned_players = Vec::new();
+        Ok(())
+    }
+
+    pub fn add_to_ban_list(ctx: Context<UpdateBanList>, player: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        let ban_list = &mut ctx.accounts.ban_list;
+        if ban_list.banned_players.contains(&player) {
+            return err!(ErrorCode::PlayerAlreadyBanned);
+        }
+        if ban_list.banned_players.len() >= MAX_BANNED_PLAYERS {
+            return err!(ErrorCode::BanListFull);
+        }
+        ban_list.banned_players.push(player);
+
+        emit!(BanListUpdated { player, banned: true });
+        Ok(())
+    }
+
+    pub fn remove_from_ban_list(ctx: Context<UpdateBanList>, player: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        let ban_list = &mut ctx.accounts.ban_list;
+        let index = ban_list
+            .banned_players
+            .iter()
+            .position(|banned| *banned == player)
+            .ok_or(ErrorCode::PlayerNotBanned)?;
+        ban_list.banned_players.swap_remove(index);
+
+        emit!(BanListUpdated { player, banned: false });
+        Ok(())
+    }
+
+    /// Blocks `wallet` from starting games. Unlike the ban list, the marker is
+    /// checked on every `start_game` and cannot be left out by the client.
+    pub fn blacklist(ctx: Context<Blacklist>, wallet: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        ctx.accounts.blacklist_entry.wallet = wallet;
+
+        emit!(BanListUpdated { player: wallet, banned: true });
+        Ok(())
+    }
+
+    /// Lifts a `blacklist` entry, returning its rent to the admin.
+    pub fn unblacklist(ctx: Context<Unblacklist>, wallet: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        emit!(BanListUpdated { player: wallet, banned: false });
+        Ok(())
+    }
+
+    /// Anchors the off-chain copy of the competition's leaderboard by its IPFS
+    /// CID and content hash.
+    pub fn anchor_leaderboard_data(
+        ctx: Context<AnchorLeaderboardData>,
+        cid: [u8; IPFS_CID_LEN],
+        hash: [u8; 32],
+    ) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        let leaderboard_anchor = &mut ctx.accounts.leaderboard_anchor;
+        leaderboard_anchor.ipfs_cid = cid;
+        leaderboard_anchor.data_hash = hash;
+
+        emit!(LeaderboardDataAnchored {
+            state: ctx.accounts.state.key(),
+            competition_index: ctx.accounts.state.competition_index,
+            ipfs_cid: cid,
+            data_hash: hash,
+        });
+
+        Ok(())
+    }
+
+    /// Fails with `LeaderboardDataMismatch` unless `raw_data` hashes to the
+    /// anchored leaderboard hash.
+    pub fn verify_leaderboard_data(ctx: Context<VerifyLeaderboardData>, raw_data: Vec<u8>) -> Result<()> {
+        if hash(&raw_data).to_bytes() != ctx.accounts.leaderboard_anchor.data_hash {
+            return err!(ErrorCode::LeaderboardDataMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Changes the score formula for subsequent submissions. Scores already on
+    /// the leaderboard keep the formula they were submitted under.
+    pub fn update_score_formula(ctx: Context<UpdateScoreFormula>, formula: ScoreFormula) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        formula.validate()?;
+
+        ctx.accounts.config.score_formula = formula;
+        Ok(())
+    }
+
+    /// Points the competition at a new rules document. Every change is logged
+    /// through `RulesHashUpdated`.
+    pub fn update_rules_hash(ctx: Context<UpdateRulesHash>, new_hash: [u8; 32]) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        let config = &mut ctx.accounts.config;
+        let old_hash = config.rules_hash;
+        config.rules_hash = new_hash;
+
+        emit!(RulesHashUpdated {
+            old_hash,
+            new_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Replaces the per-card bet windows. Only allowed before the competition
+    /// starts, so every game is played against the same windows.
+    pub fn update_bet_window_table(
+        ctx: Context<UpdateBetWindowTable>,
+        table: BetWindowTable,
+    ) -> Result<()> {
+        let state = &ctx.accounts.state;
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if Clock::get()?.unix_timestamp >= state.start_time {
+            return err!(ErrorCode::CompetitionStarted);
+        }
+        table.validate()?;
+
+        ctx.accounts.config.bet_window_table = table;
+        Ok(())
+    }
+
+    /// Emits whether `rules_doc` is the rules document the competition
+    /// committed to. Changes nothing.
+    pub fn verify_rules_hash(ctx: Context<VerifyRulesHash>, rules_doc: Vec<u8>) -> Result<()> {
+        emit!(RulesVerified {
+            matches: rules_match(&ctx.accounts.config, &rules_doc),
+        });
+        Ok(())
+    }
+
+    pub fn update_event_mask(ctx: Context<UpdateEventMask>, mask: u8) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        ctx.accounts.config.event_emission_mask = mask;
+        Ok(())
+    }
+
+    /// Copies another competition's config into this one, so recurring
+    /// tournaments can reuse their settings.
+    pub fn clone_config(ctx: Context<CloneConfig>) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if ctx.accounts.state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+
+        **ctx.accounts.config = (**ctx.accounts.source_config).clone();
+        Ok(())
+    }
+
+    /// Read-only heartbeat for monitoring. Anyone can call it.
+    pub fn health_check(ctx: Context<HealthCheck>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let clock = Clock::get()?;
+
+        emit!(HealthStatus {
+            program_version: LAYOUT_VERSION,
+            state_key: state.key(),
+            pool: state.pool,
+            participants: state.participants,
+            finalized: state.finalized,
+            clock: clock.unix_timestamp,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn add_game_tag(ctx: Context<AddGameTag>, tag: GameTag) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        state.add_tag(tag)?;
+
+        emit!(GameTagged { state: state.key(), tag });
+        Ok(())
+    }
+
+    /// Stops new games from starting. Bets and cash-outs in games already
+    /// started are unaffected.
+    pub fn close_entries(ctx: Context<CloseEntries>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        state.entries_closed = true;
+
+        emit!(EntriesClosed { state: state.key() });
+        Ok(())
+    }
+
+    /// Lifts the hold the collusion check put on prizes once the admin has
+    /// reviewed the flagged entries.
+    pub fn release_prizes(ctx: Context<ReleasePrizes>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        state.prizes_held = false;
+
+        emit!(PrizesReleased { state: state.key() });
+        Ok(())
+    }
+
+    /// Lets prizes be claimed right away instead of after the dispute window.
+    pub fn admin_override_dispute_window(ctx: Context<AdminOverrideDisputeWindow>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        state.dispute_window_bypassed = true;
+
+        emit!(DisputeWindowBypassed { state: state.key() });
+        Ok(())
+    }
+
+    /// Changes the number of leaderboard places, resizing the state account to
+    /// match. Only possible before any score or deposit reaches the competition.
+    pub fn set_leaderboard_size(ctx: Context<SetLeaderboardSize>, size: u8) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        let old_size = state.leaderboard_size;
+        state.resize_leaderboard(size)?;
+
+        emit!(LeaderboardResized { state: state.key(), old_size, new_size: size });
+        Ok(())
+    }
+
+    /// Corrects `player`'s leaderboard score by `delta`, clamped at zero, and
+    /// re-ranks the board. Only possible before finalization.
+    pub fn adjust_score(
+        ctx: Context<AdjustScore>,
+        player: Pubkey,
+        delta: i64,
+        reason: String,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if reason.len() > MAX_ADJUSTMENT_REASON_LEN {
+            return err!(ErrorCode::AdjustmentReasonTooLong);
+        }
+
+        let adjustment = state.adjust_entry_score(&player, delta)?;
+
+        emit!(ScoreAdjusted {
+            player,
+            old_score: adjustment.old_score,
+            new_score: adjustment.new_score,
+            reason,
+        });
+        if adjustment.old_position != adjustment.new_position {
+            emit!(LeaderboardPositionChanged {
+                player,
+                old_position: adjustment.old_position,
+                new_position: adjustment.new_position,
+            });
+        }
+        Ok(())
+    }
+
+    /// Emits `GameTagMatched` if the competition carries `tag`, so clients can
+    /// find competitions by subscribing to the event. Changes nothing.
+    pub fn filter_by_tag(ctx: Context<FilterByTag>, tag: GameTag) -> Result<()> {
+        if ctx.accounts.state.game_tags.contains(&tag) {
+            emit!(GameTagMatched { state: ctx.accounts.state.key() });
+        }
+        Ok(())
+    }
+
+    /// Emits the exact odds of the next bet from the cards left in the deck,
+    /// without dealing.
+    pub fn get_bet_odds(ctx: Context<GetBetOdds>) -> Result<()> {
+        let player = &ctx.accounts.player;
+
+        if player.finished {
+            return err!(ErrorCode::GameOver);
+        }
+        let (_, deck) = deck_in_play(player, &ctx.accounts.config)?;
+
+        emit!(calculate_bet_odds(&deck, player.cards_dealt)?);
+        Ok(())
+    }
+
+    /// Emits randomness metrics of the player's shuffled deck and records
+    /// their hash on the player account. Can run once per deck.
+    pub fn compute_deck_statistics(ctx: Context<ComputeDeckStatistics>) -> Result<()> {
+        let player = &mut ctx.accounts.player;
+
+        let seed = player.deck_seed().ok_or(ErrorCode::RandomnessNotReceived)?;
+        if player.deck_stats_hash != [0; 8] {
+            return err!(ErrorCode::DeckStatisticsAlreadyComputed);
+        }
+        let deck = shuffle_deck(seed, ctx.accounts.config.joker_probability_bps);
+
+        let stats = calculate_deck_statistics(&deck);
+        let mut stats_hash = [0; 8];
+        stats_hash.copy_from_slice(&hash(&stats.try_to_vec()?).to_bytes()[..8]);
+        player.deck_stats_hash = stats_hash;
+
+        emit!(stats);
+        Ok(())
+    }
+
+    pub fn get_randomness_proof(ctx: Context<GetPlayerSummary>) -> Result<RandomnessProof> {
+        ctx.accounts
+            .player
+            .randomness_proof
+            .clone()
+            .ok_or(ErrorCode::RandomnessNotReceived.into())
+    }
+
+    pub fn get_player_summary(ctx: Context<GetPlayerSummary>) -> Result<PlayerSummary> {
+        let player = &ctx.accounts.player;
+
+        Ok(PlayerSummary {
+            total_games: player.total_games,
+            best_multiplier: player.best_multiplier,
+            total_side_bet_score: player.total_side_bet_score,
+            daily_games_remaining: MAX_DAILY_GAMES.saturating_sub(player.daily_games),
+        })
+    }
+
+    /// Emits one page of the leaderboard. Fails with `LeaderboardModified` once
+    /// the leaderboard changes after `expected_epoch`; clients then restart
+    /// from page 0 with the epoch from its `LeaderboardSnapshot`.
+    pub fn get_leaderboard_page(
+        ctx: Context<GetLeaderboardPage>,
+        page: u8,
+        page_size: u8,
+        expected_epoch: u32,
+    ) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let entries = state.leaderboard_page(page, page_size, expected_epoch)?;
+
+        emit!(LeaderboardSnapshot {
+            epoch: state.leaderboard_snapshot_epoch,
+            page,
+            entries: entries.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Sets, or replaces, the special prize paid out of the pool before the
+    /// position split. `finalize_leaderboard` escrows it for the recipient.
+    pub fn set_special_prize(ctx: Context<SetSpecialPrize>, prize: SpecialPrize) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if prize.reason.len() > MAX_SPECIAL_PRIZE_REASON_LEN {
+            return err!(ErrorCode::SpecialPrizeReasonTooLong);
+        }
+
+        state.special_prize = Some(prize);
+        Ok(())
+    }
+
+    /// Pays the escrowed special prize to its recipient, returning the
+    /// escrow's rent to the admin.
+    pub fn claim_special_prize(ctx: Context<ClaimSpecialPrize>) -> Result<()> {
+        let amount = ctx.accounts.special_prize_escrow.amount;
+
+        **ctx.accounts.special_prize_escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(SpecialPrizeClaimed {
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn finalize_leaderboard<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeLeaderboard<'info>>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        // Finalizing again would restart the claim window.
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+
+        state.rank_leaderboard();
+
+        if ctx.accounts.config.collusion_check {
+            let wallets = entry_payout_wallets(state, ctx.remaining_accounts)?;
+            for (wallet, positions) in shared_payout_wallets(&wallets) {
+                state.prizes_held = true;
+                emit!(CollusionSuspected { wallet, positions });
             }
         }
-    } else {
-        None
-    };
 
-    Ok(BetOutcome {
-        correct: outcome,
-        multiplier_gain,
-        side_bet_result,
-    })
+        if let Some(prize) = state.reserve_special_prize()? {
+            let escrow = ctx
+                .accounts
+                .special_prize_escrow
+                .as_mut()
+                .ok_or(ErrorCode::SpecialPrizeEscrowMissing)?;
+            escrow.recipient = prize.recipient;
+            escrow.amount = prize.amount;
+
+            **state.to_account_info().try_borrow_mut_lamports()? -= prize.amount;
+            **escrow.to_account_info().try_borrow_mut_lamports()? += prize.amount;
+
+            emit!(SpecialPrizeSet {
+                recipient: prize.recipient,
+                amount: prize.amount,
+                reason: prize.reason,
+            });
+        }
+
+        state.finalized = true;
+        state.finalized_timestamp = Clock::get()?.unix_timestamp;
+        state.prize_pool = state.pool;
+
+        emit!(LeaderboardFinalized {
+            timestamp: state.finalized_timestamp,
+            leaderboard: state.leaderboard.clone(),
+            claim_deadline: prize_window_end(state, &ctx.accounts.config),
+            prizes: provisional_prizes(state, &ctx.accounts.config)?,
+            multiplier_scale: MULTIPLIER_SCALE,
+        });
+
+        Ok(())
+    }
+
+    /// Closes up to `MAX_BULK_CLOSE` finished player accounts of this
+    /// competition passed as remaining accounts, returning their rent to the
+    /// admin. Any account that played elsewhere, is still in play, is owed
+    /// wager winnings or backs an unclaimed prize fails the whole batch.
+    pub fn bulk_close_players<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BulkClosePlayers<'info>>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        if ctx.remaining_accounts.len() > MAX_BULK_CLOSE {
+            return err!(ErrorCode::BatchTooLarge);
+        }
+
+        let mut rent_reclaimed: u64 = 0;
+        for account_info in ctx.remaining_accounts {
+            let player = Account::<VersionedAccount<Player>>::try_from(account_info)?;
+
+            if player.state != state.key() {
+                return err!(ErrorCode::PlayerNotInCompetition);
+            }
+            if !player.finished {
+                return err!(ErrorCode::GameNotFinished);
+            }
+            if player.pending_winnings > 0 {
+                return err!(ErrorCode::WinningsUnpaid);
+            }
+
+            let unclaimed = player.score_submitted
+                && state
+                    .leaderboard
+                    .iter()
+                    .any(|entry| entry.player == player.authority && !entry.claimed);
+            if unclaimed {
+                return err!(ErrorCode::PrizeUnclaimed);
+            }
+
+            rent_reclaimed = rent_reclaimed
+                .checked_add(account_info.lamports())
+                .ok_or_else(|| overflow(ArithmeticOp::CounterAdd))?;
+            player.close(ctx.accounts.admin.to_account_info())?;
+        }
+
+        let count = ctx.remaining_accounts.len() as u8;
+        state.accounts_closed = state
+            .accounts_closed
+            .checked_add(count.into())
+            .ok_or_else(|| overflow(ArithmeticOp::CounterAdd))?;
+
+        emit!(AccountsClosedBatch { count, rent_reclaimed });
+
+        Ok(())
+    }
+
+    /// Rewrites the raw bytes of a program account into the current layout.
+    /// Only the program's upgrade authority may migrate accounts, and the
+    /// account type (its discriminator) can never change.
+    pub fn upgrade_account_layout(
+        ctx: Context<UpgradeAccountLayout>,
+        new_layout_data: Vec<u8>,
+    ) -> Result<()> {
+        let target = &ctx.accounts.target;
+
+        if new_layout_data.len() < VERSIONED_HEADER_LEN || new_layout_data[8] != LAYOUT_VERSION {
+            return err!(ErrorCode::AccountLayoutVersionMismatch);
+        }
+        if target.data_len() < 8 || target.try_borrow_data()?[..8] != new_layout_data[..8] {
+            return err!(ErrorCode::AccountTypeChanged);
+        }
+
+        let old_len = target.data_len();
+        let new_len = new_layout_data.len();
+
+        realloc_with_rent(
+            target,
+            new_len,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        target.try_borrow_mut_data()?.copy_from_slice(&new_layout_data);
+
+        emit!(AccountLayoutUpgraded {
+            account: target.key(),
+            old_len: old_len as u32,
+            new_len: new_len as u32,
+        });
+
+        Ok(())
+    }
+
+    /// Rewrites a `State` whose leaderboard entries predate `finished_at` into
+    /// the current layout, defaulting `finished_at` to 0.
+    pub fn migrate_leaderboard(ctx: Context<UpgradeAccountLayout>) -> Result<()> {
+        let target = &ctx.accounts.target;
+
+        let state = {
+            let data = target.try_borrow_data()?;
+            if data.len() < VERSIONED_HEADER_LEN || data[..8] != State::DISCRIMINATOR {
+                return err!(ErrorCode::AccountTypeChanged);
+            }
+            let mut body = &data[VERSIONED_HEADER_LEN..];
+            if State::deserialize(&mut body).is_ok() {
+                return err!(ErrorCode::AccountAlreadyMigrated);
+            }
+            decode_legacy_state(&data[VERSIONED_HEADER_LEN..])?
+        };
+
+        let leaderboard_size = state.leaderboard_size;
+        let mut new_layout_data = Vec::new();
+        VersionedAccount(state).try_serialize(&mut new_layout_data)?;
+
+        let old_len = target.data_len();
+        let new_len = old_len.max(state_space(leaderboard_size));
+
+        realloc_with_rent(
+            target,
+            new_len,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        let mut data = target.try_borrow_mut_data()?;
+        data.fill(0);
+        data[..new_layout_data.len()].copy_from_slice(&new_layout_data);
+
+        emit!(AccountLayoutUpgraded {
+            account: target.key(),
+            old_len: old_len as u32,
+            new_len: new_len as u32,
+        });
+
+        Ok(())
+    }
+
+    /// Stakes `stake` lamports on the position the signer expects to finish
+    /// in. The stake joins the prize pool straight away, so a forfeited stake
+    /// is shared out with the prizes at finalization; the escrow only records
+    /// the prediction. Predictions close when the competition ends.
+    pub fn predict_my_position(
+        ctx: Context<PredictMyPosition>,
+        predicted_position: u8,
+        stake: u64,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if Clock::get()?.unix_timestamp >= state.end_time {
+            return err!(ErrorCode::PredictionsClosed);
+        }
+        if predicted_position >= state.leaderboard_size {
+            return err!(ErrorCode::InvalidPredictedPosition);
+        }
+        if stake == 0 {
+            return err!(ErrorCode::InvalidStake);
+        }
+        state.pool = state
+            .pool
+            .checked_add(stake)
+            .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.player.to_account_info(),
+                    to: ctx.accounts.state.to_account_info(),
+                },
+            ),
+            stake,
+        )?;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.player = ctx.accounts.player.key();
+        escrow.predicted_position = predicted_position;
+        escrow.stake = stake;
+
+        emit!(PositionPredicted {
+            player: escrow.player,
+            predicted_position,
+            stake,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a position prediction after finalization. A correct prediction
+    /// is paid `POSITION_BET_PAYOUT_MULTIPLE` times the stake out of the
+    /// `PositionBetVault`; a wrong one leaves the stake in the prize pool it
+    /// joined when placed. The escrow is closed either way.
+    pub fn claim_position_bet(ctx: Context<ClaimPositionBet>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let escrow = &ctx.accounts.escrow;
+        let vault = &mut ctx.accounts.vault;
+
+        if !state.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+
+        let actual_position = state
+            .leaderboard
+            .iter()
+            .position(|entry| entry.player == escrow.player);
+        let won = actual_position == Some(escrow.predicted_position as usize);
+
+        let payout = if won {
+            let payout = escrow
+                .stake
+                .checked_mul(POSITION_BET_PAYOUT_MULTIPLE)
+                .ok_or_else(|| overflow(ArithmeticOp::PrizeMul))?;
+            vault.balance = vault
+                .balance
+                .checked_sub(payout)
+                .ok_or(ErrorCode::InsufficientPositionBetVault)?;
+
+            **vault.to_account_info().try_borrow_mut_lamports()? -= payout;
+            **ctx.accounts.player.to_account_info().try_borrow_mut_lamports()? += payout;
+            payout
+        } else {
+            0
+        };
+
+        emit!(PositionBetSettled {
+            player: escrow.player,
+            predicted_position: escrow.predicted_position,
+            actual_position: actual_position.map(|position| position as u8),
+            payout,
+        });
+
+        Ok(())
+    }
+
+    /// Lengthens the prize claim window of a finalized competition. Only
+    /// possible while the current window is still open.
+    pub fn extend_prize_claim_window(
+        ctx: Context<ExtendPrizeClaimWindow>,
+        additional_hours: u16,
+    ) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let config = &mut ctx.accounts.config;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if !state.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+        if Clock::get()?.unix_timestamp > prize_window_end(state, config) {
+            return err!(ErrorCode::PrizeWindowExpired);
+        }
+
+        config.prize_claim_window_hours = config
+            .prize_claim_window_hours
+            .checked_add(additional_hours)
+            .filter(|hours| *hours <= MAX_PRIZE_CLAIM_WINDOW_HOURS)
+            .ok_or(ErrorCode::InvalidPrizeClaimWindow)?;
+        Ok(())
+    }
+
+    /// Sends a finalized pool below `min_pool_for_payout` to the admin, since
+    /// its prizes can never be claimed.
+    pub fn reclaim_undersized_pool(ctx: Context<ReclaimUndersizedPool>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if !state.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+        if state.pool >= ctx.accounts.config.min_pool_for_payout {
+            return err!(ErrorCode::PoolAboveMinimum);
+        }
+
+        let amount = state.pool;
+        state.pool = 0;
+
+        **state.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.admin.to_account_info().try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// Returns the seconds left until the competition's milestones by the
+    /// on-chain clock, so UIs don't depend on the client's.
+    pub fn get_time_remaining(ctx: Context<GetTimeRemaining>) -> Result<TimeInfo> {
+        Ok(time_remaining(
+            &ctx.accounts.state,
+            &ctx.accounts.config,
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+
+    /// Returns where the player's current game is in its lifecycle.
+    pub fn get_game_status(ctx: Context<GetGameStatus>) -> Result<GameStatus> {
+        Ok(ctx.accounts.player.status)
+    }
+
+    /// Returns the net amount `claim_prize` would currently pay for `position`.
+    pub fn preview_prize(ctx: Context<PreviewPrize>, position: u8) -> Result<u64> {
+        let state = &ctx.accounts.state;
+        let position = LeaderboardPosition::new(position, state.leaderboard.len())?;
+
+        let (_, net_amount) = calculate_prize(state, &ctx.accounts.config, position.get())?;
+        Ok(net_amount)
+    }
+
+    pub fn claim_prize(ctx: Context<ClaimPrize>, position: u8) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let position = LeaderboardPosition::new(position, state.leaderboard.len())?;
+
+        let (amount, net_amount) =
+            take_prize(state, &ctx.accounts.config, position, &ctx.accounts.player.key())?;
+
+        if position.is_first() {
+            ctx.accounts.player_stats.record_win(state.competition_index);
+        }
+
+        PrizePayout {
+            state: ctx.accounts.state.to_account_info(),
+            escrow: &mut ctx.accounts.claim_escrow,
+            admin: ctx.accounts.admin.to_account_info(),
+            guarantee_reserve: ctx.accounts.guarantee_reserve.as_mut(),
+            analytics_program: ctx.accounts.analytics_program.as_ref(),
+        }
+        .pay(&ctx.accounts.config, ctx.accounts.player.key(), position, (amount, net_amount))
+    }
+
+    /// Creates a team whose members pool the scores of games they submit
+    /// through `submit_score`. The creator must be one of the members.
+    pub fn create_group(ctx: Context<CreateGroup>, group_id: u64, members: Vec<Pubkey>) -> Result<()> {
+        if ctx.accounts.state.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        if members.is_empty() || members.len() > MAX_GROUP_MEMBERS {
+            return err!(ErrorCode::InvalidGroupSize);
+        }
+        if members.iter().enumerate().any(|(i, member)| members[..i].contains(member)) {
+            return err!(ErrorCode::DuplicateGroupMember);
+        }
+        if !members.contains(&ctx.accounts.creator.key()) {
+            return err!(ErrorCode::NotGroupMember);
+        }
+
+        let state = ctx.accounts.state.key();
+        let group_leaderboard = &mut ctx.accounts.group_leaderboard;
+        group_leaderboard.state = state;
+
+        let group = &mut ctx.accounts.group;
+        group.group_id = group_id;
+        group.state = state;
+        group.members = members;
+
+        emit!(GroupCreated {
+            group: group.key(),
+            group_id,
+            members: group.members.clone(),
+        });
+        Ok(())
+    }
+
+    /// Pays out everything owed in the caller's claim escrow to their own
+    /// wallet or the payout wallet they registered. Nothing is lost if the
+    /// transfer fails; the amount stays owed until a withdrawal succeeds.
+    pub fn withdraw_claim(ctx: Context<WithdrawClaim>) -> Result<()> {
+        let payout_wallet = ctx.accounts.payout_wallet.as_ref().map(|payout| payout.wallet);
+        let amount = ctx
+            .accounts
+            .claim_escrow
+            .withdraw(&ctx.accounts.wallet.key(), payout_wallet)?;
+
+        **ctx.accounts.claim_escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.wallet.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(ClaimWithdrawn {
+            player: ctx.accounts.owner.key(),
+            wallet: ctx.accounts.wallet.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Tops up the reserve that backs the minimum prize guarantee.
+    pub fn fund_guarantee_reserve(ctx: Context<FundGuaranteeReserve>, amount: u64) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.guarantee_reserve.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let reserve = &mut ctx.accounts.guarantee_reserve;
+        reserve.state = ctx.accounts.state.key();
+        reserve.balance = reserve
+            .balance
+            .checked_add(amount)
+            .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+        Ok(())
+    }
+
+    /// Tops up the vault that pays out winning position bets.
+    pub fn fund_position_bet_vault(ctx: Context<FundPositionBetVault>, amount: u64) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.state = ctx.accounts.state.key();
+        vault.balance = vault
+            .balance
+            .checked_add(amount)
+            .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+        Ok(())
+    }
+
+    /// Adds `amount` to the group prize pool, paid out by `claim_group_prize`.
+    pub fn fund_group_prizes(ctx: Context<FundGroupPrizes>, amount: u64) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.group_leaderboard.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let group_leaderboard = &mut ctx.accounts.group_leaderboard;
+        group_leaderboard.pool = group_leaderboard
+            .pool
+            .checked_add(amount)
+            .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+        Ok(())
+    }
+
+    /// Ranks and freezes the group standings. The individual leaderboard has
+    /// to be finalized first.
+    pub fn finalize_group_leaderboard(ctx: Context<FinalizeGroupLeaderboard>) -> Result<()> {
+        let state = &ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if !state.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+
+        let group_leaderboard = &mut ctx.accounts.group_leaderboard;
+        if group_leaderboard.finalized {
+            return err!(ErrorCode::AlreadyFinalized);
+        }
+        group_leaderboard.rank();
+        group_leaderboard.finalized = true;
+
+        emit!(GroupLeaderboardFinalized {
+            state: state.key(),
+            entries: group_leaderboard.entries.clone(),
+            pool: group_leaderboard.pool,
+        });
+        Ok(())
+    }
+
+    /// Pays the signing member their equal share of the group prize for
+    /// `position`. No withdrawal fee is taken from group prizes.
+    pub fn claim_group_prize(ctx: Context<ClaimGroupPrize>, position: u8) -> Result<()> {
+        let group_leaderboard = &ctx.accounts.group_leaderboard;
+        if !group_leaderboard.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+        let entry = group_leaderboard
+            .entries
+            .get(position as usize)
+            .ok_or(ErrorCode::NotOnLeaderboard)?;
+        if entry.group != ctx.accounts.group.key() {
+            return err!(ErrorCode::NotOnLeaderboard);
+        }
+
+        let group = &mut ctx.accounts.group;
+        let member = ctx.accounts.member.key();
+        let index = group
+            .members
+            .iter()
+            .position(|candidate| *candidate == member)
+            .ok_or(ErrorCode::NotGroupMember)?;
+        if group.claimed & (1 << index) != 0 {
+            return err!(ErrorCode::PrizeAlreadyClaimed);
+        }
+        group.claimed |= 1 << index;
+
+        let amount = group_member_prize(group_leaderboard.pool, position, group.members.len())?;
+        **ctx.accounts.group_leaderboard.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.member.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(GroupPrizeClaimed {
+            group: ctx.accounts.group.key(),
+            member,
+            position,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Mints a commemorative NFT to a winner who has claimed their prize. The
+    /// winner brings a fresh zero-decimal mint they are the authority of; one
+    /// token is minted to their token account and its Token Metadata account
+    /// is created pointing at the position's metadata.
+    pub fn mint_winner_nft(ctx: Context<MintWinnerNft>, position: u8) -> Result<()> {
+        let state = &ctx.accounts.state;
+        let winner = ctx.accounts.winner.key();
+
+        let position = LeaderboardPosition::new(position, state.leaderboard.len())?;
+        let entry = &state.leaderboard[position.index()];
+        if entry.player != winner {
+            return err!(ErrorCode::NotOnLeaderboard);
+        }
+        if !entry.claimed {
+            return err!(ErrorCode::PrizeNotClaimed);
+        }
+
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.winner_token_account.to_account_info(),
+                    authority: ctx.accounts.winner.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let metadata = WinnerNftMetadata {
+            name: format!("degame #{} place {}", state.competition_index, position.get() + 1),
+            symbol: WINNER_NFT_SYMBOL.to_string(),
+            uri: winner_nft_uri(&ctx.accounts.config.winner_nft_metadata_uri_base, position),
+        };
+        let instruction = build_create_metadata_instruction(
+            ctx.accounts.token_metadata_program.key(),
+            ctx.accounts.metadata.key(),
+            ctx.accounts.mint.key(),
+            winner,
+            &metadata,
+        )?;
+        invoke(
+            &instruction,
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.winner.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+
+        emit!(WinnerNftMinted {
+            player: winner,
+            mint: ctx.accounts.mint.key(),
+            position: position.get(),
+        });
+        Ok(())
+    }
+
+    /// Publishes the Merkle root of a leaderboard ranked off-chain, switching
+    /// the competition to `claim_prize_merkle`. Can be corrected until the
+    /// first prize is claimed.
+    pub fn update_leaderboard_root(
+        ctx: Context<UpdateLeaderboardRoot>,
+        new_root: [u8; 32],
+        entry_count: u32,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if state.merkle_claimed != 0 || state.leaderboard.iter().any(|entry| entry.claimed) {
+            return err!(ErrorCode::PrizeAlreadyClaimed);
+        }
+
+        state.leaderboard_root = Some(new_root);
+        state.leaderboard_root_entries = entry_count;
+
+        emit!(LeaderboardRootUpdated { root: new_root, entry_count });
+        Ok(())
+    }
+
+    /// Claims the prize for `position` on the Merkle leaderboard, proving the
+    /// caller finished there with `score`. The prize is paid as `claim_prize`
+    /// pays it.
+    pub fn claim_prize_merkle(
+        ctx: Context<ClaimPrizeMerkle>,
+        position: u8,
+        score: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let config = &ctx.accounts.config;
+
+        let now = Clock::get()?.unix_timestamp;
+        if !state.finalized || now > prize_window_end(state, config) {
+            return err!(ErrorCode::PrizeWindowExpired);
+        }
+        check_dispute_window(state, config, now)?;
+        if state.pool < config.min_pool_for_payout {
+            return err!(ErrorCode::PoolBelowMinimum);
+        }
+        if state.prizes_held {
+            return err!(ErrorCode::PrizesHeldForReview);
+        }
+
+        let position = LeaderboardPosition::new(position, state.leaderboard_root_entries as usize)?;
+        let (amount, net_amount) = calculate_prize(state, config, position.get())?;
+        state.claim_merkle_position(position, &ctx.accounts.player.key(), score, &proof)?;
+
+        PrizePayout {
+            state: ctx.accounts.state.to_account_info(),
+            escrow: &mut ctx.accounts.claim_escrow,
+            admin: ctx.accounts.admin.to_account_info(),
+            guarantee_reserve: ctx.accounts.guarantee_reserve.as_mut(),
+            analytics_program: ctx.accounts.analytics_program.as_ref(),
+        }
+        .pay(&ctx.accounts.config, ctx.accounts.player.key(), position, (amount, net_amount))
+    }
+
+    /// Pays the house share of the prize schedule to the admin, once per
+    /// finalized competition, after the dispute window and within the claim
+    /// window.
+    pub fn claim_house_prize(ctx: Context<ClaimHousePrize>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        let config = &ctx.accounts.config;
+
+        if ctx.accounts.admin.key() != state.admin {
+            return err!(ErrorCode::Unauthorized);
+        }
+        if !state.finalized {
+            return err!(ErrorCode::LeaderboardNotFinalized);
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if now > prize_window_end(state, config) {
+            return err!(ErrorCode::PrizeWindowExpired);
+        }
+        check_dispute_window(state, config, now)?;
+        if state.house_claimed {
+            return err!(ErrorCode::PrizeAlreadyClaimed);
+        }
+        state.house_claimed = true;
+
+        let amount = house_prize(state, config)?;
+        **state.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.admin.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(HousePrizeClaimed { state: state.key(), amount });
+        Ok(())
+    }
+
+    /// Claims prizes from up to `MAX_CLAIM_BATCH` finalized tournaments at once,
+    /// paying each as `claim_prize` does. Remaining accounts hold, for each
+    /// claim, the tournament's state, its config, its admin, who receives the
+    /// withdrawal fee, the signer's claim escrow there, created if it doesn't
+    /// exist, and the tournament's guarantee reserve address, which need not
+    /// be initialized.
+    pub fn claim_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAll<'info>>,
+        claims: Vec<TournamentClaim>,
+    ) -> Result<()> {
+        if claims.len() > MAX_CLAIM_BATCH {
+            return err!(ErrorCode::BatchTooLarge);
+        }
+        if ctx.remaining_accounts.len() != claims.len() * 5 {
+            return err!(ErrorCode::ClaimAccountsMismatch);
+        }
+
+        let winner = ctx.accounts.player.key();
+        for (claim, accounts) in claims.iter().zip(ctx.remaining_accounts.chunks(5)) {
+            let (state_info, config_info, admin_info) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            if state_info.key() != claim.state {
+                return err!(ErrorCode::ClaimAccountsMismatch);
+            }
+            let mut state = Account::<VersionedAccount<State>>::try_from(state_info)?;
+            if config_info.key() != state.config {
+                return err!(ErrorCode::ClaimAccountsMismatch);
+            }
+            let config = Account::<VersionedAccount<CompetitionConfig>>::try_from(config_info)?;
+            if admin_info.key() != state.admin {
+                return err!(ErrorCode::Unauthorized);
+            }
+
+            let position = LeaderboardPosition::new(claim.position, state.leaderboard.len())?;
+            let (amount, net_amount) = take_prize(&mut state, &config, position, &winner)?;
+
+            if position.is_first() {
+                ctx.accounts.player_stats.record_win(state.competition_index);
+            }
+
+            // Written back right away so a repeated state in `claims` sees the
+            // prize as claimed.
+            state.exit(&crate::ID)?;
+
+            let mut escrow = open_claim_escrow(
+                &accounts[3],
+                state_info.key(),
+                &ctx.accounts.player.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            let mut reserve = guarantee_reserve(&accounts[4], &claim.state)?;
+            PrizePayout {
+                state: state_info.clone(),
+                escrow: &mut escrow,
+                admin: admin_info.clone(),
+                guarantee_reserve: reserve.as_mut(),
+                analytics_program: ctx.accounts.analytics_program.as_ref(),
+            }
+            .pay(&config, winner, position, (amount, net_amount))?;
+            escrow.exit(&crate::ID)?;
+            if let Some(reserve) = reserve {
+                reserve.exit(&crate::ID)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Utility Functions
+
+pub fn multiplier_to_bps(multiplier: f64) -> u64 {
+    (multiplier * MULTIPLIER_SCALE as f64) as u64
+}
+
+/// `BetPlaced` for a bet that just moved `player` to its current multiplier.
+pub fn bet_placed_event(
+    player: &Player,
+    player_key: Pubkey,
+    bet_type: BetType,
+    outcome: &BetOutcome,
+) -> Result<BetPlaced> {
+    let multiplier = multiplier_to_bps(player.multiplier);
+    Ok(BetPlaced {
+        filter_tag: filter_tag(FILTER_EVENT_BET_PLACED, &player_key, multiplier),
+        player: player_key,
+        game_id: player.game_id,
+        bet_type,
+        multiplier_gain: outcome.multiplier_gain,
+        side_bet_result: outcome.side_bet_result,
+        multiplier,
+        outcome: outcome.try_to_vec()?,
+    })
+}
+
+/// First 8 bytes of `keccak(event_type || player || multiplier)`, with
+/// `multiplier` little-endian. Leads filterable events so indexers can
+/// pre-filter logs without decoding them.
+pub fn filter_tag(event_type: u8, player: &Pubkey, multiplier: u64) -> [u8; 8] {
+    let hash = keccak::hashv(&[&[event_type], player.as_ref(), &multiplier.to_le_bytes()]);
+    let mut tag = [0; 8];
+    tag.copy_from_slice(&hash.to_bytes()[..8]);
+    tag
+}
+
+/// Reads a serialized `BetOutcome` of any version. Fields added after
+/// version 1 are ignored, and fields version 1 lacks take their defaults.
+pub fn decode_bet_outcome(data: &[u8]) -> Result<BetOutcome> {
+    let (version, mut fields) = data.split_first().ok_or(ErrorCode::InvalidBetOutcome)?;
+    if *version == 0 {
+        return err!(ErrorCode::InvalidBetOutcome);
+    }
+    let v1 = BetOutcomeV1::deserialize(&mut fields).map_err(|_| ErrorCode::InvalidBetOutcome)?;
+
+    Ok(BetOutcome {
+        correct: v1.correct,
+        multiplier_gain: v1.multiplier_gain as f64 / MULTIPLIER_SCALE as f64,
+        side_bet_result: v1.side_bet_result,
+        side_bet_streak_milestone: None,
+        hot_card: None,
+    })
+}
+
+pub fn game_over_event(player: &Player, player_key: Pubkey, reason: GameEndReason) -> GameOver {
+    GameOver {
+        player: player_key,
+        game_id: player.game_id,
+        final_multiplier: player.multiplier,
+        peak_multiplier: player.peak_multiplier,
+        side_bet_score: player.side_bet_score,
+        reason,
+        final_multiplier_scaled: multiplier_to_bps(player.multiplier),
+    }
+}
+
+/// Leaderboard score in basis points: the final multiplier, or the weighted
+/// peak multiplier if that is higher.
+pub fn calculate_score(player: &Player, peak_weight: u16) -> Result<u64> {
+    let weighted_peak = player
+        .peak_multiplier
+        .checked_mul(peak_weight.into())
+        .and_then(|total| total.checked_div(BPS_DENOMINATOR))
+        .ok_or_else(|| overflow(ArithmeticOp::MultiplierMul))?;
+
+    Ok(multiplier_to_bps(player.multiplier).max(weighted_peak))
+}
+
+/// Converts a side bet score into leaderboard points, rejecting a net loss
+/// with `NegativeScore` rather than wrapping or silently clamping it. Side
+/// bets weighted zero add no points, so a loss there is no reason to refuse
+/// the score.
+pub fn side_bet_points(side_bet_score: i64, side_bet_weight: u16) -> Result<u64> {
+    if side_bet_weight == 0 {
+        return Ok(0);
+    }
+    u64::try_from(side_bet_score).map_err(|_| ErrorCode::NegativeScore.into())
+}
+
+/// Leaderboard score blending the multiplier score with side bets, time left
+/// in the competition, correct-bet streak and cards left in the deck,
+/// weighted by the state's `ScoreFormula`, plus the unweighted hard-mode bonus.
+pub fn calculate_total_score(
+    player: &Player,
+    state: &State,
+    config: &CompetitionConfig,
+    now: i64,
+) -> Result<u64> {
+    let formula = &config.score_formula;
+    let components = [
+        (calculate_score(player, config.peak_weight)?, formula.multiplier_weight),
+        (side_bet_points(player.side_bet_score, formula.side_bet_weight)?, formula.side_bet_weight),
+        (state.end_time.saturating_sub(now).max(0) as u64, formula.time_bonus_weight),
+        (player.correct_bets.into(), formula.streak_bonus_weight),
+        (
+            STANDARD_DECK_SIZE.saturating_sub(player.cards_dealt).into(),
+            formula.cards_remaining_weight,
+        ),
+    ];
+
+    components
+        .iter()
+        .try_fold(0u64, |total, (value, weight)| {
+            value
+                .checked_mul((*weight).into())
+                .and_then(|weighted| total.checked_add(weighted))
+        })
+        .and_then(|total| total.checked_div(BPS_DENOMINATOR))
+        .and_then(|total| total.checked_add(hard_mode_bonus(player, config)?))
+        .ok_or_else(|| overflow(ArithmeticOp::ScoreAdd))
+}
+
+/// Flat score bonus for starting `deck_offset` cards into the deck.
+pub fn hard_mode_bonus(player: &Player, config: &CompetitionConfig) -> Option<u64> {
+    u64::from(player.deck_offset).checked_mul(config.hard_mode_bonus_per_card)
+}
+
+/// Fails with `PlayerBanned` if the round's ban list lists `authority`. A ban
+/// list that was never initialized bans nobody.
+fn ensure_not_banned(ban_list: &AccountInfo, authority: &Pubkey) -> Result<()> {
+    if ban_list.data_is_empty() {
+        return Ok(());
+    }
+
+    let ban_list = VersionedAccount::<BanList>::try_deserialize(&mut &ban_list.try_borrow_data()?[..])?;
+    if ban_list.banned_players.contains(authority) {
+        return err!(ErrorCode::PlayerBanned);
+    }
+
+    Ok(())
+}
+
+/// Nicknames are 1 to `MAX_NICKNAME_LEN` bytes of ASCII letters, digits, `_`
+/// or `-`, so they render safely and cannot imitate other names with
+/// look-alike characters.
+pub fn validate_nickname(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_NICKNAME_LEN {
+        return err!(ErrorCode::InvalidNicknameLength);
+    }
+    if !name
+        .bytes()
+        .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-')
+    {
+        return err!(ErrorCode::InvalidNicknameCharacters);
+    }
+
+    Ok(())
+}
+
+/// Adds what a winning `bet_amount` wager earns at `multiplier_gain` to the
+/// player's pending winnings, returning the amount added.
+pub fn credit_winnings(player: &mut Player, bet_amount: u64, multiplier_gain: f64) -> Result<u64> {
+    let winnings = (bet_amount as f64 * multiplier_gain) as u64;
+    player.pending_winnings = player
+        .pending_winnings
+        .checked_add(winnings)
+        .ok_or_else(|| overflow(ArithmeticOp::WinningsAdd))?;
+    Ok(winnings)
+}
+
+/// Pays the player's pending wager winnings out of the prize pool to `wallet`,
+/// which must be the player's authority.
+fn settle_winnings<'info>(
+    state: &mut Account<'info, VersionedAccount<State>>,
+    player: &mut Account<'info, VersionedAccount<Player>>,
+    wallet: &AccountInfo<'info>,
+) -> Result<()> {
+    if wallet.key() != player.authority {
+        return err!(ErrorCode::Unauthorized);
+    }
+    let amount = player.pending_winnings;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    state.pool = state
+        .pool
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientPool)?;
+    player.pending_winnings = 0;
+
+    **state.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **wallet.try_borrow_mut_lamports()? += amount;
+
+    emit!(WinningsSettled {
+        player: player.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Checks that `winner` can claim `position` within the claim window, marks
+/// it claimed and returns the gross and net prize.
+fn take_prize(
+    state: &mut State,
+    config: &CompetitionConfig,
+    position: LeaderboardPosition,
+    winner: &Pubkey,
+) -> Result<(u64, u64)> {
+    if !state.finalized {
+        return err!(ErrorCode::PrizeWindowExpired);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now > prize_window_end(state, config) {
+        return err!(ErrorCode::PrizeWindowExpired);
+    }
+
+    check_dispute_window(state, config, now)?;
+
+    if state.pool < config.min_pool_for_payout {
+        return err!(ErrorCode::PoolBelowMinimum);
+    }
+
+    if state.leaderboard_root.is_some() {
+        return err!(ErrorCode::MerkleLeaderboardActive);
+    }
+
+    if state.prizes_held {
+        return err!(ErrorCode::PrizesHeldForReview);
+    }
+
+    let prize = calculate_prize(state, config, position.get())?;
+    let entry = state
+        .leaderboard
+        .get_mut(position.index())
+        .ok_or(ErrorCode::NotOnLeaderboard)?;
+
+    if entry.player != *winner {
+        return err!(ErrorCode::NotOnLeaderboard);
+    }
+
+    if entry.claimed {
+        return err!(ErrorCode::PrizeAlreadyClaimed);
+    }
+
+    entry.claimed = true;
+
+    Ok(prize)
+}
+
+/// Accounts a claimed prize is paid through.
+struct PrizePayout<'a, 'info> {
+    state: AccountInfo<'info>,
+    escrow: &'a mut Account<'info, VersionedAccount<ClaimEscrow>>,
+    /// Receives the withdrawal fee.
+    admin: AccountInfo<'info>,
+    guarantee_reserve: Option<&'a mut Account<'info, VersionedAccount<GuaranteeReserve>>>,
+    analytics_program: Option<&'a AccountInfo<'info>>,
+}
+
+impl PrizePayout<'_, '_> {
+    /// Parks the `net_amount` of the `amount` prize `owner` claimed for
+    /// `position` in their escrow, to be paid out by `withdraw_claim` so a
+    /// failing payout never undoes the claim. The rest goes to the admin as
+    /// the withdrawal fee, and the reserve tops the prize up to the position's
+    /// minimum guarantee as far as it can.
+    fn pay(
+        self,
+        config: &CompetitionConfig,
+        owner: Pubkey,
+        position: LeaderboardPosition,
+        (amount, net_amount): (u64, u64),
+    ) -> Result<()> {
+        **self.state.try_borrow_mut_lamports()? -= amount;
+        **self.escrow.to_account_info().try_borrow_mut_lamports()? += net_amount;
+        **self.admin.try_borrow_mut_lamports()? += amount - net_amount;
+
+        let floor = config.minimum_prize_guarantee[position.index()];
+        let available = self.guarantee_reserve.as_ref().map_or(0, |reserve| reserve.balance);
+        let (top_up, shortfall) = guarantee_top_up(net_amount, floor, available);
+        if let Some(reserve) = self.guarantee_reserve {
+            reserve.balance -= top_up;
+            **reserve.to_account_info().try_borrow_mut_lamports()? -= top_up;
+            **self.escrow.to_account_info().try_borrow_mut_lamports()? += top_up;
+        }
+
+        self.escrow.state = self.state.key();
+        self.escrow.owner = owner;
+        self.escrow.record(net_amount + top_up)?;
+        if shortfall > 0 {
+            emit!(GuaranteeShortfall {
+                position: position.get(),
+                shortfall_amount: shortfall,
+            });
+        }
+
+        emit!(PrizeClaimed {
+            player: owner,
+            position: position.index(),
+            gross_prize: amount,
+            net_prize: net_amount,
+        });
+
+        record_analytics(config, self.analytics_program, ANALYTICS_EVENT_CLAIM, owner, net_amount)
+    }
+}
+
+/// The guarantee reserve of the competition `state` at `reserve`, if one was
+/// set up.
+fn guarantee_reserve<'info>(
+    reserve: &'info AccountInfo<'info>,
+    state: &Pubkey,
+) -> Result<Option<Account<'info, VersionedAccount<GuaranteeReserve>>>> {
+    let (address, _) = Pubkey::find_program_address(&[b"guarantee_reserve", state.as_ref()], &crate::ID);
+    if reserve.key() != address {
+        return err!(ErrorCode::ClaimAccountsMismatch);
+    }
+    if reserve.data_is_empty() {
+        return Ok(None);
+    }
+    Account::try_from(reserve).map(Some)
+}
+
+/// `owner`'s claim escrow for the competition `state`, created at the owner's
+/// expense if this is their first claim there.
+fn open_claim_escrow<'info>(
+    escrow: &'info AccountInfo<'info>,
+    state: Pubkey,
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Account<'info, VersionedAccount<ClaimEscrow>>> {
+    let seeds: &[&[u8]] = &[b"claim_escrow", state.as_ref(), owner.key.as_ref()];
+    let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    if escrow.key() != address {
+        return err!(ErrorCode::ClaimAccountsMismatch);
+    }
+    if !escrow.data_is_empty() {
+        return Account::try_from(escrow);
+    }
+
+    let space = VERSIONED_HEADER_LEN + ClaimEscrow::LEN;
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: owner.clone(),
+                to: escrow.clone(),
+            },
+            &[&[seeds, &[&[bump]]].concat()],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+    Account::try_from_unchecked(escrow)
+}
+
+/// Wallet each leaderboard entry would be paid to. `payout_accounts` holds
+/// every entry's `[b"payout", player]` address in leaderboard order; entries
+/// that never registered a payout wallet are paid to the player itself.
+fn entry_payout_wallets<'info>(
+    state: &State,
+    payout_accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Pubkey>> {
+    if payout_accounts.len() != state.leaderboard.len() {
+        return err!(ErrorCode::InvalidPayoutWallet);
+    }
+
+    state
+        .leaderboard
+        .iter()
+        .zip(payout_accounts)
+        .map(|(entry, account_info)| {
+            let (address, _) =
+                Pubkey::find_program_address(&[b"payout", entry.player.as_ref()], &crate::ID);
+            if account_info.key() != address {
+                return err!(ErrorCode::InvalidPayoutWallet);
+            }
+            if account_info.data_is_empty() {
+                return Ok(entry.player);
+            }
+            Ok(Account::<VersionedAccount<PayoutWallet>>::try_from(account_info)?.wallet)
+        })
+        .collect()
+}
+
+/// Wallets that more than one leaderboard position pays out to, with those
+/// positions, in order of first appearance.
+pub fn shared_payout_wallets(wallets: &[Pubkey]) -> Vec<(Pubkey, Vec<u8>)> {
+    let mut shared: Vec<(Pubkey, Vec<u8>)> = Vec::new();
+    for wallet in wallets {
+        if shared.iter().any(|(seen, _)| seen == wallet) {
+            continue;
+        }
+        let positions: Vec<u8> = wallets
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| *other == wallet)
+            .map(|(position, _)| position as u8)
+            .collect();
+        if positions.len() > 1 {
+            shared.push((*wallet, positions));
+        }
+    }
+    shared
+}
+
+/// Leaf of the Merkle leaderboard: `keccak(player || score || position)`,
+/// with `score` little-endian.
+pub fn leaderboard_leaf(player: &Pubkey, score: u64, position: u8) -> [u8; 32] {
+    keccak::hashv(&[player.as_ref(), &score.to_le_bytes(), &[position]]).to_bytes()
+}
+
+/// Parent of two Merkle nodes. The pair is hashed in sorted order, so proofs
+/// need no left/right flags.
+pub fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&low, &high]).to_bytes()
+}
+
+pub fn verify_merkle_proof(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| merkle_parent(node, *sibling)) == root
+}
+
+/// Seconds from `now` until each competition milestone.
+pub fn time_remaining(state: &State, config: &CompetitionConfig, now: i64) -> TimeInfo {
+    TimeInfo {
+        until_start: state.start_time.saturating_sub(now),
+        until_end: state.end_time.saturating_sub(now),
+        until_claim_deadline: state
+            .finalized
+            .then(|| prize_window_end(state, config).saturating_sub(now)),
+    }
+}
+
+/// Checked operations that can overflow, named in the log when they do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArithmeticOp {
+    PoolAdd,
+    PoolSub,
+    PrizeMul,
+    MultiplierMul,
+    ScoreAdd,
+    WinningsAdd,
+    CounterAdd,
+}
+
+impl ArithmeticOp {
+    /// Pool, prize and score overflows get their own errors; the rest share
+    /// `ArithmeticError`.
+    pub fn error_code(self) -> ErrorCode {
+        match self {
+            ArithmeticOp::PoolAdd | ArithmeticOp::PoolSub => ErrorCode::PoolOverflow,
+            ArithmeticOp::PrizeMul => ErrorCode::PrizeOverflow,
+            ArithmeticOp::MultiplierMul | ArithmeticOp::ScoreAdd => ErrorCode::ScoreOverflow,
+            ArithmeticOp::WinningsAdd | ArithmeticOp::CounterAdd => ErrorCode::ArithmeticError,
+        }
+    }
+}
+
+/// Logs which operation overflowed and returns its error, for use in
+/// `ok_or_else` after checked math.
+pub fn overflow(op: ArithmeticOp) -> Error {
+    msg!("Arithmetic overflow in {:?}", op);
+    op.error_code().into()
+}
+
+/// Amount the guarantee reserve adds to a `net_prize` below `floor`, out of
+/// the `available` reserve, and the part of the gap left unpaid.
+pub fn guarantee_top_up(net_prize: u64, floor: u64, available: u64) -> (u64, u64) {
+    let gap = floor.saturating_sub(net_prize);
+    let top_up = gap.min(available);
+    (top_up, gap - top_up)
+}
+
+/// Last moment prizes of a finalized competition can be claimed. The claim
+/// window opens once the dispute window has passed.
+pub fn prize_window_end(state: &State, config: &CompetitionConfig) -> i64 {
+    state.finalized_timestamp
+        + (i64::from(config.dispute_window_hours) + i64::from(config.prize_claim_window_hours))
+            * 3600
+}
+
+/// Fails with `DisputeWindowActive` until `dispute_window_hours` have passed
+/// since finalization, unless the admin bypassed the window.
+pub fn check_dispute_window(state: &State, config: &CompetitionConfig, now: i64) -> Result<()> {
+    let window_end = state.finalized_timestamp + i64::from(config.dispute_window_hours) * 3600;
+    if !state.dispute_window_bypassed && now < window_end {
+        return err!(ErrorCode::DisputeWindowActive);
+    }
+    Ok(())
+}
+
+/// The house's share of a finalized competition's pool, claimable by the
+/// admin through `claim_house_prize`.
+pub fn house_prize(state: &State, config: &CompetitionConfig) -> Result<u64> {
+    state
+        .prize_base()
+        .checked_mul(config.prize_schedule.house.into())
+        .and_then(|total| total.checked_div(100))
+        .ok_or_else(|| overflow(ArithmeticOp::PrizeMul))
+}
+
+/// Each member's equal share of the group prize for `position`, out of the
+/// group prize `pool`, split on the standard schedule. Any remainder stays in
+/// the pool.
+pub fn group_member_prize(pool: u64, position: u8, member_count: usize) -> Result<u64> {
+    if usize::from(position) >= MAX_GROUP_LEADERBOARD_SIZE {
+        return err!(ErrorCode::NotOnLeaderboard);
+    }
+    pool.checked_mul(PrizeSchedule::STANDARD.percentage(position)?)
+        .and_then(|total| total.checked_div(100))
+        .and_then(|total| total.checked_div(member_count as u64))
+        .ok_or_else(|| overflow(ArithmeticOp::PrizeMul))
+}
+
+/// Pools a member's game score into their group and re-ranks the group
+/// leaderboard. Fails with `NotGroupMember` if `member` isn't in `group`.
+pub fn add_group_score(
+    group: &mut PlayerGroup,
+    group_leaderboard: &mut GroupLeaderboard,
+    group_key: Pubkey,
+    member: &Pubkey,
+    score: u64,
+) -> Result<()> {
+    if !group.members.contains(member) {
+        return err!(ErrorCode::NotGroupMember);
+    }
+    if group.state != group_leaderboard.state {
+        return err!(ErrorCode::GroupLeaderboardMissing);
+    }
+    if group_leaderboard.finalized {
+        return err!(ErrorCode::AlreadyFinalized);
+    }
+
+    group.group_score = group
+        .group_score
+        .checked_add(score)
+        .ok_or_else(|| overflow(ArithmeticOp::ScoreAdd))?;
+
+    group_leaderboard.entries.retain(|entry| entry.group != group_key);
+    group_leaderboard.entries.push(GroupEntry {
+        group: group_key,
+        group_score: group.group_score,
+    });
+    group_leaderboard.rank();
+    Ok(())
+}
+
+/// Gross and net (after the withdrawal fee) prize for a leaderboard position.
+pub fn calculate_prize(
+    state: &State,
+    config: &CompetitionConfig,
+    position: u8,
+) -> Result<(u64, u64)> {
+    let amount = state
+        .prize_base()
+        .checked_mul(config.prize_schedule.percentage(position)?)
+        .and_then(|total| total.checked_div(100))
+        .ok_or_else(|| overflow(ArithmeticOp::PrizeMul))?;
+
+    // The withdrawal fee is taken out of the prize and paid to the admin.
+    let fee = amount
+        .checked_mul(config.withdrawal_fee_bps.into())
+        .and_then(|total| total.checked_div(BPS_DENOMINATOR))
+        .ok_or_else(|| overflow(ArithmeticOp::PrizeMul))?;
+    let net_amount = amount.checked_sub(fee).ok_or_else(|| overflow(ArithmeticOp::PrizeMul))?;
+
+    Ok((amount, net_amount))
+}
+
+/// Net prize each leaderboard position would be paid if claimed now.
+pub fn provisional_prizes(state: &State, config: &CompetitionConfig) -> Result<Vec<ProvisionalPrize>> {
+    state
+        .leaderboard
+        .iter()
+        .enumerate()
+        .map(|(position, entry)| {
+            let (_, net_amount) = calculate_prize(state, config, position as u8)?;
+            Ok(ProvisionalPrize {
+                position: position as u8,
+                player: entry.player,
+                amount: net_amount,
+            })
+        })
+        .collect()
+}
+
+/// Marks the game as finished, reveals the deck seed and queues the game-over
+/// hook if the round has one.
+fn end_game(
+    player: &mut Account<VersionedAccount<Player>>,
+    config: &CompetitionConfig,
+    seed: u64,
+    reason: GameEndReason,
+) {
+    player.finish(reason);
+    player.revealed_seed = Some(seed);
+
+    if config.hook_program.is_some() {
+        player.pending_hook = Some(reason);
+    }
+
+    if config.emits(EVENT_BIT_GAME_OVER) {
+        emit!(game_over_event(player, player.key(), reason));
+    }
+}
+
+/// Builds the provider-specific randomness request. `accounts` is the
+/// provider's randomness account followed by the paying admin.
+/// `BlockhashFallback` has no oracle to call, so no instruction is built and
+/// the randomness is delivered directly through `receive_randomness`.
+pub fn build_randomness_instruction(
+    source: &RandomnessSource,
+    seed: u64,
+    accounts: &[AccountInfo],
+) -> Option<Instruction> {
+    let (randomness_account, admin) = (&accounts[0], &accounts[1]);
+
+    match source {
+        RandomnessSource::SwitchboardVrf { program_id } => Some(Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(randomness_account.key(), false),
+                AccountMeta::new(admin.key(), true),
+            ],
+            data: seed.to_le_bytes().to_vec(),
+        }),
+        RandomnessSource::PythEntropy { program_id } => {
+            let mut user_commitment = [0u8; 32];
+            user_commitment[..8].copy_from_slice(&seed.to_le_bytes());
+
+            Some(Instruction {
+                program_id: *program_id,
+                accounts: vec![
+                    AccountMeta::new(admin.key(), true),
+                    AccountMeta::new(randomness_account.key(), false),
+                ],
+                data: user_commitment.to_vec(),
+            })
+        }
+        RandomnessSource::BlockhashFallback => None,
+    }
+}
+
+/// Hash stored as a game's deck commitment for the deck shuffled from `seed`.
+pub fn commit_seed(seed: u64) -> [u8; 32] {
+    hash(&seed.to_le_bytes()).to_bytes()
+}
+
+/// Reports `event_type` to the configured analytics program, if one is set and
+/// passed in. A failed call emits `AnalyticsCallFailed` instead of failing the
+/// caller. Only errors raised before the callee runs can be caught this way;
+/// an analytics program that fails during execution still aborts the
+/// transaction.
+fn record_analytics(
+    config: &CompetitionConfig,
+    analytics_program: Option<&AccountInfo>,
+    event_type: u8,
+    player: Pubkey,
+    value: u64,
+) -> Result<()> {
+    let (Some(program_id), Some(program)) = (config.analytics_program, analytics_program) else {
+        return Ok(());
+    };
+    if program.key() != program_id {
+        return err!(ErrorCode::InvalidAnalyticsProgram);
+    }
+
+    let args = AnalyticsEventArgs {
+        event_type,
+        player,
+        value,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    let instruction = build_analytics_instruction(program_id, &args)?;
+    if invoke(&instruction, std::slice::from_ref(program)).is_err() {
+        emit!(AnalyticsCallFailed { event_type, player });
+    }
+    Ok(())
+}
+
+/// Builds the `on_game_over` call of a hook program. The player account comes
+/// first, followed by `extra_accounts`, all forwarded without signer
+/// privileges.
+pub fn build_game_over_hook_instruction(
+    program_id: Pubkey,
+    args: &GameOverHookArgs,
+    extra_accounts: &[AccountInfo],
+) -> Result<Instruction> {
+    let mut data = GAME_OVER_HOOK_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data)?;
+
+    let mut accounts = vec![AccountMeta::new_readonly(args.player, false)];
+    for account in extra_accounts {
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.key(), false)
+        } else {
+            AccountMeta::new_readonly(account.key(), false)
+        });
+    }
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Metadata URI of the winner NFT for `position`.
+pub fn winner_nft_uri(base: &str, position: LeaderboardPosition) -> String {
+    format!("{}{}.json", base, position.get())
+}
+
+/// Builds Token Metadata's `CreateMetadataAccountV3` call for a winner NFT.
+/// `authority` is the mint authority and pays for and owns the metadata; the
+/// NFT has no royalties, creators, collection or uses, and is immutable.
+pub fn build_create_metadata_instruction(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    authority: Pubkey,
+    args: &WinnerNftMetadata,
+) -> Result<Instruction> {
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3_TAG];
+    args.serialize(&mut data)?;
+    // Seller fee, then no creators, collection or uses, not mutable and no
+    // collection details.
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+
+    Ok(Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    })
+}
+
+/// Builds the `record_event` call of an analytics program.
+pub fn build_analytics_instruction(
+    program_id: Pubkey,
+    args: &AnalyticsEventArgs,
+) -> Result<Instruction> {
+    let mut data = ANALYTICS_RECORD_EVENT_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id,
+        accounts: vec![],
+        data,
+    })
+}
+
+/// Compute units a dynamic-odds bet needs with `cards_dealt` cards gone.
+pub fn required_compute_units(cards_dealt: u8) -> u32 {
+    let remaining = STANDARD_DECK_SIZE.saturating_sub(cards_dealt);
+    BASE_COMPUTE_UNITS + u32::from(remaining) * COMPUTE_UNITS_PER_REMAINING_CARD
+}
+
+/// Builds the `set_compute_unit_limit` call clients must put directly before
+/// `settle_bet` when dynamic odds are on. The runtime only honours compute
+/// budget instructions at the top level of a transaction, so the program
+/// cannot request the units itself through CPI.
+pub fn request_compute_units(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT_TAG];
+    data.extend_from_slice(&units.to_le_bytes());
+
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Checks that `previous` sets a compute unit limit of at least `required`.
+pub fn check_compute_budget(previous: &Instruction, required: u32) -> Result<()> {
+    if previous.program_id != COMPUTE_BUDGET_PROGRAM_ID {
+        return err!(ErrorCode::ComputeBudgetMustBeFirst);
+    }
+    let units = match previous.data.as_slice() {
+        [SET_COMPUTE_UNIT_LIMIT_TAG, units @ ..] => {
+            u32::from_le_bytes(units.try_into().map_err(|_| ErrorCode::ComputeBudgetMustBeFirst)?)
+        }
+        _ => return err!(ErrorCode::ComputeBudgetMustBeFirst),
+    };
+    if units < required {
+        return err!(ErrorCode::InsufficientComputeBudget);
+    }
+    Ok(())
+}
+
+/// Pool after depositing `amount`, failing with `PoolFull` past a non-zero
+/// `max_pool_size`.
+/// Splits an entry fee into the part that goes to the pool and the
+/// `contribution_bps` share that goes to the jackpot.
+pub fn split_entry_fee(entry_fee: u64, contribution_bps: u16) -> Result<(u64, u64)> {
+    let jackpot_share = entry_fee
+        .checked_mul(contribution_bps.into())
+        .map(|share| share / BPS_DENOMINATOR)
+        .ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+    let pool_share = entry_fee
+        .checked_sub(jackpot_share)
+        .ok_or_else(|| overflow(ArithmeticOp::PoolSub))?;
+    Ok((pool_share, jackpot_share))
+}
+
+pub fn pool_with_deposit(pool: u64, amount: u64, max_pool_size: u64) -> Result<u64> {
+    let new_pool = pool.checked_add(amount).ok_or_else(|| overflow(ArithmeticOp::PoolAdd))?;
+    if max_pool_size > 0 && new_pool > max_pool_size {
+        return err!(ErrorCode::PoolFull);
+    }
+    Ok(new_pool)
+}
+
+/// Fails with `TournamentFull` when `player` would be a new participant past a
+/// non-zero `max_entries`. Players already in keep starting games.
+pub fn check_entry_cap(state: &State, player: &Player, max_entries: u32) -> Result<()> {
+    if max_entries > 0 && player.total_games == 0 && state.participants >= max_entries {
+        return err!(ErrorCode::TournamentFull);
+    }
+    Ok(())
+}
+
+/// Checks that a competition runs forwards between the Unix epoch and
+/// `MAX_COMPETITION_TIMESTAMP`.
+pub fn check_competition_times(start_time: i64, end_time: i64) -> Result<()> {
+    for time in [start_time, end_time] {
+        if !(0..=MAX_COMPETITION_TIMESTAMP).contains(&time) {
+            return err!(ErrorCode::InvalidTimestamp);
+        }
+    }
+    if start_time >= end_time {
+        return err!(ErrorCode::InvalidStartTime);
+    }
+    Ok(())
+}
+
+/// The certificate `player` earns in competition `competition_index` of
+/// `state`, or `NotEnoughBetsForCertificate` if they haven't placed `min_bets`
+/// bets in it yet. Only games and bets in that competition count.
+pub fn participation_certificate(
+    player: &Player,
+    state: &Pubkey,
+    competition_index: u32,
+    min_bets: u8,
+    now: i64,
+) -> Result<ParticipationCertificate> {
+    let played_here =
+        player.state == *state && player.activity_competition_index == competition_index;
+    let (games, bets) = if played_here {
+        (player.competition_games, player.competition_bets)
+    } else {
+        (0, 0)
+    };
+    if bets < u32::from(min_bets) {
+        return err!(ErrorCode::NotEnoughBetsForCertificate);
+    }
+
+    let best_score = if player.state == *state && player.scores_competition_index == competition_index {
+        player.competition_scores.iter().copied().max().unwrap_or(0)
+    } else {
+        0
+    };
+    Ok(ParticipationCertificate {
+        games_completed: games.try_into().unwrap_or(u8::MAX),
+        best_score,
+        issued_at: now,
+    })
+}
+
+/// Whether a player at `multiplier` may place a side bet under `config`'s
+/// multiplier band.
+pub fn side_bet_eligible(multiplier: f64, config: &CompetitionConfig) -> bool {
+    let multiplier = multiplier_to_bps(multiplier);
+    multiplier >= config.side_bet_min_multiplier_bps
+        && (config.side_bet_max_multiplier_bps == 0
+            || multiplier <= config.side_bet_max_multiplier_bps)
+}
+
+/// Whether `min_bet_delay` seconds have passed since randomness was received
+/// at `received_at`.
+pub fn bet_delay_elapsed(received_at: i64, now: i64, min_bet_delay: u32) -> bool {
+    now.saturating_sub(received_at) >= i64::from(min_bet_delay)
+}
+
+/// Whether the player has run out of time to bet on the current card, counted
+/// from the start of the game.
+pub fn bet_window_lapsed(player: &Player, deck: &[Card], table: &BetWindowTable, now: i64) -> bool {
+    let window = deck
+        .get(player.cards_dealt as usize)
+        .map_or(MAX_BET_WINDOW_SECONDS, |card| table.window_for(card.value));
+    now.saturating_sub(player.start_time) > i64::from(window)
+}
+
+/// Reports a resolved bet to the player's spectators, if they opted in.
+fn emit_spectator_update(player: &Account<VersionedAccount<Player>>, last_bet_type: BetType) {
+    if player.spectator_events {
+        emit!(SpectatorUpdate {
+            player: player.key(),
+            spectator_count: player.spectator_count,
+            last_bet_type,
+            new_multiplier: multiplier_to_bps(player.multiplier),
+        });
+    }
+}
+
+/// Game ids must grow with every game a player starts, so an id never names
+/// two games in events or seed derivation.
+pub fn check_game_id(player: &Player, game_id: u64) -> Result<()> {
+    if player.total_games > 0 && game_id <= player.game_id {
+        return err!(ErrorCode::GameIdReused);
+    }
+    Ok(())
+}
+
+/// The values of the first and middle cards of a freshly shuffled deck,
+/// which double the gain of bets placed on them for that deck.
+pub fn hot_values(deck: &[Card]) -> [u8; 2] {
+    let value = |index: usize| deck.get(index).map_or(0, |card| card.value);
+    [value(0), value(HOT_CARD_MIDDLE_INDEX)]
+}
+
+/// Commits `player` to the deck shuffled from `seed`, as `receive_randomness`
+/// would, but with no proof and no player seed mixed in.
+#[cfg(feature = "test-rng")]
+pub fn inject_test_seed(player: &mut Player, seed: u64, now: i64) -> Result<()> {
+    if player.deck_commitment.is_some() {
+        return err!(ErrorCode::RandomnessAlreadyReceived);
+    }
+    player.deck_commitment = Some(commit_seed(seed));
+    player.vrf_output = Some(seed);
+    player.player_seed = Some(0);
+    player.last_randomness_at = now;
+    player.status = GameStatus::Active;
+    Ok(())
+}
+
+/// Whether `rules_doc` hashes to the config's `rules_hash`.
+pub fn rules_match(config: &CompetitionConfig, rules_doc: &[u8]) -> bool {
+    keccak::hash(rules_doc).to_bytes() == config.rules_hash
+}
+
+/// Checks a player's revealed seed against their keccak256 commitment.
+pub fn verify_seed_commitment(seed: u64, seed_hash: Option<[u8; 32]>) -> Result<()> {
+    let seed_hash = seed_hash.ok_or(ErrorCode::SeedNotCommitted)?;
+    if keccak::hash(&seed.to_le_bytes()).to_bytes() != seed_hash {
+        return err!(ErrorCode::SeedCommitmentMismatch);
+    }
+    Ok(())
+}
+
+/// Shuffles a standard 52-card deck, then inserts up to `MAX_JOKERS` jokers,
+/// each with `joker_probability_bps` chance, at positions drawn from the same
+/// seeded RNG.
+pub fn shuffle_deck(randomness: u64, joker_probability_bps: u16) -> Vec<Card> {
+    let suits = ["Hearts", "Diamonds", "Clubs", "Spades"];
+    let values = 2..=14;
+
+    let mut deck: Vec<Card> = suits
+        .iter()
+        .flat_map(|suit| values.clone().map(move |value| Card {
+            suit: suit.to_string(),
+            value,
+        }))
+        .collect();
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(randomness);
+    deck.shuffle(&mut rng);
+
+    for _ in 0..MAX_JOKERS {
+        if rng.gen_range(0..BPS_DENOMINATOR) < u64::from(joker_probability_bps) {
+            let position = rng.gen_range(0..=deck.len());
+            deck.insert(position, Card {
+                suit: JOKER_SUIT.to_string(),
+                value: JOKER_VALUE,
+            });
+        }
+    }
+
+    deck
+}
+
+/// Deck positions in the order the next bet deals them: the dealt cards,
+/// then the card showing, then the rest, shuffled by `draw_seed` if given.
+pub fn arrange_deck(
+    deck_len: usize,
+    dealt_mask: u64,
+    current: u8,
+    draw_seed: Option<[u8; 32]>,
+) -> Vec<usize> {
+    let current = usize::from(current);
+    let dealt = |position: usize| dealt_mask & 1 << position != 0;
+
+    let mut rest: Vec<usize> =
+        (0..deck_len).filter(|&position| position != current && !dealt(position)).collect();
+    if let Some(draw_seed) = draw_seed {
+        rest.shuffle(&mut rand::rngs::StdRng::from_seed(draw_seed));
+    }
+
+    (0..deck_len)
+        .filter(|&position| dealt(position))
+        .chain((current < deck_len).then_some(current))
+        .chain(rest)
+        .collect()
+}
+
+/// Seed the cards after the one showing are drawn from when a bet settles:
+/// the hash of the slot the bet was placed in, unknown to the bettor, bound
+/// to the player and how far into the deck they are.
+pub fn draw_seed(slot_hash: &[u8; 32], player: &Pubkey, cards_dealt: u8) -> [u8; 32] {
+    keccak::hashv(&[slot_hash, player.as_ref(), &[cards_dealt]]).to_bytes()
+}
+
+/// Looks `slot` up in the raw data of the `SlotHashes` sysvar: a length
+/// followed by `(slot, hash)` entries. `None` once the slot has aged out.
+pub fn slot_hash_at(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    const ENTRY_LEN: usize = 8 + 32;
+
+    let len = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    data.get(8..)?
+        .chunks_exact(ENTRY_LEN)
+        .take(usize::try_from(len).ok()?)
+        .find(|entry| entry[..8] == slot.to_le_bytes())
+        .map(|entry| entry[8..].try_into().unwrap())
+}
+
+/// The player's deck seed and deck arranged for checks on the card showing
+/// and on what is left, without drawing.
+fn deck_in_play(player: &Player, config: &CompetitionConfig) -> Result<(u64, Vec<Card>)> {
+    let seed = player.deck_seed().ok_or(ErrorCode::RandomnessNotReceived)?;
+    let deck = shuffle_deck(seed, config.joker_probability_bps);
+    let order = arrange_deck(deck.len(), player.dealt_mask, player.current_card, None);
+    Ok((seed, order.iter().map(|&position| deck[position].clone()).collect()))
+}
+
+/// Fails with `DeckCorrupt` if any card other than a joker appears twice.
+/// Checked on every bet in debug builds and with the `strict` feature.
+pub fn check_deck_unique(deck: &[Card]) -> Result<()> {
+    let duplicate = deck.iter().enumerate().any(|(i, card)| {
+        card.value != JOKER_VALUE
+            && deck[..i]
+                .iter()
+                .any(|earlier| earlier.value == card.value && earlier.suit == card.suit)
+    });
+    if duplicate {
+        return err!(ErrorCode::DeckCorrupt);
+    }
+    Ok(())
+}
+
+/// Deals the current card of `deck` and resolves the bets placed on it.
+///
+/// The main bet compares the current card with the next one. Color and
+/// parity side bets read the card chosen by `side_bet_card`, suit runs read
+/// the cards from the next one on, and streak bets are settled by the next
+/// main bet. A joker settles only a pending streak bet.
+pub fn resolve_bet(
+    player: &mut Player,
+    deck: &[Card],
+    bet_type: &BetType,
+    side_bet: Option<SideBetType>,
+    dynamic_odds: bool,
+    side_bet_card: SideBetCard,
+) -> Result<BetOutcome> {
+    let remaining = deck.get(player.cards_dealt as usize..).unwrap_or_default();
+    #[cfg(any(feature = "strict", debug_assertions))]
+    check_deck_unique(remaining)?;
+    let (current_card, upcoming) = remaining.split_first().ok_or(ErrorCode::GameOver)?;
+    let next_card = upcoming.first().ok_or(ErrorCode::GameOver)?;
+
+    // A joker doubles the multiplier whatever the bet, and is dealt together
+    // with the card after it.
+    if current_card.value == JOKER_VALUE {
+        player.cards_dealt += 2;
+        player.joker_count = player.joker_count.saturating_add(1);
+
+        return Ok(BetOutcome {
+            correct: true,
+            multiplier_gain: 2.0,
+            side_bet_result: settle_streak_bet(player, true),
+            side_bet_streak_milestone: None,
+            hot_card: None,
+        });
+    }
+
+    let mut multiplier_gain =
+        bet_multiplier_gain(deck, player.cards_dealt, *bet_type, dynamic_odds)?;
+    player.cards_dealt += 1;
+
+    let hot_card = player.hot_values.contains(&current_card.value).then_some(current_card.value);
+    if hot_card.is_some() {
+        multiplier_gain *= 2.0;
+    }
+
+    let outcome = match bet_type {
+        BetType::High => next_card.value > current_card.value,
+        BetType::Low => next_card.value < current_card.value,
+    };
+
+    let side_bet_target = match side_bet_card {
+        SideBetCard::Current => current_card,
+        SideBetCard::Next => next_card,
+    };
+    let side_bet_result = if let Some(bet) = side_bet.clone() {
+        match bet {
+            SideBetType::Color { red } => {
+                let is_red = side_bet_target.suit == "Hearts" || side_bet_target.suit == "Diamonds";
+                if red == is_red {
+                    Some(1)
+                } else {
+                    Some(-1)
+                }
+            }
+            SideBetType::Parity { even } => {
+                let is_even = side_bet_target.value % 2 == 0;
+                if even == is_even {
+                    Some(1)
+                } else {
+                    Some(-1)
+                }
+            }
+            SideBetType::SuitRun { length } => {
+                let payout = suit_run_payout(length).ok_or(ErrorCode::InvalidSideBet)?;
+                let run = upcoming
+                    .get(..length as usize)
+                    .ok_or(ErrorCode::InsufficientDeckForSideBet)?;
+
+                if run.iter().all(|card| card.suit == run[0].suit) {
+                    Some(payout)
+                } else {
+                    Some(-1)
+                }
+            }
+            // Settled by the next main bet, after any streak pending from
+            // this one.
+            SideBetType::HighLowStreak { .. } => None,
+        }
+    } else {
+        None
+    };
+
+    let side_bet_streak_milestone = side_bet_result.and_then(|result| {
+        player.side_bet_streak = if result > 0 { player.side_bet_streak.saturating_add(1) } else { 0 };
+        let milestone = player.side_bet_streak.checked_rem(SIDE_BET_STREAK_MILESTONE) == Some(0);
+        (player.side_bet_streak > 0 && milestone).then_some(player.side_bet_streak)
+    });
+
+    let streak_result = settle_streak_bet(player, outcome);
+    if let Some(SideBetType::HighLowStreak { correct }) = side_bet {
+        player.pending_streak_bet = Some(correct);
+    }
+    let side_bet_result = match (side_bet_result, streak_result) {
+        (Some(side), Some(streak)) => Some(side + streak),
+        (side, streak) => side.or(streak),
+    };
+
+    Ok(BetOutcome {
+        correct: outcome,
+        multiplier_gain,
+        side_bet_result,
+        side_bet_streak_milestone,
+        hot_card,
+    })
+}
+
+/// Settles a pending `HighLowStreak` side bet against whether the main bet
+/// it predicted was `correct`.
+fn settle_streak_bet(player: &mut Player, correct: bool) -> Option<i64> {
+    let predicted = player.pending_streak_bet.take()?;
+    Some(if predicted == correct { STREAK_SIDE_BET_PAYOUT } else { -1 })
+}
+
+/// Records a game score towards the current competition and returns the
+/// player's competition score: the sum of their best `rounds_per_competition`
+/// game scores.
+fn record_round_score(
+    player: &mut Player,
+    state: &State,
+    config: &CompetitionConfig,
+    game_score: u64,
+) -> Result<u64> {
+    if player.scores_competition_index != state.competition_index {
+        player.scores_competition_index = state.competition_index;
+        player.competition_scores.clear();
+    }
+
+    player.competition_scores.push(game_score);
+    top_rounds_total(&mut player.competition_scores, config.rounds_per_competition)
+}
+
+/// Keeps only the best `rounds` of `scores` and returns their sum.
+pub fn top_rounds_total(scores: &mut Vec<u64>, rounds: u8) -> Result<u64> {
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    scores.truncate(rounds.into());
+
+    scores
+        .iter()
+        .try_fold(0u64, |total, score| total.checked_add(*score))
+        .ok_or_else(|| overflow(ArithmeticOp::ScoreAdd))
+}
+
+/// Resizes a program account, topping up its rent from `payer` when it grows.
+fn realloc_with_rent<'info>(
+    target: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(target.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    target.realloc(new_len, false)?;
+    Ok(())
+}
+
+/// Decodes a `State` body stored with `LegacyLeaderboardEntry` entries.
+pub fn decode_legacy_state(mut data: &[u8]) -> Result<State> {
+    let legacy = LegacyState::deserialize(&mut data)
+        .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+
+    Ok(State {
+        admin: legacy.admin,
+        config: legacy.config,
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+        leaderboard_size: legacy.leaderboard_size,
+        leaderboard: legacy.leaderboard.into_iter().map(Into::into).collect(),
+        finalized: legacy.finalized,
+        finalized_timestamp: legacy.finalized_timestamp,
+        pool: legacy.pool,
+        accounts_closed: legacy.accounts_closed,
+        participants: legacy.participants,
+        competition_index: legacy.competition_index,
+        game_tags: Vec::new(),
+        leaderboard_snapshot_epoch: 0,
+        special_prize: None,
+        house_claimed: false,
+        entries_closed: false,
+        leaderboard_root: None,
+        leaderboard_root_entries: 0,
+        merkle_claimed: 0,
+        prizes_held: false,
+        dispute_window_bypassed: false,
+        prize_pool: legacy.pool,
+        jackpot: 0,
+    })
+}
+
+/// Side-bet payout for predicting that the next `length` cards share a suit.
+/// Fails unless `side_bet` can be resolved with `upcoming` cards left after
+/// the current one.
+pub fn validate_side_bet(side_bet: &SideBetType, upcoming: usize) -> Result<()> {
+    if let SideBetType::SuitRun { length } = *side_bet {
+        if suit_run_payout(length).is_none() {
+            return err!(ErrorCode::InvalidSideBet);
+        }
+        if upcoming < length as usize {
+            return err!(ErrorCode::InsufficientDeckForSideBet);
+        }
+    }
+    Ok(())
+}
+
+fn suit_run_payout(length: u8) -> Option<i64> {
+    match length {
+        2 => Some(3),
+        3 => Some(12),
+        4 => Some(45),
+        _ => None,
+    }
+}
+
+/// Odds of the next High and Low bet, counted over the cards after the current
+/// one. A joker wins whatever the bet.
+pub fn calculate_bet_odds(deck: &[Card], cards_dealt: u8) -> Result<BetOdds> {
+    let remaining = deck.get(cards_dealt as usize..).unwrap_or_default();
+    let (current_card, upcoming) = remaining.split_first().ok_or(ErrorCode::GameOver)?;
+    let denominator = upcoming.len() as u64;
+
+    let (high_numerator, low_numerator, tie_numerator) = if current_card.value == JOKER_VALUE {
+        (denominator, denominator, 0)
+    } else {
+        let count = |matches: fn(u8, u8) -> bool| {
+            upcoming
+                .iter()
+                .filter(|card| matches(card.value, current_card.value))
+                .count() as u64
+        };
+        (
+            count(|next, current| next > current),
+            count(|next, current| next < current),
+            count(|next, current| next == current),
+        )
+    };
+
+    Ok(BetOdds {
+        high_numerator,
+        low_numerator,
+        tie_numerator,
+        denominator,
+        current_card_value: current_card.value,
+        current_card_suit: current_card.suit.clone(),
+    })
+}
+
+/// Multiplier gain of a correct bet on the card at `cards_dealt`. With
+/// `dynamic_odds` it is the fair payout for the cards left in the deck, so
+/// bets made unlikely by the cards already dealt pay more; a bet that cannot
+/// win falls back to the static table.
+pub fn bet_multiplier_gain(
+    deck: &[Card],
+    cards_dealt: u8,
+    bet_type: BetType,
+    dynamic_odds: bool,
+) -> Result<f64> {
+    let odds = calculate_bet_odds(deck, cards_dealt)?;
+    let static_gain = calculate_multiplier_gain(odds.current_card_value, bet_type);
+    if !dynamic_odds {
+        return Ok(static_gain);
+    }
+
+    let winning = match bet_type {
+        BetType::High => odds.high_numerator,
+        BetType::Low => odds.low_numerator,
+    };
+    if winning == 0 {
+        return Ok(static_gain);
+    }
+    Ok(odds.denominator as f64 / winning as f64)
+}
+
+/// Randomness metrics of a deck, jokers excluded:
+/// - `chi_squared_bps`: chi-squared of the suit counts against a uniform
+///   split, summed over the deck's four quarters, in basis points. Grouped
+///   suits score high.
+/// - `runs_up` / `runs_down`: maximal strictly ascending / descending value
+///   runs.
+/// - `entropy_estimate_bps`: Shannon entropy, in bits and basis points, of the
+///   differences between consecutive values. Ordered decks score low.
+pub fn calculate_deck_statistics(deck: &[Card]) -> DeckStatistics {
+    let cards: Vec<&Card> = deck.iter().filter(|card| card.value != JOKER_VALUE).collect();
+
+    let quarter = cards.len() / 4;
+    let mut chi_squared_bps = 0;
+    if quarter > 0 {
+        for block in cards.chunks_exact(quarter).take(4) {
+            for suit in ["Hearts", "Diamonds", "Clubs", "Spades"] {
+                let observed = block.iter().filter(|card| card.suit == suit).count() as u64;
+                let deviation = (4 * observed).abs_diff(quarter as u64);
+                chi_squared_bps += deviation * deviation * BPS_DENOMINATOR / (4 * quarter as u64);
+            }
+        }
+    }
+
+    let (mut runs_up, mut runs_down) = (0, 0);
+    let mut previous_step = std::cmp::Ordering::Equal;
+    let mut steps = Vec::with_capacity(cards.len());
+    for pair in cards.windows(2) {
+        let step = pair[1].value.cmp(&pair[0].value);
+        if step != previous_step {
+            match step {
+                std::cmp::Ordering::Greater => runs_up += 1,
+                std::cmp::Ordering::Less => runs_down += 1,
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+        previous_step = step;
+        steps.push(i16::from(pair[1].value) - i16::from(pair[0].value));
+    }
+
+    steps.sort_unstable();
+    let total = steps.len() as f64;
+    let mut entropy = 0.0;
+    let mut start = 0;
+    while start < steps.len() {
+        let count = steps[start..].iter().take_while(|step| **step == steps[start]).count();
+        let p = count as f64 / total;
+        entropy -= p * p.log2();
+        start += count;
+    }
+
+    DeckStatistics {
+        chi_squared_bps,
+        runs_up,
+        runs_down,
+        entropy_estimate_bps: (entropy * BPS_DENOMINATOR as f64) as u64,
+    }
+}
+
+fn calculate_multiplier_gain(current_card_value: u8, bet_type: BetType) -> f64 {
+    match current_card_value {
+        2 => match bet_type {
+            BetType::High => 1.2,
+            BetType::Low => 4.0,
+        },
+        3 => match bet_type {
+            BetType::High => 1.25,
+            BetType::Low => 3.5,
+        },
+        4 => match bet_type {
+            BetType::High => 1.3,
+            BetType::Low => 3.0,
+        },
+        5 => match bet_type {
+            BetType::High => 1.35,
+            BetType::Low => 2.5,
+        },
+        6 => match bet_type {
+            BetType::High => 1.4,
+            BetType::Low => 2.0,
+        },
+        7 => match bet_type {
+            BetType::High => 1.5,
+            BetType::Low => 1.7,
+        },
+        8 => match bet_type {
+            BetType::High => 1.6,
+            BetType::Low => 1.6,
+        },
+        9 => match bet_type {
+            BetType::High => 1.8,
+            BetType::Low => 1.6,
+        },
+        10 => match bet_type {
+            BetType::High => 2.0,
+            BetType::Low => 1.5,
+        },
+        11 => match bet_type {
+            BetType::High => 2.5,
+            BetType::Low => 1.4,
+        },
+        12 => match bet_type {
+            BetType::High => 3.0,
+            BetType::Low => 1.3,
+        },
+        13 => match bet_type {
+            BetType::High => 4.0,
+            BetType::Low => 1.2,
+        },
+        14 => match bet_type {
+            BetType::High => 1.2,
+            BetType::Low => 1.2,
+        },
+        _ => 1.0,
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = state_space(DEFAULT_LEADERBOARD_SIZE))]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+        seeds = [b"config", state.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomness<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    pub randomness_program: AccountInfo<'info>,
+    #[account(mut)]
+    pub randomness_account: AccountInfo<'info>,
+    #[account(mut, address = state.admin @ ErrorCode::Unauthorized)]
+    pub admin: Signer<'info>,
+    #[account(constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePlayer<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = player_space(),
+        seeds = [b"player", authority.key().as_ref()],
+        bump
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitDeckSeed<'info> {
+    #[account(mut, constraint = player.authority == authority.key() @ ErrorCode::Unauthorized)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReceiveRandomness<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut, constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    /// Delivers the VRF output; only the admin of the player's competition may.
+    #[account(address = state.admin @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealDeckSeed<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InjectTestRandomness<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut, constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(address = state.admin @ ErrorCode::Unauthorized)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartGame<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut, constraint = player.authority == authority.key() @ ErrorCode::Unauthorized)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    /// Pays the entry fee into the pool.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The player's `BlacklistEntry` address; must not exist.
+    #[account(seeds = [b"blacklist", state.key().as_ref(), player.authority.as_ref()], bump)]
+    pub blacklist_entry: AccountInfo<'info>,
+    /// The competition's `BanList` address; it need not be initialized.
+    #[account(seeds = [b"banlist", state.key().as_ref()], bump)]
+    pub ban_list: AccountInfo<'info>,
+    pub analytics_program: Option<AccountInfo<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBet<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(signer)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub player_wallet: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleBet<'info> {
+    #[account(mut)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    /// The config the pending bet was placed under.
+    #[account(
+        constraint = player.pending_bet.as_ref().is_some_and(|bet| bet.config == config.key())
+            @ ErrorCode::NoPendingBet
+    )]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+    /// Instructions sysvar, used to find the compute budget request that
+    /// must precede a dynamic-odds settlement.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitScore<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub analytics_program: Option<AccountInfo<'info>>,
+    /// The authority's group, to pool this game's score into.
+    #[account(mut, constraint = group.state == state.key() @ ErrorCode::NotGroupMember)]
+    pub group: Option<Account<'info, VersionedAccount<PlayerGroup>>>,
+    #[account(mut, seeds = [b"group_leaderboard", state.key().as_ref()], bump)]
+    pub group_leaderboard: Option<Account<'info, VersionedAccount<GroupLeaderboard>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(group_id: u64)]
+pub struct CreateGroup<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init,
+        payer = creator,
+        space = VERSIONED_HEADER_LEN + PlayerGroup::LEN,
+        seeds = [b"group", group_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub group: Account<'info, VersionedAccount<PlayerGroup>>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = VERSIONED_HEADER_LEN + GroupLeaderboard::LEN,
+        seeds = [b"group_leaderboard", state.key().as_ref()],
+        bump
+    )]
+    pub group_leaderboard: Account<'info, VersionedAccount<GroupLeaderboard>>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundPositionBetVault<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + PositionBetVault::LEN,
+        seeds = [b"position_bet_vault", state.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, VersionedAccount<PositionBetVault>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundGuaranteeReserve<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + GuaranteeReserve::LEN,
+        seeds = [b"guarantee_reserve", state.key().as_ref()],
+        bump
+    )]
+    pub guarantee_reserve: Account<'info, VersionedAccount<GuaranteeReserve>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundGroupPrizes<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, seeds = [b"group_leaderboard", state.key().as_ref()], bump)]
+    pub group_leaderboard: Account<'info, VersionedAccount<GroupLeaderboard>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeGroupLeaderboard<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, seeds = [b"group_leaderboard", state.key().as_ref()], bump)]
+    pub group_leaderboard: Account<'info, VersionedAccount<GroupLeaderboard>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGroupPrize<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, seeds = [b"group_leaderboard", state.key().as_ref()], bump)]
+    pub group_leaderboard: Account<'info, VersionedAccount<GroupLeaderboard>>,
+    #[account(mut, constraint = group.state == state.key() @ ErrorCode::NotGroupMember)]
+    pub group: Account<'info, VersionedAccount<PlayerGroup>>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Cashout<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimJackpot<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundFailedGame<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(
+        mut,
+        constraint = player.authority == authority.key() @ ErrorCode::Unauthorized,
+        constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition
+    )]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitScoresBatch<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DispatchGameOverHook<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut, constraint = player.state == state.key() @ ErrorCode::PlayerNotInCompetition)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    pub hook_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct SetNickname<'info> {
+    #[account(mut, constraint = player.authority == authority.key() @ ErrorCode::Unauthorized)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(
+        init,
+        payer = authority,
+        space = VERSIONED_HEADER_LEN + NicknameRecord::LEN,
+        seeds = [b"nickname", name.as_bytes()],
+        bump
+    )]
+    pub nickname: Account<'info, VersionedAccount<NicknameRecord>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"nickname", player.nickname.as_bytes()],
+        bump
+    )]
+    pub old_nickname: Option<Account<'info, VersionedAccount<NicknameRecord>>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HealthCheck<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+}
+
+#[derive(Accounts)]
+pub struct AnchorLeaderboardData<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + LeaderboardAnchor::LEN,
+        seeds = [b"anchor", state.key().as_ref(), &state.competition_index.to_le_bytes()],
+        bump
+    )]
+    pub leaderboard_anchor: Account<'info, VersionedAccount<LeaderboardAnchor>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyLeaderboardData<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        seeds = [b"anchor", state.key().as_ref(), &state.competition_index.to_le_bytes()],
+        bump
+    )]
+    pub leaderboard_anchor: Account<'info, VersionedAccount<LeaderboardAnchor>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateScoreFormula<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRulesHash<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBetWindowTable<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyRulesHash<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEventMask<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloneConfig<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    pub source_config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct ExemptFromCooldown<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = player)]
+    pub player_stats: Account<'info, VersionedAccount<Player>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBanList<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + BanList::LEN,
+        seeds = [b"banlist", state.key().as_ref()],
+        bump
+    )]
+    pub ban_list: Account<'info, VersionedAccount<BanList>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBanList<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, seeds = [b"banlist", state.key().as_ref()], bump)]
+    pub ban_list: Account<'info, VersionedAccount<BanList>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct Blacklist<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + BlacklistEntry::LEN,
+        seeds = [b"blacklist", state.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, VersionedAccount<BlacklistEntry>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct Unblacklist<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"blacklist", state.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, VersionedAccount<BlacklistEntry>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddGameTag<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(size: u8)]
+pub struct SetLeaderboardSize<'info> {
+    #[account(
+        mut,
+        realloc = state_space(size),
+        realloc::payer = admin,
+        realloc::zero = false
+    )]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEntries<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleasePrizes<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminOverrideDisputeWindow<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdjustScore<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FilterByTag<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+}
+
+#[derive(Accounts)]
+pub struct SetSpectatorEvents<'info> {
+    #[account(mut, constraint = player.authority == authority.key() @ ErrorCode::Unauthorized)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubscribeSpectator<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(
+        init,
+        payer = spectator,
+        space = VERSIONED_HEADER_LEN + SpectatorSubscription::LEN,
+        seeds = [b"spectate", player.key().as_ref(), spectator.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, VersionedAccount<SpectatorSubscription>>,
+    #[account(mut)]
+    pub spectator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IssueCertificate<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(constraint = player.authority == authority.key() @ ErrorCode::Unauthorized)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(
+        init,
+        payer = authority,
+        space = VERSIONED_HEADER_LEN + ParticipationCertificate::LEN,
+        seeds = [b"cert", state.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub certificate: Account<'info, VersionedAccount<ParticipationCertificate>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnsubscribeSpectator<'info> {
+    #[account(mut)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+    #[account(
+        mut,
+        close = spectator,
+        seeds = [b"spectate", player.key().as_ref(), spectator.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, VersionedAccount<SpectatorSubscription>>,
+    #[account(mut)]
+    pub spectator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetLeaderboardPage<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+}
+
+#[derive(Accounts)]
+pub struct GetBetOdds<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    pub player: Account<'info, VersionedAccount<Player>>,
+}
+
+#[derive(Accounts)]
+pub struct ComputeDeckStatistics<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub player: Account<'info, VersionedAccount<Player>>,
+}
+
+#[derive(Accounts)]
+pub struct GetPlayerSummary<'info> {
+    pub player: Account<'info, VersionedAccount<Player>>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeLeaderboard<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// Required when the competition has a special prize.
+    #[account(
+        init,
+        payer = admin,
+        space = VERSIONED_HEADER_LEN + SpecialPrizeEscrow::LEN,
+        seeds = [b"special_prize", state.key().as_ref()],
+        bump
+    )]
+    pub special_prize_escrow: Option<Account<'info, VersionedAccount<SpecialPrizeEscrow>>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct SetSpecialPrize<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSpecialPrize<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        mut,
+        close = admin,
+        has_one = recipient @ ErrorCode::Unauthorized,
+        seeds = [b"special_prize", state.key().as_ref()],
+        bump
+    )]
+    pub special_prize_escrow: Account<'info, VersionedAccount<SpecialPrizeEscrow>>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(mut, address = state.admin @ ErrorCode::Unauthorized)]
+    pub admin: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BulkClosePlayers<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeAccountLayout<'info> {
+    #[account(mut, owner = crate::ID)]
+    pub target: AccountInfo<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::PixelCardGame>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PredictMyPosition<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        init,
+        payer = player,
+        space = VERSIONED_HEADER_LEN + PositionBetEscrow::LEN,
+        seeds = [b"position_bet", state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, VersionedAccount<PositionBetEscrow>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPositionBet<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"position_bet", state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, VersionedAccount<PositionBetEscrow>>,
+    #[account(mut, seeds = [b"position_bet_vault", state.key().as_ref()], bump)]
+    pub vault: Account<'info, VersionedAccount<PositionBetVault>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendPrizeClaimWindow<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(mut, address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(signer)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimUndersizedPool<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetTimeRemaining<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct GetGameStatus<'info> {
+    pub player: Account<'info, VersionedAccount<Player>>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewPrize<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLeaderboardRoot<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrizeMerkle<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = VERSIONED_HEADER_LEN + ClaimEscrow::LEN,
+        seeds = [b"claim_escrow", state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub claim_escrow: Account<'info, VersionedAccount<ClaimEscrow>>,
+    #[account(mut, address = state.admin @ ErrorCode::Unauthorized)]
+    pub admin: SystemAccount<'info>,
+    pub analytics_program: Option<AccountInfo<'info>>,
+    /// Only needed when the prize falls below the position's guarantee.
+    #[account(mut, seeds = [b"guarantee_reserve", state.key().as_ref()], bump)]
+    pub guarantee_reserve: Option<Account<'info, VersionedAccount<GuaranteeReserve>>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = VERSIONED_HEADER_LEN + ClaimEscrow::LEN,
+        seeds = [b"claim_escrow", state.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub claim_escrow: Account<'info, VersionedAccount<ClaimEscrow>>,
+    #[account(mut, address = state.admin @ ErrorCode::Unauthorized)]
+    pub admin: SystemAccount<'info>,
+    #[account(mut, constraint = player_stats.authority == player.key() @ ErrorCode::Unauthorized)]
+    pub player_stats: Account<'info, VersionedAccount<Player>>,
+    pub analytics_program: Option<AccountInfo<'info>>,
+    /// Only needed when the prize falls below the position's guarantee.
+    #[account(mut, seeds = [b"guarantee_reserve", state.key().as_ref()], bump)]
+    pub guarantee_reserve: Option<Account<'info, VersionedAccount<GuaranteeReserve>>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawClaim<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"claim_escrow", state.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = claim_escrow.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub claim_escrow: Account<'info, VersionedAccount<ClaimEscrow>>,
+    /// Must be the owner itself or the payout wallet they registered.
+    #[account(mut)]
+    pub wallet: SystemAccount<'info>,
+    #[account(seeds = [b"payout", owner.key().as_ref()], bump)]
+    pub payout_wallet: Option<Account<'info, VersionedAccount<PayoutWallet>>>,
+}
+
+#[derive(Accounts)]
+pub struct MintWinnerNft<'info> {
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(
+        mut,
+        mint::decimals = 0,
+        mint::authority = winner,
+        constraint = mint.supply == 0 @ ErrorCode::InvalidNftMint
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = winner)]
+    pub winner_token_account: Account<'info, TokenAccount>,
+    /// Created by the Token Metadata program.
+    #[account(
+        mut,
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.key().as_ref()],
+        seeds::program = TOKEN_METADATA_PROGRAM_ID,
+        bump
+    )]
+    pub metadata: AccountInfo<'info>,
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimHousePrize<'info> {
+    #[account(mut)]
+    pub state: Account<'info, VersionedAccount<State>>,
+    #[account(address = state.config)]
+    pub config: Account<'info, VersionedAccount<CompetitionConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAll<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(mut, constraint = player_stats.authority == player.key() @ ErrorCode::Unauthorized)]
+    pub player_stats: Account<'info, VersionedAccount<Player>>,
+    pub analytics_program: Option<AccountInfo<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutWallet<'info> {
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = VERSIONED_HEADER_LEN + PayoutWallet::LEN,
+        seeds = [b"payout", player.key().as_ref()],
+        bump
+    )]
+    pub payout_wallet: Account<'info, VersionedAccount<PayoutWallet>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(filter: EventFilter)]
+pub struct RegisterEventFilter<'info> {
+    #[account(
+        init_if_needed,
+        payer = subscriber,
+        space = VERSIONED_HEADER_LEN + FilterRegistration::LEN,
+        seeds = [b"filter", subscriber.key().as_ref(), &[filter.event_types]],
+        bump
+    )]
+    pub filter_registration: Account<'info, VersionedAccount<FilterRegistration>>,
+    #[account(mut)]
+    pub subscriber: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Program account prefixed with `LAYOUT_VERSION`. The version byte is checked
+/// right after the discriminator, before the account body is deserialized, so
+/// accounts on an outdated layout fail with `AccountLayoutVersionMismatch`
+/// instead of being misread.
+#[derive(Clone)]
+pub struct VersionedAccount<T>(T);
+
+impl<T> Deref for VersionedAccount<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for VersionedAccount<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Discriminator> Discriminator for VersionedAccount<T> {
+    const DISCRIMINATOR: [u8; 8] = T::DISCRIMINATOR;
+}
+
+impl<T: Owner> Owner for VersionedAccount<T> {
+    fn owner() -> Pubkey {
+        T::owner()
+    }
+}
+
+impl<T: AnchorSerialize + Discriminator> AccountSerialize for VersionedAccount<T> {
+    fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&T::DISCRIMINATOR)
+            .and_then(|_| writer.write_all(&[LAYOUT_VERSION]))
+            .and_then(|_| self.0.serialize(writer))
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize.into())
+    }
+}
+
+impl<T: AnchorDeserialize + Discriminator> AccountDeserialize for VersionedAccount<T> {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < VERSIONED_HEADER_LEN {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if buf[..8] != T::DISCRIMINATOR {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        if buf[8] != LAYOUT_VERSION {
+            return err!(ErrorCode::AccountLayoutVersionMismatch);
+        }
+
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data = buf
+            .get(VERSIONED_HEADER_LEN..)
+            .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+
+        T::deserialize(&mut data)
+            .map(VersionedAccount)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+/// Runtime data of one competition. Its rules live in the `CompetitionConfig`
+/// referenced by `config`.
+#[account]
+pub struct State {
+    pub admin: Pubkey,
+    pub config: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub leaderboard_size: u8,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub finalized: bool,
+    pub finalized_timestamp: i64,
+    pub pool: u64,
+    pub accounts_closed: u32,
+    pub participants: u32,
+    pub competition_index: u32,
+    pub game_tags: Vec<GameTag>,
+    pub leaderboard_snapshot_epoch: u32,
+    /// Discretionary prize carved out of the pool at finalization.
+    pub special_prize: Option<SpecialPrize>,
+    pub house_claimed: bool,
+    /// Set by `close_entries`: no new games, but games already running can
+    /// still be played out.
+    pub entries_closed: bool,
+    /// Merkle root of the full leaderboard computed off-chain, for
+    /// competitions too large for `leaderboard`. Once set, prizes are claimed
+    /// through `claim_prize_merkle` only.
+    pub leaderboard_root: Option<[u8; 32]>,
+    pub leaderboard_root_entries: u32,
+    /// Bit `n` is set once position `n` was claimed with a Merkle proof.
+    pub merkle_claimed: u8,
+    /// Set when the collusion check flagged shared payout wallets; prizes
+    /// can't be claimed until the admin calls `release_prizes`.
+    pub prizes_held: bool,
+    /// Set by `admin_override_dispute_window` to allow claims before the
+    /// dispute window ends.
+    pub dispute_window_bypassed: bool,
+    /// `pool` as it stood at finalization, which prizes are split from.
+    pub prize_pool: u64,
+    /// Lamports set aside from entry fees for `claim_jackpot`, held apart
+    /// from `pool` and kept until someone wins them.
+    pub jackpot: u64,
+}
+
+impl State {
+    /// Size without the leaderboard entries themselves.
+    pub const BASE_LEN: usize = 32 + 32 + 8 + 8 + 1
+        + 4
+        + 1 + 8 + 8 + 4 + 4 + 4
+        + 4 + MAX_GAME_TAGS
+        + 4
+        + 1 + SpecialPrize::LEN
+        + 1 + 1
+        + 33 + 4 + 1
+        + 1 + 1
+        + 8 + 8;
+
+    /// Checks that `player` finished at `position` with `score` in the
+    /// Merkle leaderboard and marks the position claimed.
+    pub fn claim_merkle_position(
+        &mut self,
+        position: LeaderboardPosition,
+        player: &Pubkey,
+        score: u64,
+        proof: &[[u8; 32]],
+    ) -> Result<()> {
+        let root = self.leaderboard_root.ok_or(ErrorCode::InvalidMerkleProof)?;
+        let leaf = leaderboard_leaf(player, score, position.get());
+        if !verify_merkle_proof(root, leaf, proof) {
+            return err!(ErrorCode::InvalidMerkleProof);
+        }
+
+        let bit = 1u8.checked_shl(position.get().into()).ok_or(ErrorCode::NotOnLeaderboard)?;
+        if self.merkle_claimed & bit != 0 {
+            return err!(ErrorCode::PrizeAlreadyClaimed);
+        }
+        self.merkle_claimed |= bit;
+        Ok(())
+    }
+
+    pub fn check_entries_open(&self) -> Result<()> {
+        if self.entries_closed {
+            return err!(ErrorCode::EntriesClosed);
+        }
+        Ok(())
+    }
+
+    /// Adds a category tag, keeping at most `MAX_GAME_TAGS` distinct tags.
+    pub fn add_tag(&mut self, tag: GameTag) -> Result<()> {
+        if self.game_tags.contains(&tag) {
+            return err!(ErrorCode::DuplicateGameTag);
+        }
+        if self.game_tags.len() >= MAX_GAME_TAGS {
+            return err!(ErrorCode::TooManyTags);
+        }
+        self.game_tags.push(tag);
+        Ok(())
+    }
+
+    /// Sets the number of leaderboard places, between 1 and
+    /// `MAX_LEADERBOARD_SIZE`. Fails with `LeaderboardLocked` once the
+    /// competition holds a score or any pool.
+    pub fn resize_leaderboard(&mut self, size: u8) -> Result<()> {
+        if size == 0 || size as usize > MAX_LEADERBOARD_SIZE {
+            return err!(ErrorCode::InvalidLeaderboardSize);
+        }
+        if self.pool > 0 || !self.leaderboard.is_empty() {
+            return err!(ErrorCode::LeaderboardLocked);
+        }
+        self.leaderboard_size = size;
+        Ok(())
+    }
+
+    /// Applies `delta` to `player`'s score, clamping at zero, and re-ranks.
+    pub fn adjust_entry_score(&mut self, player: &Pubkey, delta: i64) -> Result<ScoreAdjustment> {
+        let old_position = self
+            .leaderboard
+            .iter()
+            .position(|entry| entry.player == *player)
+            .ok_or(ErrorCode::NotOnLeaderboard)?;
+
+        let entry = &mut self.leaderboard[old_position];
+        let old_score = entry.score;
+        entry.score = if delta < 0 {
+            old_score.saturating_sub(delta.unsigned_abs())
+        } else {
+            old_score.checked_add(delta as u64).ok_or_else(|| overflow(ArithmeticOp::ScoreAdd))?
+        };
+        let new_score = entry.score;
+
+        self.rank_leaderboard();
+        let new_position = self
+            .leaderboard
+            .iter()
+            .position(|entry| entry.player == *player)
+            .ok_or(ErrorCode::NotOnLeaderboard)?;
+
+        Ok(ScoreAdjustment {
+            old_score,
+            new_score,
+            old_position: old_position as u8,
+            new_position: new_position as u8,
+        })
+    }
+
+    /// Amount prizes are split from: the live pool until finalization, then
+    /// the pool as it stood at finalization.
+    pub fn prize_base(&self) -> u64 {
+        if self.finalized {
+            self.prize_pool
+        } else {
+            self.pool
+        }
+    }
+
+    /// Takes the special prize, if any, out of the pool ahead of the
+    /// percentage split and returns it.
+    pub fn reserve_special_prize(&mut self) -> Result<Option<SpecialPrize>> {
+        let Some(prize) = self.special_prize.clone() else {
+            return Ok(None);
+        };
+        self.pool = self
+            .pool
+            .checked_sub(prize.amount)
+            .ok_or(ErrorCode::SpecialPrizeExceedsPool)?;
+        Ok(Some(prize))
+    }
+
+    /// Orders the leaderboard by score, then earliest finish, then side-bet
+    /// score, and keeps the top `leaderboard_size` entries. Bumps the
+    /// snapshot epoch so paginating clients notice the change.
+    pub fn rank_leaderboard(&mut self) {
+        self.leaderboard.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.finished_at.cmp(&b.finished_at))
+                .then_with(|| b.side_bet_score.cmp(&a.side_bet_score))
+        });
+        self.leaderboard.truncate(self.leaderboard_size.into());
+        self.leaderboard_snapshot_epoch = self.leaderboard_snapshot_epoch.wrapping_add(1);
+    }
+
+    /// Entries on page `page` of `page_size`, as long as the leaderboard is
+    /// still at `expected_epoch`. Pages past the end are empty.
+    pub fn leaderboard_page(
+        &self,
+        page: u8,
+        page_size: u8,
+        expected_epoch: u32,
+    ) -> Result<&[LeaderboardEntry]> {
+        if page_size == 0 {
+            return err!(ErrorCode::InvalidPageSize);
+        }
+        if self.leaderboard_snapshot_epoch != expected_epoch {
+            return err!(ErrorCode::LeaderboardModified);
+        }
+
+        let start = (usize::from(page) * usize::from(page_size)).min(self.leaderboard.len());
+        let end = (start + usize::from(page_size)).min(self.leaderboard.len());
+        Ok(&self.leaderboard[start..end])
+    }
+
+    /// Adds `entry`, replacing the player's previous entry so the leaderboard
+    /// holds one competition score per player.
+    pub fn upsert_entry(&mut self, entry: LeaderboardEntry) {
+        self.leaderboard.retain(|existing| existing.player != entry.player);
+        self.leaderboard.push(entry);
+    }
+}
+
+/// Settings `initialize` stores in a competition's `CompetitionConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompetitionConfigArgs {
+    pub entry_fee: u64,
+    pub max_side_bets_per_game: u8,
+    pub randomness_source: RandomnessSource,
+    pub hook_program: Option<Pubkey>,
+    pub peak_weight: u16,
+    pub deck_completion_bonus: u32,
+    pub withdrawal_fee_bps: u16,
+    pub joker_probability_bps: u16,
+    pub max_bets_per_game: u8,
+    pub post_win_cooldown_competitions: u8,
+    pub score_formula: ScoreFormula,
+    pub starting_multiplier_bps: u64,
+    pub max_bet_amount: u64,
+    pub bet_timeout_policy: BetTimeoutPolicy,
+    pub prize_claim_window_hours: u16,
+    pub min_pool_for_payout: u64,
+    pub rounds_per_competition: u8,
+    pub analytics_program: Option<Pubkey>,
+    pub max_pool_size: u64,
+    pub dynamic_odds: bool,
+    pub min_bet_delay: u32,
+    pub max_spectators_per_game: u8,
+    /// Deepest `deck_offset` a game may start from; zero allows only
+    /// standard games.
+    pub max_deck_offset: u8,
+    pub hard_mode_bonus_per_card: u64,
+    /// Defaults to `DEFAULT_SIDE_BET_STREAK_BONUS` when `None`.
+    pub side_bet_streak_bonus: Option<u8>,
+    pub prize_schedule: PrizeSchedule,
+    /// keccak256 of the canonical off-chain rules document.
+    pub rules_hash: [u8; 32],
+    /// Whether a failing game-over hook fails `dispatch_game_over_hook`;
+    /// otherwise the failure is reported through `GameOverHookFailed`.
+    pub hook_required: bool,
+    pub bet_window_table: BetWindowTable,
+    /// Winner NFTs point at `{base}{position}.json`.
+    pub winner_nft_metadata_uri_base: String,
+    pub side_bet_card: SideBetCard,
+    /// Most distinct players the competition admits; zero means no cap.
+    pub max_entries: u32,
+    /// Bets a player must have placed to be issued a participation certificate.
+    pub min_bets_for_certificate: u8,
+    /// Least net prize each position receives from `claim_prize`, topped up
+    /// from the `GuaranteeReserve` while it lasts.
+    pub minimum_prize_guarantee: [u64; MAX_LEADERBOARD_SIZE],
+    /// Multiplier band, in `MULTIPLIER_SCALE` units, within which side bets
+    /// are taken; a zero maximum means no upper bound.
+    pub side_bet_min_multiplier_bps: u64,
+    pub side_bet_max_multiplier_bps: u64,
+    /// When set, `finalize_leaderboard` holds prizes for admin review if
+    /// several leaderboard entries pay out to the same wallet.
+    pub collusion_check: bool,
+    /// Hours after finalization during which prizes can't be claimed, so
+    /// disputes can be raised first.
+    pub dispute_window_hours: u8,
+    /// Share of each entry fee, in basis points, that goes to the jackpot
+    /// instead of the pool.
+    pub jackpot_contribution_bps: u16,
+    /// Final multiplier, in `MULTIPLIER_SCALE` units, a game must end above
+    /// for its player to claim the jackpot; zero means it can't be won.
+    pub jackpot_threshold_bps: u64,
+    /// Seconds after `start_game` a game still waiting for its randomness
+    /// can be refunded with `refund_failed_game`; zero means never.
+    pub randomness_timeout_seconds: u32,
+}
+
+impl CompetitionConfigArgs {
+    /// Fails with the error of the first setting out of its allowed range.
+    pub fn validate(&self) -> Result<()> {
+        if self.entry_fee == 0 {
+            return err!(ErrorCode::InvalidEntryFee);
+        }
+        if u64::from(self.peak_weight) > BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidPeakWeight);
+        }
+        if u64::from(self.withdrawal_fee_bps) > BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidWithdrawalFee);
+        }
+        if u64::from(self.joker_probability_bps) > BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidJokerProbability);
+        }
+        if u64::from(self.jackpot_contribution_bps) > BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidJackpotContribution);
+        }
+        self.score_formula.validate()?;
+        self.prize_schedule.validate()?;
+        self.bet_window_table.validate()?;
+        if self.starting_multiplier_bps < BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidStartingMultiplier);
+        }
+        if !(MIN_PRIZE_CLAIM_WINDOW_HOURS..=MAX_PRIZE_CLAIM_WINDOW_HOURS)
+            .contains(&self.prize_claim_window_hours)
+        {
+            return err!(ErrorCode::InvalidPrizeClaimWindow);
+        }
+        if self.dispute_window_hours > MAX_DISPUTE_WINDOW_HOURS {
+            return err!(ErrorCode::InvalidDisputeWindow);
+        }
+        if !(1..=MAX_ROUNDS_PER_COMPETITION).contains(&self.rounds_per_competition) {
+            return err!(ErrorCode::InvalidRoundsPerCompetition);
+        }
+        if self.max_deck_offset > MAX_DECK_OFFSET {
+            return err!(ErrorCode::InvalidDeckOffset);
+        }
+        if self.winner_nft_metadata_uri_base.len() > MAX_NFT_URI_BASE_LEN {
+            return err!(ErrorCode::NftUriBaseTooLong);
+        }
+        Ok(())
+    }
+}
+
+/// Rules of a competition, kept apart from `State` so the admin can change or
+/// copy them without touching runtime data.
+#[account]
+pub struct CompetitionConfig {
+    pub entry_fee: u64,
+    pub max_side_bets_per_game: u8,
+    pub randomness_source: RandomnessSource,
+    pub hook_program: Option<Pubkey>,
+    pub peak_weight: u16,
+    pub deck_completion_bonus: u32,
+    pub withdrawal_fee_bps: u16,
+    pub event_emission_mask: u8,
+    pub joker_probability_bps: u16,
+    pub max_bets_per_game: u8,
+    pub post_win_cooldown_competitions: u8,
+    pub score_formula: ScoreFormula,
+    pub starting_multiplier_bps: u64,
+    pub max_bet_amount: u64,
+    pub bet_timeout_policy: BetTimeoutPolicy,
+    pub prize_claim_window_hours: u16,
+    pub min_pool_for_payout: u64,
+    pub rounds_per_competition: u8,
+    pub analytics_program: Option<Pubkey>,
+    pub max_pool_size: u64,
+    pub dynamic_odds: bool,
+    pub min_bet_delay: u32,
+    pub max_spectators_per_game: u8,
+    /// Deepest `deck_offset` a game may start from; zero allows only
+    /// standard games.
+    pub max_deck_offset: u8,
+    pub hard_mode_bonus_per_card: u64,
+    /// Side-bet score added at each `SIDE_BET_STREAK_MILESTONE` streak.
+    pub side_bet_streak_bonus: u8,
+    pub prize_schedule: PrizeSchedule,
+    /// keccak256 of the canonical off-chain rules document.
+    pub rules_hash: [u8; 32],
+    /// Whether a failing game-over hook fails `dispatch_game_over_hook`;
+    /// otherwise the failure is reported through `GameOverHookFailed`.
+    pub hook_required: bool,
+    pub bet_window_table: BetWindowTable,
+    /// Winner NFTs point at `{base}{position}.json`.
+    pub winner_nft_metadata_uri_base: String,
+    pub side_bet_card: SideBetCard,
+    /// Most distinct players the competition admits; zero means no cap.
+    pub max_entries: u32,
+    /// Bets a player must have placed to be issued a participation certificate.
+    pub min_bets_for_certificate: u8,
+    /// Least net prize each position receives from `claim_prize`, topped up
+    /// from the `GuaranteeReserve` while it lasts.
+    pub minimum_prize_guarantee: [u64; MAX_LEADERBOARD_SIZE],
+    /// Multiplier band, in `MULTIPLIER_SCALE` units, within which side bets
+    /// are taken; a zero maximum means no upper bound.
+    pub side_bet_min_multiplier_bps: u64,
+    pub side_bet_max_multiplier_bps: u64,
+    /// When set, `finalize_leaderboard` holds prizes for admin review if
+    /// several leaderboard entries pay out to the same wallet.
+    pub collusion_check: bool,
+    /// Hours after finalization during which prizes can't be claimed, so
+    /// disputes can be raised first.
+    pub dispute_window_hours: u8,
+    /// Share of each entry fee, in basis points, that goes to the jackpot
+    /// instead of the pool.
+    pub jackpot_contribution_bps: u16,
+    /// Final multiplier, in `MULTIPLIER_SCALE` units, a game must end above
+    /// for its player to claim the jackpot; zero means it can't be won.
+    pub jackpot_threshold_bps: u64,
+    /// Seconds after `start_game` a game still waiting for its randomness
+    /// can be refunded with `refund_failed_game`; zero means never.
+    pub randomness_timeout_seconds: u32,
+}
+
+impl CompetitionConfig {
+    pub const LEN: usize = 8 + 1 + RandomnessSource::LEN + 33 + 2 + 4 + 2 + 1 + 2 + 1 + 1
+        + ScoreFormula::LEN + 8 + 8 + 1 + 2 + 8 + 1 + 33 + 8 + 1 + 4 + 1 + 1 + 8 + 1
+        + PrizeSchedule::LEN + 32 + 1 + BetWindowTable::LEN + 4 + MAX_NFT_URI_BASE_LEN + 1 + 4 + 1
+        + 8 * MAX_LEADERBOARD_SIZE + 8 + 8 + 1 + 1 + 2 + 8 + 4;
+
+    /// Validates `args` and builds the config they describe, with every
+    /// event enabled.
+    pub fn new(args: CompetitionConfigArgs) -> Result<Self> {
+        args.validate()?;
+
+        Ok(CompetitionConfig {
+            entry_fee: args.entry_fee,
+            max_side_bets_per_game: args.max_side_bets_per_game,
+            randomness_source: args.randomness_source,
+            hook_program: args.hook_program,
+            peak_weight: args.peak_weight,
+            deck_completion_bonus: args.deck_completion_bonus,
+            withdrawal_fee_bps: args.withdrawal_fee_bps,
+            event_emission_mask: u8::MAX,
+            joker_probability_bps: args.joker_probability_bps,
+            max_bets_per_game: args.max_bets_per_game,
+            post_win_cooldown_competitions: args.post_win_cooldown_competitions,
+            score_formula: args.score_formula,
+            starting_multiplier_bps: args.starting_multiplier_bps,
+            max_bet_amount: args.max_bet_amount,
+            bet_timeout_policy: args.bet_timeout_policy,
+            prize_claim_window_hours: args.prize_claim_window_hours,
+            min_pool_for_payout: args.min_pool_for_payout,
+            rounds_per_competition: args.rounds_per_competition,
+            analytics_program: args.analytics_program,
+            max_pool_size: args.max_pool_size,
+            dynamic_odds: args.dynamic_odds,
+            min_bet_delay: args.min_bet_delay,
+            max_spectators_per_game: args.max_spectators_per_game,
+            max_deck_offset: args.max_deck_offset,
+            hard_mode_bonus_per_card: args.hard_mode_bonus_per_card,
+            side_bet_streak_bonus: args
+                .side_bet_streak_bonus
+                .unwrap_or(DEFAULT_SIDE_BET_STREAK_BONUS),
+            prize_schedule: args.prize_schedule,
+            rules_hash: args.rules_hash,
+            hook_required: args.hook_required,
+            bet_window_table: args.bet_window_table,
+            winner_nft_metadata_uri_base: args.winner_nft_metadata_uri_base,
+            side_bet_card: args.side_bet_card,
+            max_entries: args.max_entries,
+            min_bets_for_certificate: args.min_bets_for_certificate,
+            minimum_prize_guarantee: args.minimum_prize_guarantee,
+            side_bet_min_multiplier_bps: args.side_bet_min_multiplier_bps,
+            side_bet_max_multiplier_bps: args.side_bet_max_multiplier_bps,
+            collusion_check: args.collusion_check,
+            dispute_window_hours: args.dispute_window_hours,
+            jackpot_contribution_bps: args.jackpot_contribution_bps,
+            jackpot_threshold_bps: args.jackpot_threshold_bps,
+            randomness_timeout_seconds: args.randomness_timeout_seconds,
+        })
+    }
+
+    pub fn emits(&self, event_bit: u8) -> bool {
+        self.event_emission_mask & (1 << event_bit) != 0
+    }
+}
+
+#[account]
+pub struct Player {
+    pub game_id: u64,
+    pub start_time: i64,
+    pub multiplier: f64,
+    pub side_bet_score: i64,
+    pub deck_commitment: Option<[u8; 32]>,
+    pub cards_dealt: u8,
+    pub daily_games: u8,
+    pub finished: bool,
+    pub side_bets_placed: u8,
+    pub total_games: u32,
+    pub best_multiplier: f64,
+    pub total_side_bet_score: i64,
+    pub pending_hook: Option<GameEndReason>,
+    pub peak_multiplier: u64,
+    pub score_submitted: bool,
+    pub authority: Pubkey,
+    pub nickname: String,
+    pub randomness_proof: Option<RandomnessProof>,
+    pub revealed_seed: Option<u64>,
+    pub joker_count: u8,
+    pub bets_placed: u8,
+    pub last_win_competition_index: Option<u32>,
+    pub correct_bets: u8,
+    pub last_randomness_at: i64,
+    pub pending_winnings: u64,
+    pub pending_streak_bet: Option<bool>,
+    pub competition_scores: Vec<u64>,
+    pub scores_competition_index: u32,
+    pub seed_hash: Option<[u8; 32]>,
+    pub player_seed: Option<u64>,
+    pub deck_stats_hash: [u8; 8],
+    pub spectator_events: bool,
+    pub spectator_count: u32,
+    /// Cards skipped at the top of the deck in a hard-mode game.
+    pub deck_offset: u8,
+    /// Consecutive correct side bets this game; bets without a side bet
+    /// leave it as is.
+    pub side_bet_streak: u8,
+    /// Bets placed over all games.
+    pub total_bets: u32,
+    /// Card values that double the gain of bets placed on them, drawn from
+    /// the current deck when its randomness arrives.
+    pub hot_values: [u8; 2],
+    /// Where the current game is in its lifecycle. A bet that fails leaves
+    /// it unchanged, so an `Active` game can still be resumed.
+    pub status: GameStatus,
+    /// VRF output for the current game, held until the player reveals the
+    /// seed it is mixed with.
+    pub vrf_output: Option<u64>,
+    /// Bet placed but not yet settled.
+    pub pending_bet: Option<PendingBet>,
+    /// Deck positions, in shuffle order, of the cards dealt this game.
+    pub dealt_mask: u64,
+    /// Deck position, in shuffle order, of the card showing.
+    pub current_card: u8,
+    /// Competition the current game was started in.
+    pub state: Pubkey,
+    /// Competition whose admin waived the post-win cooldown.
+    pub cooldown_waived_in: Option<Pubkey>,
+    /// Games started in competition `activity_competition_index` of `state`.
+    pub competition_games: u32,
+    /// Bets settled in competition `activity_competition_index` of `state`.
+    pub competition_bets: u32,
+    pub activity_competition_index: u32,
+    /// Set once the current game's jackpot win has been claimed.
+    pub jackpot_claimed: bool,
+}
+
+impl Player {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 33 + 1 + 1 + 1 + 1 + 4 + 8 + 8 + 2 + 8 + 1 + 32
+        + 4 + MAX_NICKNAME_LEN
+        + 1 + RandomnessProof::LEN
+        + 9 + 1 + 1 + 5 + 1 + 8 + 8 + 2
+        + 4 + MAX_ROUNDS_PER_COMPETITION as usize * 8
+        + 4 + 33 + 9 + 8 + 1 + 4 + 1 + 1 + 4 + 2 + 1
+        + 9 + 1 + PendingBet::LEN + 8 + 1
+        + 32 + 33 + 4 + 4 + 4 + 1;
+
+    /// Starts a post-win cooldown from competition `competition_index`,
+    /// dropping any earlier waiver.
+    pub fn record_win(&mut self, competition_index: u32) {
+        self.last_win_competition_index = Some(competition_index);
+        self.cooldown_waived_in = None;
+    }
+
+    /// Binds the player to competition `competition_index` of `state`,
+    /// counting their games and bets afresh if they played elsewhere before.
+    pub fn enter_competition(&mut self, state: Pubkey, competition_index: u32) {
+        if self.state != state || self.activity_competition_index != competition_index {
+            self.competition_games = 0;
+            self.competition_bets = 0;
+            self.activity_competition_index = competition_index;
+        }
+        self.state = state;
+    }
+
+    /// Ends the current game for `reason`.
+    pub fn finish(&mut self, reason: GameEndReason) {
+        self.finished = true;
+        self.status = GameStatus::ended_by(reason);
+    }
+
+    /// Seed of the current game's deck, once its randomness is revealed.
+    pub fn deck_seed(&self) -> Option<u64> {
+        Some(self.vrf_output? ^ self.player_seed?)
+    }
+
+    /// Takes the first `cards_dealt` positions of `order`, as arranged by
+    /// `arrange_deck`, as dealt and the next one as the card showing.
+    pub fn record_deal(&mut self, order: &[usize]) {
+        let dealt = order.len().min(self.cards_dealt.into());
+        self.dealt_mask = order[..dealt].iter().fold(0, |mask, &position| mask | 1 << position);
+        if let Some(&current) = order.get(dealt) {
+            self.current_card = current as u8;
+        }
+    }
+
+    /// Counts a new spectator, up to `max_spectators`.
+    pub fn add_spectator(&mut self, max_spectators: u8) -> Result<()> {
+        if self.spectator_count >= u32::from(max_spectators) {
+            return err!(ErrorCode::MaxSpectatorsReached);
+        }
+        self.spectator_count += 1;
+        Ok(())
+    }
+}
+
+/// Proof that a player took part in a competition, at
+/// `[b"cert", state, player]`.
+#[account]
+pub struct ParticipationCertificate {
+    pub games_completed: u8,
+    pub best_score: u64,
+    pub issued_at: i64,
+}
+
+impl ParticipationCertificate {
+    pub const LEN: usize = 1 + 8 + 8;
+}
+
+/// Subscription of `spectator` to `player`'s game updates.
+#[account]
+pub struct SpectatorSubscription {
+    pub player: Pubkey,
+    pub spectator: Pubkey,
+}
+
+impl SpectatorSubscription {
+    pub const LEN: usize = 32 + 32;
+}
+
+/// Account space, header included, for a `State` holding up to
+/// `leaderboard_size` leaderboard entries.
+pub const fn state_space(leaderboard_size: u8) -> usize {
+    VERSIONED_HEADER_LEN + State::BASE_LEN + leaderboard_size as usize * LeaderboardEntry::LEN
+}
+
+/// Account space, header included, for a `Player`. The deck is derived from
+/// the committed seed rather than stored, so the size does not depend on it.
+pub const fn player_space() -> usize {
+    VERSIONED_HEADER_LEN + Player::LEN
+}
+
+#[account]
+pub struct PayoutWallet {
+    pub wallet: Pubkey,
+}
+
+impl PayoutWallet {
+    pub const LEN: usize = 32;
+}
+
+/// A subscriber's event filter, at `[b"filter", subscriber, event_types]`.
+#[account]
+pub struct FilterRegistration {
+    pub subscriber: Pubkey,
+    pub filter: EventFilter,
+}
+
+impl FilterRegistration {
+    pub const LEN: usize = 32 + EventFilter::LEN;
+}
+
+#[account]
+pub struct LeaderboardAnchor {
+    pub ipfs_cid: [u8; IPFS_CID_LEN],
+    pub data_hash: [u8; 32],
+}
+
+impl LeaderboardAnchor {
+    pub const LEN: usize = IPFS_CID_LEN + 32;
+}
+
+#[account]
+pub struct PositionBetEscrow {
+    pub player: Pubkey,
+    pub predicted_position: u8,
+    pub stake: u64,
+}
+
+impl PositionBetEscrow {
+    pub const LEN: usize = 32 + 1 + 8;
+}
+
+/// Admin-funded lamports that pay winning position bets, at
+/// `[b"position_bet_vault", state]`.
+#[account]
+pub struct PositionBetVault {
+    pub state: Pubkey,
+    pub balance: u64,
+}
+
+impl PositionBetVault {
+    pub const LEN: usize = 32 + 8;
+}
+
+#[account]
+pub struct BanList {
+    pub banned_players: Vec<Pubkey>,
+}
+
+impl BanList {
+    pub const LEN: usize = 4 + MAX_BANNED_PLAYERS * 32;
+}
+
+/// Reserved for a future waitlist of players turned away by `PoolFull`, at
+/// `[b"waitlist", state, wallet]`. Nothing creates it yet.
+#[account]
+pub struct WaitlistEntry {}
+
+impl WaitlistEntry {
+    pub const LEN: usize = 0;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SpecialPrize {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: String,
+}
+
+impl SpecialPrize {
+    pub const LEN: usize = 32 + 8 + 4 + MAX_SPECIAL_PRIZE_REASON_LEN;
+}
+
+/// Holds a finalized competition's special prize until its recipient claims it.
+#[account]
+pub struct SpecialPrizeEscrow {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+impl SpecialPrizeEscrow {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Team of players whose submitted game scores are pooled.
+#[account]
+pub struct PlayerGroup {
+    pub group_id: u64,
+    pub state: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub group_score: u64,
+    /// Bit `i` is set once `members[i]` has claimed their group prize share.
+    pub claimed: u16,
+}
+
+impl PlayerGroup {
+    pub const LEN: usize = 8 + 32 + 4 + MAX_GROUP_MEMBERS * 32 + 8 + 2;
+}
+
+/// Admin-funded lamports that top prizes up to the config's
+/// `minimum_prize_guarantee`, at `[b"guarantee_reserve", state]`.
+#[account]
+pub struct GuaranteeReserve {
+    pub state: Pubkey,
+    pub balance: u64,
+}
+
+impl GuaranteeReserve {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Prize lamports a winner has claimed but not yet withdrawn, at
+/// `[b"claim_escrow", state, owner]`.
+#[account]
+pub struct ClaimEscrow {
+    pub state: Pubkey,
+    pub owner: Pubkey,
+    pub owed: u64,
+}
+
+impl ClaimEscrow {
+    pub const LEN: usize = 32 + 32 + 8;
+
+    /// Adds a freshly claimed prize to what the owner is owed.
+    pub fn record(&mut self, amount: u64) -> Result<()> {
+        self.owed = self
+            .owed
+            .checked_add(amount)
+            .ok_or_else(|| overflow(ArithmeticOp::WinningsAdd))?;
+        Ok(())
+    }
+
+    /// Checks `wallet` may receive the owner's prizes and takes everything
+    /// owed. `payout_wallet` is the wallet the owner registered, if any.
+    pub fn withdraw(&mut self, wallet: &Pubkey, payout_wallet: Option<Pubkey>) -> Result<u64> {
+        if *wallet != self.owner && payout_wallet != Some(*wallet) {
+            return err!(ErrorCode::InvalidPayoutWallet);
+        }
+        if self.owed == 0 {
+            return err!(ErrorCode::NothingToWithdraw);
+        }
+        Ok(std::mem::take(&mut self.owed))
+    }
+}
+
+/// A competition's top groups, kept apart from the individual leaderboard,
+/// and the pool their prizes are paid from.
+#[account]
+pub struct GroupLeaderboard {
+    pub state: Pubkey,
+    pub entries: Vec<GroupEntry>,
+    pub pool: u64,
+    pub finalized: bool,
 }
 
-fn calculate_multiplier_gain(current_card_value: u8, bet_type: BetType) -> f64 {
-    match current_card_value {
-        2 => match bet_type {
-            BetType::High => 1.2,
-            BetType::Low => 4.0,
-        },
-        3 => match bet_type {
-            BetType::High => 1.25,
-            BetType::Low => 3.5,
-        },
-        4 => match bet_type {
-            BetType::High => 1.3,
-            BetType::Low => 3.0,
-        },
-        5 => match bet_type {
-            BetType::High => 1.35,
-            BetType::Low => 2.5,
-        },
-        6 => match bet_type {
-            BetType::High => 1.4,
-            BetType::Low => 2.0,
-        },
-        7 => match bet_type {
-            BetType::High => 1.5,
-            BetType::Low => 1.7,
-        },
-        8 => match bet_type {
-            BetType::High => 1.6,
-            BetType::Low => 1.6,
-        },
-        9 => match bet_type {
-            BetType::High => 1.8,
-            BetType::Low => 1.6,
-        },
-        10 => match bet_type {
-            BetType::High => 2.0,
-            BetType::Low => 1.5,
-        },
-        11 => match bet_type {
-            BetType::High => 2.5,
-            BetType::Low => 1.4,
-        },
-        12 => match bet_type {
-            BetType::High => 3.0,
-            BetType::Low => 1.3,
-        },
-        13 => match bet_type {
-            BetType::High => 4.0,
-            BetType::Low => 1.2,
-        },
-        14 => match bet_type {
-            BetType::High => 1.2,
-            BetType::Low => 1.2,
-        },
-        _ => 1.0,
+impl GroupLeaderboard {
+    pub const LEN: usize = 32 + 4 + MAX_GROUP_LEADERBOARD_SIZE * GroupEntry::LEN + 8 + 1;
+
+    /// Orders the groups by score and keeps the top `MAX_GROUP_LEADERBOARD_SIZE`.
+    pub fn rank(&mut self) {
+        self.entries.sort_by_key(|entry| std::cmp::Reverse(entry.group_score));
+        self.entries.truncate(MAX_GROUP_LEADERBOARD_SIZE);
     }
 }
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = admin, space = 8 + State::LEN)]
-    pub state: Account<'info, State>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GroupEntry {
+    pub group: Pubkey,
+    pub group_score: u64,
 }
 
-#[derive(Accounts)]
-pub struct RequestRandomness<'info> {
-    pub switchboard_vrf_program: AccountInfo<'info>,
-    #[account(mut)]
-    pub vrf_account: AccountInfo<'info>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
+impl GroupEntry {
+    pub const LEN: usize = 32 + 8;
 }
 
-#[derive(Accounts)]
-pub struct ReceiveRandomness<'info> {
-    #[account(mut)]
-    pub player: Account<'info, Player>,
-    #[account(signer)]
-    pub authority: Signer<'info>,
+/// Marks a wallet as blacklisted from a competition while it exists.
+#[account]
+pub struct BlacklistEntry {
+    pub wallet: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct StartGame<'info> {
-    #[account(mut)]
-    pub state: Account<'info, State>,
-    #[account(mut)]
-    pub player: Account<'info, Player>,
-    #[account(signer)]
-    pub authority: Signer<'info>,
+impl BlacklistEntry {
+    pub const LEN: usize = 32;
 }
 
-#[derive(Accounts)]
-pub struct PlaceBet<'info> {
-    #[account(mut)]
-    pub player: Account<'info, Player>,
-    #[account(signer)]
-    pub authority: Signer<'info>,
+#[account]
+pub struct NicknameRecord {
+    pub owner: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct FinalizeLeaderboard<'info> {
-    #[account(mut)]
-    pub state: Account<'info, State>,
-    #[account(signer)]
-    pub admin: Signer<'info>,
+impl NicknameRecord {
+    pub const LEN: usize = 32;
 }
 
-#[derive(Accounts)]
-pub struct ClaimPrize<'info> {
-    #[account(mut)]
-    pub state: Account<'info, State>,
-    #[account(signer)]
-    pub player: Signer<'info>,
-    #[account(mut)]
-    pub player_wallet: SystemAccount<'info>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Card {
+    pub suit: String,
+    pub value: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RandomnessProof {
+    pub proof: [u8; VRF_PROOF_LEN],
+    pub alpha: [u8; 32],
+}
+
+impl RandomnessProof {
+    pub const LEN: usize = VRF_PROOF_LEN + 32;
+}
+
+/// Basis-point weights of each score component; they must sum to 10_000.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ScoreFormula {
+    pub multiplier_weight: u16,
+    pub side_bet_weight: u16,
+    pub time_bonus_weight: u16,
+    pub streak_bonus_weight: u16,
+    pub cards_remaining_weight: u16,
+}
+
+impl ScoreFormula {
+    pub const LEN: usize = 2 * 5;
+
+    pub fn validate(&self) -> Result<()> {
+        let total: u64 = [
+            self.multiplier_weight,
+            self.side_bet_weight,
+            self.time_bonus_weight,
+            self.streak_bonus_weight,
+            self.cards_remaining_weight,
+        ]
+        .iter()
+        .map(|weight| u64::from(*weight))
+        .sum();
+
+        if total != BPS_DENOMINATOR {
+            return err!(ErrorCode::InvalidScoreFormula);
+        }
+        Ok(())
+    }
+}
+
+/// Percent of the pool paid to each leaderboard position and kept by the
+/// house; together they must sum to 100.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PrizeSchedule {
+    pub positions: [u8; MAX_LEADERBOARD_SIZE],
+    pub house: u8,
+}
+
+impl PrizeSchedule {
+    pub const LEN: usize = MAX_LEADERBOARD_SIZE + 1;
+
+    /// 50/30/20 between the top three, nothing for the house.
+    pub const STANDARD: PrizeSchedule = PrizeSchedule::new([50, 30, 20], 0);
+
+    /// Schedule paying `top` to the first positions in order and nothing to
+    /// the rest of the board.
+    pub const fn new<const N: usize>(top: [u8; N], house: u8) -> PrizeSchedule {
+        let mut positions = [0; MAX_LEADERBOARD_SIZE];
+        let mut position = 0;
+        while position < N {
+            positions[position] = top[position];
+            position += 1;
+        }
+        PrizeSchedule { positions, house }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let total: u64 = self
+            .positions
+            .iter()
+            .chain([&self.house])
+            .map(|percentage| u64::from(*percentage))
+            .sum();
+
+        if total != 100 {
+            return err!(ErrorCode::InvalidPrizeSchedule);
+        }
+        Ok(())
+    }
+
+    /// Percent of the pool paid to a leaderboard position.
+    pub fn percentage(&self, position: u8) -> Result<u64> {
+        self.positions
+            .get(position as usize)
+            .map(|percentage| u64::from(*percentage))
+            .ok_or(ErrorCode::NotOnLeaderboard.into())
+    }
+}
+
+/// Seconds a player has to bet on each card, indexed by `card_value - 2`, so
+/// near-certain bets can be given less time than even ones.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BetWindowTable {
+    pub windows_seconds: [u32; 13],
+}
+
+impl BetWindowTable {
+    pub const LEN: usize = 13 * 4;
+
+    pub fn validate(&self) -> Result<()> {
+        if !self
+            .windows_seconds
+            .iter()
+            .all(|window| (MIN_BET_WINDOW_SECONDS..=MAX_BET_WINDOW_SECONDS).contains(window))
+        {
+            return err!(ErrorCode::InvalidBetWindow);
+        }
+        Ok(())
+    }
+
+    /// Window for a card; jokers win whatever the bet and get the longest.
+    pub fn window_for(&self, card_value: u8) -> u32 {
+        card_value
+            .checked_sub(2)
+            .and_then(|index| self.windows_seconds.get(index as usize))
+            .copied()
+            .unwrap_or(MAX_BET_WINDOW_SECONDS)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TournamentClaim {
+    pub state: Pubkey,
+    pub position: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlayerSummary {
+    pub total_games: u32,
+    pub best_multiplier: f64,
+    pub total_side_bet_score: i64,
+    pub daily_games_remaining: u8,
+}
+
+/// Name, symbol and URI of a winner NFT, the leading fields of Token
+/// Metadata's `DataV2`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WinnerNftMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// A position known to be on a leaderboard of a given size. Positions taken
+/// from instruction arguments go through `new` before they index anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeaderboardPosition(u8);
+
+impl LeaderboardPosition {
+    /// Fails with `NotOnLeaderboard` unless `position < leaderboard_size`.
+    pub fn new(position: u8, leaderboard_size: usize) -> Result<Self> {
+        if position as usize >= leaderboard_size {
+            return err!(ErrorCode::NotOnLeaderboard);
+        }
+        Ok(LeaderboardPosition(position))
+    }
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn is_first(self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub nickname: String,
+    pub score: u64,
+    pub side_bet_score: i64,
+    pub claimed: bool,
+    pub finished_at: i64,
+}
+
+/// Events an off-chain subscriber is interested in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct EventFilter {
+    /// Bitmask of `FILTER_EVENT_*` values.
+    pub event_types: u8,
+    /// Lowest multiplier of interest, in `MULTIPLIER_SCALE` units.
+    pub min_multiplier_bps: u64,
+    /// Only events about this player, when set.
+    pub player_key: Option<Pubkey>,
+}
+
+impl EventFilter {
+    pub const LEN: usize = 1 + 8 + 33;
+}
+
+/// Seconds until each milestone, zero or negative once it has passed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct TimeInfo {
+    pub until_start: i64,
+    pub until_end: i64,
+    /// `None` until the competition is finalized and the deadline is known.
+    pub until_claim_deadline: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProvisionalPrize {
+    pub position: u8,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+impl LeaderboardEntry {
+    pub const LEN: usize = 32 + 4 + MAX_NICKNAME_LEN + 8 + 8 + 1 + 8;
+}
+
+/// `LeaderboardEntry` as stored before `finished_at` was added.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyLeaderboardEntry {
+    pub player: Pubkey,
+    pub nickname: String,
+    pub score: u64,
+    pub side_bet_score: i64,
+    pub claimed: bool,
+}
+
+impl From<LegacyLeaderboardEntry> for LeaderboardEntry {
+    fn from(entry: LegacyLeaderboardEntry) -> Self {
+        LeaderboardEntry {
+            player: entry.player,
+            nickname: entry.nickname,
+            score: entry.score,
+            side_bet_score: entry.side_bet_score,
+            claimed: entry.claimed,
+            finished_at: 0,
+        }
+    }
 }
 
-#[account]
-pub struct State {
+/// `State` body as stored with `LegacyLeaderboardEntry` entries.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyState {
     pub admin: Pubkey,
-    pub entry_fee: u64,
+    pub config: Pubkey,
     pub start_time: i64,
     pub end_time: i64,
     pub leaderboard_size: u8,
-    pub leaderboard: Vec<LeaderboardEntry>,
+    pub leaderboard: Vec<LegacyLeaderboardEntry>,
     pub finalized: bool,
     pub finalized_timestamp: i64,
     pub pool: u64,
+    pub accounts_closed: u32,
+    pub participants: u32,
+    pub competition_index: u32,
 }
 
-impl State {
-    pub const LEN: usize = 32 + 8 + 8 + 8 + 1 + 4 + 8 + 8;
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum BetType {
+    High,
+    Low,
 }
 
-#[account]
-pub struct Player {
-    pub game_id: u64,
-    pub start_time: i64,
-    pub multiplier: f64,
-    pub side_bet_score: i64,
-    pub randomness: Option<u64>,
-    pub deck: Vec<Card>,
-    pub daily_games: u8,
-    pub finished: bool,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum RandomnessSource {
+    SwitchboardVrf { program_id: Pubkey },
+    PythEntropy { program_id: Pubkey },
+    BlockhashFallback,
+}
+
+impl RandomnessSource {
+    pub const LEN: usize = 1 + 32;
+
+    pub fn program_id(&self) -> Option<Pubkey> {
+        match self {
+            RandomnessSource::SwitchboardVrf { program_id }
+            | RandomnessSource::PythEntropy { program_id } => Some(*program_id),
+            RandomnessSource::BlockhashFallback => None,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Card {
-    pub suit: String,
-    pub value: u8,
+pub enum SideBetType {
+    Color { red: bool },
+    Parity { even: bool },
+    SuitRun { length: u8 },
+    /// Predicts whether the player's next main bet will be correct.
+    HighLowStreak { correct: bool },
 }
 
+/// A placed bet waiting for the hash of the slot it landed in.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct LeaderboardEntry {
-    pub player: Pubkey,
-    pub score: u64,
+pub struct PendingBet {
+    pub config: Pubkey,
+    pub bet_type: BetType,
+    pub side_bet: Option<SideBetType>,
+    pub bet_amount: u64,
+    pub slot: u64,
+}
+
+impl PendingBet {
+    pub const LEN: usize = 32 + 1 + 3 + 8 + 8;
+}
+
+/// Card color and parity side bets are judged on.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SideBetCard {
+    /// The card showing when the bet is placed.
+    Current,
+    /// The card the bet reveals, the one the main bet is settled on.
+    Next,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub enum BetType {
-    High,
-    Low,
+pub enum GameEndReason {
+    LostBet,
+    DeckExhausted,
+    BetLimitReached,
+    BetTimeExpired,
+}
+
+/// Lifecycle of a player's current game.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    /// Dealt and taking bets.
+    Active,
+    /// Started, waiting for the deck's randomness.
+    AwaitingRandomness,
+    Finished,
+    /// Ended because a bet window lapsed.
+    TimedOut,
+    /// Entry fee refunded because the randomness never arrived; it has no
+    /// score.
+    Refunded,
+}
+
+impl GameStatus {
+    /// Status of a game that ended for `reason`.
+    pub fn ended_by(reason: GameEndReason) -> Self {
+        match reason {
+            GameEndReason::BetTimeExpired => GameStatus::TimedOut,
+            _ => GameStatus::Finished,
+        }
+    }
+}
+
+/// Category a competition can be listed under.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameTag {
+    Practice,
+    Beginner,
+    Advanced,
+    Tournament,
+    Sponsored,
+    Private,
+}
+
+/// What happens to a game whose bet window lapses.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum BetTimeoutPolicy {
+    /// Ends the game keeping the score earned so far.
+    FinishGame,
+    /// Ends the game with the multiplier reset to zero.
+    ForfeitMultiplier,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum ScoreSkipReason {
+    InvalidAccount,
+    GameNotFinished,
+    AlreadySubmitted,
+    UnknownAuthority,
+    NegativeScore,
+    OtherCompetition,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub enum SideBetType {
-    Color { red: bool },
-    Parity { even: bool },
+pub struct AnalyticsEventArgs {
+    pub event_type: u8,
+    pub player: Pubkey,
+    pub value: u64,
+    pub timestamp: i64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GameOverHookArgs {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub final_multiplier: f64,
+    pub side_bet_score: i64,
+    pub reason: GameEndReason,
+    /// Multiplier score in `MULTIPLIER_SCALE` units, as `calculate_score`.
+    pub final_score: u64,
+}
+
+/// Result of `State::adjust_entry_score`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoreAdjustment {
+    pub old_score: u64,
+    pub new_score: u64,
+    pub old_position: u8,
+    pub new_position: u8,
+}
+
+/// Serializes as a `BET_OUTCOME_VERSION` byte followed by the
+/// `BetOutcomeV1` fields; read it back with `decode_bet_outcome`.
+#[derive(Clone)]
 pub struct BetOutcome {
     pub correct: bool,
     pub multiplier_gain: f64,
     pub side_bet_result: Option<i64>,
+    /// Streak length, when this bet's side bet reached a streak milestone.
+    pub side_bet_streak_milestone: Option<u8>,
+    /// Value of the current card when it was one of the player's hot values,
+    /// which doubled `multiplier_gain`.
+    pub hot_card: Option<u8>,
+}
+
+impl AnchorSerialize for BetOutcome {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BET_OUTCOME_VERSION.serialize(writer)?;
+        BetOutcomeV1::from(self).serialize(writer)
+    }
+}
+
+/// Wire layout of a version 1 `BetOutcome`. Later versions only append
+/// fields, so every version starts with these.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct BetOutcomeV1 {
+    pub correct: bool,
+    /// In `MULTIPLIER_SCALE` units.
+    pub multiplier_gain: u64,
+    pub side_bet_result: Option<i64>,
+}
+
+impl From<&BetOutcome> for BetOutcomeV1 {
+    fn from(outcome: &BetOutcome) -> Self {
+        BetOutcomeV1 {
+            correct: outcome.correct,
+            multiplier_gain: multiplier_to_bps(outcome.multiplier_gain),
+            side_bet_result: outcome.side_bet_result,
+        }
+    }
 }
 
 #[event]
@@ -451,7 +6080,7 @@ pub struct RandomnessRequested {
 
 #[event]
 pub struct RandomnessReceived {
-    pub randomness: u64,
+    pub deck_commitment: [u8; 32],
 }
 
 #[event]
@@ -462,32 +6091,374 @@ pub struct GameStarted {
 
 #[event]
 pub struct BetPlaced {
+    /// `filter_tag(FILTER_EVENT_BET_PLACED, player, multiplier)`.
+    pub filter_tag: [u8; 8],
     pub player: Pubkey,
     pub game_id: u64,
     pub bet_type: BetType,
     pub multiplier_gain: f64,
     pub side_bet_result: Option<i64>,
+    /// Multiplier after the bet, in `MULTIPLIER_SCALE` units.
+    pub multiplier: u64,
+    /// The versioned `BetOutcome`, length-prefixed so decoders built for an
+    /// older version can skip fields they don't know.
+    pub outcome: Vec<u8>,
+}
+
+/// A pending bet whose slot hash expired before it was settled, lost.
+#[event]
+pub struct BetForfeited {
+    pub player: Pubkey,
+    pub slot: u64,
 }
 
+/// `peak_multiplier` and `final_multiplier_scaled` are in `MULTIPLIER_SCALE`
+/// units, as stored on the player.
 #[event]
 pub struct GameOver {
     pub player: Pubkey,
     pub game_id: u64,
     pub final_multiplier: f64,
+    pub peak_multiplier: u64,
     pub side_bet_score: i64,
+    pub reason: GameEndReason,
+    pub final_multiplier_scaled: u64,
+}
+
+#[event]
+pub struct ScoreSubmitted {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub nickname: String,
+    pub score: u64,
+}
+
+#[event]
+pub struct ScoreSkipped {
+    pub account: Pubkey,
+    pub reason: ScoreSkipReason,
+}
+
+#[event]
+pub struct ScoresBatchSubmitted {
+    pub submitted: u8,
+    pub skipped: u8,
 }
 
 #[event]
 pub struct LeaderboardFinalized {
     pub timestamp: i64,
     pub leaderboard: Vec<LeaderboardEntry>,
+    pub claim_deadline: i64,
+    pub prizes: Vec<ProvisionalPrize>,
+    /// Scale of the leaderboard scores; always `MULTIPLIER_SCALE`.
+    pub multiplier_scale: u64,
 }
 
 #[event]
 pub struct PrizeClaimed {
     pub player: Pubkey,
     pub position: usize,
-    pub prize: u64,
+    pub gross_prize: u64,
+    pub net_prize: u64,
+}
+
+#[event]
+pub struct AccountsClosedBatch {
+    pub count: u8,
+    pub rent_reclaimed: u64,
+}
+
+#[event]
+pub struct PayoutWalletSet {
+    pub player: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct AccountLayoutUpgraded {
+    pub account: Pubkey,
+    pub old_len: u32,
+    pub new_len: u32,
+}
+
+#[event]
+pub struct LeaderboardDataAnchored {
+    pub state: Pubkey,
+    pub competition_index: u32,
+    pub ipfs_cid: [u8; IPFS_CID_LEN],
+    pub data_hash: [u8; 32],
+}
+
+#[event]
+pub struct WinningsSettled {
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JackpotClaimed {
+    pub state: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GameRefunded {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionPredicted {
+    pub player: Pubkey,
+    pub predicted_position: u8,
+    pub stake: u64,
+}
+
+#[event]
+pub struct PositionBetSettled {
+    pub player: Pubkey,
+    pub predicted_position: u8,
+    pub actual_position: Option<u8>,
+    pub payout: u64,
+}
+
+#[event]
+pub struct HealthStatus {
+    pub program_version: u8,
+    pub state_key: Pubkey,
+    pub pool: u64,
+    pub participants: u32,
+    pub finalized: bool,
+    pub clock: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CooldownWaived {
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct BanListUpdated {
+    pub player: Pubkey,
+    pub banned: bool,
+}
+
+#[event]
+pub struct NicknameSet {
+    pub player: Pubkey,
+    pub nickname: String,
+}
+
+#[event]
+pub struct AnalyticsCallFailed {
+    pub event_type: u8,
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct PoolFull {
+    pub current_pool: u64,
+    pub max_pool: u64,
+}
+
+#[event]
+pub struct DeckStatistics {
+    pub chi_squared_bps: u64,
+    pub runs_up: u32,
+    pub runs_down: u32,
+    pub entropy_estimate_bps: u64,
+}
+
+#[event]
+pub struct GameTagged {
+    pub state: Pubkey,
+    pub tag: GameTag,
+}
+
+#[event]
+pub struct GameTagMatched {
+    pub state: Pubkey,
+}
+
+#[event]
+pub struct WinnerNftMinted {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub position: u8,
+}
+
+#[event]
+pub struct GameOverHookFailed {
+    pub player: Pubkey,
+    pub game_id: u64,
+}
+
+#[event]
+pub struct RulesHashUpdated {
+    pub old_hash: [u8; 32],
+    pub new_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RulesVerified {
+    pub matches: bool,
+}
+
+#[event]
+pub struct HousePrizeClaimed {
+    pub state: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SpecialPrizeSet {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: String,
+}
+
+#[event]
+pub struct SpecialPrizeClaimed {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ScoreAdjusted {
+    pub player: Pubkey,
+    pub old_score: u64,
+    pub new_score: u64,
+    pub reason: String,
+}
+
+#[event]
+pub struct LeaderboardPositionChanged {
+    pub player: Pubkey,
+    pub old_position: u8,
+    pub new_position: u8,
+}
+
+/// Gains are in `MULTIPLIER_SCALE` units.
+#[event]
+pub struct HotCardActivated {
+    pub player: Pubkey,
+    pub card_value: u8,
+    pub normal_gain: u64,
+    pub boosted_gain: u64,
+}
+
+#[event]
+pub struct SideBetStreakBonus {
+    pub player: Pubkey,
+    pub streak: u8,
+    pub bonus: i32,
+}
+
+#[event]
+pub struct CertificateIssued {
+    pub player: Pubkey,
+    pub best_score: u64,
+    pub games_completed: u8,
+}
+
+#[event]
+pub struct GuaranteeShortfall {
+    pub position: u8,
+    pub shortfall_amount: u64,
+}
+
+#[event]
+pub struct CollusionSuspected {
+    pub wallet: Pubkey,
+    /// Zero-based leaderboard positions paying out to `wallet`.
+    pub positions: Vec<u8>,
+}
+
+#[event]
+pub struct PrizesReleased {
+    pub state: Pubkey,
+}
+
+#[event]
+pub struct DisputeWindowBypassed {
+    pub state: Pubkey,
+}
+
+#[event]
+pub struct ClaimWithdrawn {
+    pub player: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LeaderboardRootUpdated {
+    pub root: [u8; 32],
+    pub entry_count: u32,
+}
+
+#[event]
+pub struct EntriesClosed {
+    pub state: Pubkey,
+}
+
+#[event]
+pub struct LeaderboardResized {
+    pub state: Pubkey,
+    pub old_size: u8,
+    pub new_size: u8,
+}
+
+#[event]
+pub struct GroupCreated {
+    pub group: Pubkey,
+    pub group_id: u64,
+    pub members: Vec<Pubkey>,
+}
+
+#[event]
+pub struct GroupLeaderboardFinalized {
+    pub state: Pubkey,
+    pub entries: Vec<GroupEntry>,
+    pub pool: u64,
+}
+
+#[event]
+pub struct GroupPrizeClaimed {
+    pub group: Pubkey,
+    pub member: Pubkey,
+    pub position: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LeaderboardSnapshot {
+    pub epoch: u32,
+    pub page: u8,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[event]
+pub struct SpectatorUpdate {
+    pub player: Pubkey,
+    pub spectator_count: u32,
+    pub last_bet_type: BetType,
+    pub new_multiplier: u64,
+}
+
+#[event]
+pub struct BetOdds {
+    pub high_numerator: u64,
+    pub low_numerator: u64,
+    pub tie_numerator: u64,
+    pub denominator: u64,
+    pub current_card_value: u8,
+    pub current_card_suit: String,
 }
 
 #[error_code]
@@ -512,4 +6483,216 @@ pub enum ErrorCode {
     ArithmeticError,
     #[msg("Unauthorized access.")]
     Unauthorized,
+    #[msg("Side bet limit reached for this game.")]
+    SideBetLimitReached,
+    #[msg("Randomness program does not match the configured source.")]
+    InvalidRandomnessProgram,
+    #[msg("Hook program does not match the round config.")]
+    InvalidHookProgram,
+    #[msg("No game-over hook is pending for this player.")]
+    NoPendingHook,
+    #[msg("Too many accounts forwarded to the hook.")]
+    TooManyHookAccounts,
+    #[msg("Peak weight cannot exceed 10000 basis points.")]
+    InvalidPeakWeight,
+    #[msg("Withdrawal fee cannot exceed 10000 basis points.")]
+    InvalidWithdrawalFee,
+    #[msg("Leaderboard already finalized.")]
+    AlreadyFinalized,
+    #[msg("Game is not finished.")]
+    GameNotFinished,
+    #[msg("Score already submitted for this game.")]
+    ScoreAlreadySubmitted,
+    #[msg("Prize can only be paid to the winner or their registered payout wallet.")]
+    InvalidPayoutWallet,
+    #[msg("Prize already claimed.")]
+    PrizeAlreadyClaimed,
+    #[msg("Too many accounts in batch.")]
+    BatchTooLarge,
+    #[msg("Player still has an unclaimed prize.")]
+    PrizeUnclaimed,
+    #[msg("Nickname must be between 1 and 16 bytes.")]
+    InvalidNicknameLength,
+    #[msg("Nickname may only contain ASCII letters, digits, '_' and '-'.")]
+    InvalidNicknameCharacters,
+    #[msg("The current nickname record must be passed to release it.")]
+    NicknameRecordMissing,
+    #[msg("Suit run length must be 2, 3 or 4.")]
+    InvalidSideBet,
+    #[msg("Not enough cards left in the deck for this side bet.")]
+    InsufficientDeckForSideBet,
+    #[msg("Account layout version does not match this program.")]
+    AccountLayoutVersionMismatch,
+    #[msg("Layout upgrades cannot change the account type.")]
+    AccountTypeChanged,
+    #[msg("Player is banned from this competition.")]
+    PlayerBanned,
+    #[msg("Player is already banned.")]
+    PlayerAlreadyBanned,
+    #[msg("Player is not on the ban list.")]
+    PlayerNotBanned,
+    #[msg("Ban list is full.")]
+    BanListFull,
+    #[msg("Randomness has not been received yet.")]
+    RandomnessNotReceived,
+    #[msg("Predicted position is outside the leaderboard.")]
+    InvalidPredictedPosition,
+    #[msg("Stake cannot be zero.")]
+    InvalidStake,
+    #[msg("Leaderboard is not finalized yet.")]
+    LeaderboardNotFinalized,
+    #[msg("Seed does not match the deck commitment.")]
+    DeckCommitmentMismatch,
+    #[msg("Joker probability cannot exceed 10000 basis points.")]
+    InvalidJokerProbability,
+    #[msg("Player is in the post-win cooldown.")]
+    PlayerInCooldown,
+    #[msg("Remaining accounts do not match the claimed tournaments.")]
+    ClaimAccountsMismatch,
+    #[msg("Score formula weights must sum to 10000 basis points.")]
+    InvalidScoreFormula,
+    #[msg("Starting multiplier must be at least 1.0x (10000 basis points).")]
+    InvalidStartingMultiplier,
+    #[msg("Leaderboard data does not match the anchored hash.")]
+    LeaderboardDataMismatch,
+    #[msg("Randomness delivered too soon after the previous delivery.")]
+    RandomnessRateLimited,
+    #[msg("Bet amount exceeds the maximum bet.")]
+    BetAmountTooLarge,
+    #[msg("Prize pool cannot cover the payout.")]
+    InsufficientPool,
+    #[msg("Wallet is blacklisted.")]
+    Blacklisted,
+    #[msg("Prize claim window must be between 24 and 8760 hours.")]
+    InvalidPrizeClaimWindow,
+    #[msg("Prize pool is below the minimum for payouts.")]
+    PoolBelowMinimum,
+    #[msg("Prize pool meets the minimum for payouts.")]
+    PoolAboveMinimum,
+    #[msg("Rounds per competition must be between 1 and 10.")]
+    InvalidRoundsPerCompetition,
+    #[msg("Account already uses the current layout.")]
+    AccountAlreadyMigrated,
+    #[msg("Player has not committed a deck seed.")]
+    SeedNotCommitted,
+    #[msg("Seed does not match the player's commitment.")]
+    SeedCommitmentMismatch,
+    #[msg("Player seed has not been revealed yet.")]
+    PlayerSeedNotRevealed,
+    #[msg("Analytics program does not match the configured one.")]
+    InvalidAnalyticsProgram,
+    #[msg("Prize pool has reached its maximum size.")]
+    PoolFull,
+    #[msg("Deck statistics were already computed for this deck.")]
+    DeckStatisticsAlreadyComputed,
+    #[msg("Competition already has the maximum number of tags.")]
+    TooManyTags,
+    #[msg("Competition already has this tag.")]
+    DuplicateGameTag,
+    #[msg("Too soon after randomness was received to bet.")]
+    TooSoonToBet,
+    #[msg("Game already has the maximum number of spectators.")]
+    MaxSpectatorsReached,
+    #[msg("Side bet score is negative and cannot be converted to points.")]
+    NegativeScore,
+    #[msg("Page size must be greater than zero.")]
+    InvalidPageSize,
+    #[msg("Leaderboard changed since the expected epoch; restart from page 0.")]
+    LeaderboardModified,
+    #[msg("Deterministic test randomness is not enabled in this build.")]
+    TestRngDisabled,
+    #[msg("A compute unit limit instruction must directly precede a dynamic-odds bet.")]
+    ComputeBudgetMustBeFirst,
+    #[msg("Requested compute unit limit is too low for this bet.")]
+    InsufficientComputeBudget,
+    #[msg("A group needs between 1 and 10 members.")]
+    InvalidGroupSize,
+    #[msg("Wallet is listed twice in the group.")]
+    DuplicateGroupMember,
+    #[msg("Wallet is not a member of this group.")]
+    NotGroupMember,
+    #[msg("The group leaderboard for this competition must be passed with the group.")]
+    GroupLeaderboardMissing,
+    #[msg("Leaderboard size must be between 1 and the maximum.")]
+    InvalidLeaderboardSize,
+    #[msg("Leaderboard size can't change once scores or deposits exist.")]
+    LeaderboardLocked,
+    #[msg("Deck offset is above the allowed maximum.")]
+    InvalidDeckOffset,
+    #[msg("Game id must be greater than the player's previous game id.")]
+    GameIdReused,
+    #[msg("Score adjustment reason is too long.")]
+    AdjustmentReasonTooLong,
+    #[msg("The deck holds the same card twice.")]
+    DeckCorrupt,
+    #[msg("Special prize reason is too long.")]
+    SpecialPrizeReasonTooLong,
+    #[msg("Special prize is larger than the pool.")]
+    SpecialPrizeExceedsPool,
+    #[msg("The special prize escrow must be passed to finalize a competition with a special prize.")]
+    SpecialPrizeEscrowMissing,
+    #[msg("Prize schedule percentages must sum to 100.")]
+    InvalidPrizeSchedule,
+    #[msg("Bet windows must be between 5 and 300 seconds.")]
+    InvalidBetWindow,
+    #[msg("The competition has already started.")]
+    CompetitionStarted,
+    #[msg("Winner NFT metadata URI base is too long.")]
+    NftUriBaseTooLong,
+    #[msg("The prize for this position has not been claimed.")]
+    PrizeNotClaimed,
+    #[msg("The NFT mint must be a fresh mint with no decimals.")]
+    InvalidNftMint,
+    #[msg("Entries are closed; no new games can be started.")]
+    EntriesClosed,
+    #[msg("Bet outcome data is malformed.")]
+    InvalidBetOutcome,
+    #[msg("The competition has reached its maximum number of players.")]
+    TournamentFull,
+    #[msg("Merkle proof does not match the leaderboard root.")]
+    InvalidMerkleProof,
+    #[msg("Prizes of this competition are claimed with Merkle proofs.")]
+    MerkleLeaderboardActive,
+    #[msg("Competition timestamps must be between 1970 and 3000.")]
+    InvalidTimestamp,
+    #[msg("Not enough bets placed for a participation certificate.")]
+    NotEnoughBetsForCertificate,
+    #[msg("Pool arithmetic overflowed.")]
+    PoolOverflow,
+    #[msg("Prize arithmetic overflowed.")]
+    PrizeOverflow,
+    #[msg("Score arithmetic overflowed.")]
+    ScoreOverflow,
+    #[msg("Nothing is owed in this claim escrow.")]
+    NothingToWithdraw,
+    #[msg("Prizes are held while the admin reviews shared payout wallets.")]
+    PrizesHeldForReview,
+    #[msg("Dispute window must be at most 72 hours.")]
+    InvalidDisputeWindow,
+    #[msg("Prizes can't be claimed during the dispute window.")]
+    DisputeWindowActive,
+    #[msg("The previous bet has not been settled yet.")]
+    BetPending,
+    #[msg("No bet placed under this config is waiting to be settled.")]
+    NoPendingBet,
+    #[msg("A bet can be settled from the slot after it was placed.")]
+    BetNotSettleable,
+    #[msg("Position predictions close when the competition ends.")]
+    PredictionsClosed,
+    #[msg("The position bet vault can't cover the winnings.")]
+    InsufficientPositionBetVault,
+    #[msg("The player's game was started in another competition.")]
+    PlayerNotInCompetition,
+    #[msg("The player's wager winnings have not been paid out.")]
+    WinningsUnpaid,
+    #[msg("Jackpot contribution must be at most 10000 basis points.")]
+    InvalidJackpotContribution,
+    #[msg("The game didn't end above the jackpot threshold.")]
+    JackpotNotWon,
+    #[msg("The jackpot is empty.")]
+    JackpotEmpty,
+    #[msg("Only a game still waiting for its randomness can be refunded.")]
+    GameNotRefundable,
+    #[msg("The randomness can still arrive; refunds open after the timeout.")]
+    RandomnessTimeoutPending,
 }
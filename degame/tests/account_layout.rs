@@ -0,0 +1,46 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator};
+use degame::*;
+
+mod common;
+
+use common::program::{failure, process, TestAccount};
+
+/// A stored player account with `version` as its layout version.
+fn stored_player(version: u8) -> Vec<u8> {
+    let mut data = Vec::new();
+    common::player().try_serialize(&mut data).unwrap();
+    data[8] = version;
+    data
+}
+
+#[test]
+fn accounts_are_stored_with_the_layout_version() {
+    let data = stored_player(LAYOUT_VERSION);
+
+    assert_eq!(data[..8], Player::DISCRIMINATOR);
+    assert_eq!(data[8], LAYOUT_VERSION);
+    assert!(VersionedAccount::<Player>::try_deserialize(&mut &data[..]).is_ok());
+}
+
+#[test]
+fn outdated_layout_is_rejected() {
+    let data = stored_player(LAYOUT_VERSION.wrapping_sub(1));
+
+    assert_eq!(
+        VersionedAccount::<Player>::try_deserialize(&mut &data[..]).err(),
+        Some(ErrorCode::AccountLayoutVersionMismatch.into())
+    );
+}
+
+#[test]
+fn instructions_refuse_outdated_accounts() {
+    let key = Pubkey::new_unique();
+    let mut player = TestAccount::program(key, &*common::player(), player_space());
+    player.data[8] = LAYOUT_VERSION.wrapping_add(1);
+
+    assert_eq!(
+        process(instruction::GetPlayerSummary {}, &mut [player], 0),
+        failure(ErrorCode::AccountLayoutVersionMismatch)
+    );
+}
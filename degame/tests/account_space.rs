@@ -0,0 +1,65 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use degame::*;
+
+mod common;
+
+fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data.len()
+}
+
+fn full_entry() -> LeaderboardEntry {
+    LeaderboardEntry {
+        player: Pubkey::default(),
+        nickname: "n".repeat(MAX_NICKNAME_LEN),
+        score: 0,
+        side_bet_score: 0,
+        claimed: false,
+        finished_at: 0,
+    }
+}
+
+#[test]
+fn state_space_fits_a_full_state() {
+    for leaderboard_size in 0..=MAX_LEADERBOARD_SIZE as u8 {
+        let mut state = common::versioned_state();
+        state.leaderboard = vec![full_entry(); leaderboard_size.into()];
+        state.game_tags = vec![GameTag::Practice; MAX_GAME_TAGS];
+        state.special_prize = Some(SpecialPrize {
+            recipient: Pubkey::default(),
+            amount: 0,
+            reason: "r".repeat(MAX_SPECIAL_PRIZE_REASON_LEN),
+        });
+        state.leaderboard_root = Some([0; 32]);
+
+        assert_eq!(serialized_len(&state), state_space(leaderboard_size));
+    }
+}
+
+#[test]
+fn player_space_fits_a_full_player() {
+    let mut player = common::player();
+    player.deck_commitment = Some([0; 32]);
+    player.pending_hook = Some(GameEndReason::LostBet);
+    player.nickname = "n".repeat(MAX_NICKNAME_LEN);
+    player.randomness_proof = Some(RandomnessProof { proof: [0; VRF_PROOF_LEN], alpha: [0; 32] });
+    player.revealed_seed = Some(0);
+    player.last_win_competition_index = Some(0);
+    player.pending_streak_bet = Some(true);
+    player.competition_scores = vec![0; MAX_ROUNDS_PER_COMPETITION.into()];
+    player.seed_hash = Some([0; 32]);
+    player.player_seed = Some(0);
+    player.vrf_output = Some(0);
+    player.cooldown_waived_in = Some(Pubkey::default());
+    player.pending_bet = Some(PendingBet {
+        config: Pubkey::default(),
+        bet_type: BetType::High,
+        side_bet: Some(SideBetType::SuitRun { length: 3 }),
+        bet_amount: 0,
+        slot: 0,
+    });
+
+    assert_eq!(serialized_len(&player), player_space());
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use degame::*;
+
+const ANALYTICS: Pubkey = Pubkey::new_from_array([5; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+fn event_type_sent(event_type: u8) -> u8 {
+    let args = AnalyticsEventArgs { event_type, player: PLAYER, value: 7, timestamp: 9 };
+    let instruction = build_analytics_instruction(ANALYTICS, &args).unwrap();
+
+    assert_eq!(instruction.program_id, ANALYTICS);
+    assert_eq!(instruction.data[..8], ANALYTICS_RECORD_EVENT_DISCRIMINATOR);
+    assert_eq!(instruction.data[8..], args.try_to_vec().unwrap());
+    instruction.data[8]
+}
+
+#[test]
+fn record_event_carries_the_event_type() {
+    assert_eq!(event_type_sent(ANALYTICS_EVENT_JOIN), 1);
+    assert_eq!(event_type_sent(ANALYTICS_EVENT_SCORE_SUBMIT), 2);
+    assert_eq!(event_type_sent(ANALYTICS_EVENT_CLAIM), 3);
+}
+
+#[test]
+fn record_event_layout() {
+    let args = AnalyticsEventArgs { event_type: 2, player: PLAYER, value: 7, timestamp: -9 };
+    let data = build_analytics_instruction(ANALYTICS, &args).unwrap().data;
+
+    assert_eq!(
+        data[8..],
+        [&[2][..], &PLAYER.to_bytes(), &7u64.to_le_bytes(), &(-9i64).to_le_bytes()].concat()
+    );
+}
@@ -0,0 +1,56 @@
+use degame::*;
+
+mod common;
+
+fn state(pool: u64) -> State {
+    State {
+        leaderboard_size: 3,
+        finalized: true,
+        pool,
+        prize_pool: pool,
+        ..common::state()
+    }
+}
+
+fn config() -> CompetitionConfig {
+    let mut config = common::config();
+    config.prize_schedule = PrizeSchedule::STANDARD;
+    config
+}
+
+#[test]
+fn pool_add_overflow() {
+    assert_eq!(pool_with_deposit(u64::MAX, 1, 0).unwrap_err(), ErrorCode::PoolOverflow.into());
+}
+
+#[test]
+fn prize_mul_overflow() {
+    assert_eq!(
+        calculate_prize(&state(u64::MAX), &config(), 0).unwrap_err(),
+        ErrorCode::PrizeOverflow.into()
+    );
+    assert_eq!(group_member_prize(u64::MAX, 0, 3).unwrap_err(), ErrorCode::PrizeOverflow.into());
+}
+
+#[test]
+fn multiplier_mul_overflow() {
+    let mut player = common::player();
+    player.peak_multiplier = u64::MAX;
+
+    assert_eq!(calculate_score(&player, 2).unwrap_err(), ErrorCode::ScoreOverflow.into());
+}
+
+#[test]
+fn score_add_overflow() {
+    assert_eq!(
+        top_rounds_total(&mut vec![u64::MAX, 1], 2).unwrap_err(),
+        ErrorCode::ScoreOverflow.into()
+    );
+}
+
+#[test]
+fn counters_keep_the_generic_error() {
+    assert_eq!(overflow(ArithmeticOp::CounterAdd), ErrorCode::ArithmeticError.into());
+    assert_eq!(overflow(ArithmeticOp::WinningsAdd), ErrorCode::ArithmeticError.into());
+    assert_eq!(overflow(ArithmeticOp::PoolSub), ErrorCode::PoolOverflow.into());
+}
@@ -0,0 +1,15 @@
+use degame::*;
+
+#[test]
+fn first_bet_waits_for_the_delay() {
+    let received_at = 1_000;
+
+    assert!(!bet_delay_elapsed(received_at, received_at, 5));
+    assert!(!bet_delay_elapsed(received_at, received_at + 4, 5));
+    assert!(bet_delay_elapsed(received_at, received_at + 5, 5));
+}
+
+#[test]
+fn zero_delay_allows_immediate_bets() {
+    assert!(bet_delay_elapsed(1_000, 1_000, 0));
+}
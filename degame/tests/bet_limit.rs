@@ -0,0 +1,46 @@
+use degame::*;
+
+mod common;
+
+use common::game::*;
+
+const LIMIT: u8 = 3;
+
+/// Places and wins a high bet with `bets_placed` bets already made.
+fn win_bet(bets_placed: u8) -> Player {
+    let config = CompetitionConfig {
+        max_bets_per_game: LIMIT,
+        ..config()
+    };
+    let player = Player {
+        bets_placed,
+        ..dealt_player()
+    };
+    let (result, player, _) = place(player, &config, high(None), BET_SLOT);
+    assert_eq!(result, Ok(()));
+
+    let hash = winning_hash(&player, &config);
+    let (result, player) = settle_on(player, &config, hash);
+    assert_eq!(result, Ok(()));
+    player
+}
+
+#[test]
+fn game_ends_after_the_last_allowed_bet() {
+    let player = win_bet(LIMIT - 1);
+
+    assert_eq!(player.bets_placed, LIMIT);
+    assert!(player.finished);
+    assert_eq!(player.status, GameStatus::Finished);
+    assert!(player.multiplier > 1.0);
+    assert_eq!(player.revealed_seed, Some(DECK_SEED));
+}
+
+#[test]
+fn game_goes_on_below_the_limit() {
+    let player = win_bet(LIMIT - 2);
+
+    assert_eq!(player.bets_placed, LIMIT - 1);
+    assert!(!player.finished);
+    assert_eq!(player.status, GameStatus::Active);
+}
@@ -0,0 +1,37 @@
+use degame::*;
+
+fn deck(values: &[u8]) -> Vec<Card> {
+    values
+        .iter()
+        .map(|&value| Card { suit: "Spades".to_string(), value })
+        .collect()
+}
+
+#[test]
+fn high_from_a_two_always_wins() {
+    let odds = calculate_bet_odds(&deck(&[2, 14, 14, 14, 14]), 0).unwrap();
+
+    assert_eq!(
+        (odds.high_numerator, odds.low_numerator, odds.tie_numerator, odds.denominator),
+        (4, 0, 0, 4)
+    );
+    assert_eq!(odds.current_card_value, 2);
+    assert_eq!(odds.current_card_suit, "Spades");
+}
+
+#[test]
+fn odds_count_only_cards_after_the_cursor() {
+    let odds = calculate_bet_odds(&deck(&[2, 14, 9, 9, 3, 12]), 2).unwrap();
+
+    assert_eq!(
+        (odds.high_numerator, odds.low_numerator, odds.tie_numerator, odds.denominator),
+        (1, 1, 1, 3)
+    );
+}
+
+#[test]
+fn joker_wins_either_bet() {
+    let odds = calculate_bet_odds(&deck(&[JOKER_VALUE, 2, 14]), 0).unwrap();
+
+    assert_eq!((odds.high_numerator, odds.low_numerator, odds.denominator), (2, 2, 2));
+}
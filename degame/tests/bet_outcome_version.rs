@@ -0,0 +1,55 @@
+use anchor_lang::AnchorSerialize;
+use degame::*;
+
+fn v1() -> BetOutcomeV1 {
+    BetOutcomeV1 { correct: true, multiplier_gain: 15_000, side_bet_result: Some(-2) }
+}
+
+#[test]
+fn outcome_serializes_with_its_version() {
+    let outcome = BetOutcome {
+        correct: true,
+        multiplier_gain: 1.5,
+        side_bet_result: Some(-2),
+        side_bet_streak_milestone: Some(5),
+        hot_card: None,
+    };
+    let data = outcome.try_to_vec().unwrap();
+
+    assert_eq!(data[0], BET_OUTCOME_VERSION);
+    assert_eq!(data[1..], v1().try_to_vec().unwrap());
+}
+
+#[test]
+fn v1_round_trips() {
+    let data = [&[1][..], &v1().try_to_vec().unwrap()].concat();
+    let outcome = decode_bet_outcome(&data).unwrap();
+
+    assert!(outcome.correct);
+    assert_eq!(outcome.multiplier_gain, 1.5);
+    assert_eq!(outcome.side_bet_result, Some(-2));
+    assert_eq!(outcome.side_bet_streak_milestone, None);
+}
+
+#[test]
+fn unknown_fields_of_a_later_version_are_ignored() {
+    let data = [&[2][..], &v1().try_to_vec().unwrap(), &[0xAB; 12]].concat();
+    let outcome = decode_bet_outcome(&data).unwrap();
+
+    assert_eq!(BetOutcomeV1::from(&outcome), v1());
+}
+
+#[test]
+fn malformed_outcomes_are_rejected() {
+    let v1 = v1().try_to_vec().unwrap();
+
+    assert_eq!(decode_bet_outcome(&[]).err(), Some(ErrorCode::InvalidBetOutcome.into()));
+    assert_eq!(
+        decode_bet_outcome(&[&[0][..], &v1].concat()).err(),
+        Some(ErrorCode::InvalidBetOutcome.into())
+    );
+    assert_eq!(
+        decode_bet_outcome(&[&[1][..], &v1[..3]].concat()).err(),
+        Some(ErrorCode::InvalidBetOutcome.into())
+    );
+}
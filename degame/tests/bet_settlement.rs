@@ -0,0 +1,144 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::game::*;
+use common::program::failure;
+
+fn place_bet(player: Player, slot: u64) -> (ProgramResult, Player) {
+    let (result, player, _) = place(player, &config(), high(None), slot);
+    (result, player)
+}
+
+fn settle_bet(
+    player: Player,
+    config_key: Pubkey,
+    hashes: Vec<u8>,
+    slot: u64,
+) -> (ProgramResult, Player) {
+    settle(player, &config(), config_key, hashes, slot)
+}
+
+fn bet_placed() -> Player {
+    let (result, player) = place_bet(dealt_player(), BET_SLOT);
+    assert_eq!(result, Ok(()));
+    player
+}
+
+#[test]
+fn arrangement_keeps_dealt_and_current_cards_in_front() {
+    let order = arrange_deck(10, 0b101, 4, Some([9; 32]));
+
+    assert_eq!(order[..3], [0, 2, 4]);
+    let mut rest = order[3..].to_vec();
+    rest.sort();
+    assert_eq!(rest, [1, 3, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn draws_reshuffle_the_undealt_cards() {
+    let first = arrange_deck(52, 0, 0, Some([1; 32]));
+    let second = arrange_deck(52, 0, 0, Some([2; 32]));
+
+    assert_ne!(first[1..], second[1..]);
+    assert_eq!(arrange_deck(5, 0b1, 1, None), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn draw_seed_depends_on_slot_hash_player_and_position() {
+    let seed = draw_seed(&[1; 32], &PLAYER, 3);
+
+    assert_ne!(seed, draw_seed(&[2; 32], &PLAYER, 3));
+    assert_ne!(seed, draw_seed(&[1; 32], &AUTHORITY, 3));
+    assert_ne!(seed, draw_seed(&[1; 32], &PLAYER, 4));
+}
+
+#[test]
+fn slot_hash_lookup() {
+    let data = slot_hashes(&[(902, [2; 32]), (900, [1; 32])]);
+
+    assert_eq!(slot_hash_at(&data, 900), Some([1; 32]));
+    assert_eq!(slot_hash_at(&data, 902), Some([2; 32]));
+    assert_eq!(slot_hash_at(&data, 901), None);
+    assert_eq!(slot_hash_at(&data[..20], 900), None);
+}
+
+#[test]
+fn record_deal_tracks_dealt_and_current_cards() {
+    let mut player = common::player();
+    player.cards_dealt = 2;
+
+    player.record_deal(&[3, 0, 5, 1]);
+
+    assert_eq!(player.dealt_mask, 0b1001);
+    assert_eq!(player.current_card, 5);
+}
+
+#[test]
+fn placing_a_bet_leaves_it_pending() {
+    let player = bet_placed();
+
+    let bet = player.pending_bet.unwrap();
+    assert_eq!((bet.config, bet.slot), (CONFIG, BET_SLOT));
+    assert_eq!(player.cards_dealt, 0);
+    assert_eq!(player.bets_placed, 0);
+}
+
+#[test]
+fn one_bet_at_a_time() {
+    let (result, _) = place_bet(bet_placed(), BET_SLOT + 1);
+
+    assert_eq!(result, failure(ErrorCode::BetPending));
+}
+
+#[test]
+fn settlement_waits_for_the_next_slot() {
+    let hashes = slot_hashes(&[(BET_SLOT, [1; 32])]);
+    let (result, _) = settle_bet(bet_placed(), CONFIG, hashes, BET_SLOT);
+
+    assert_eq!(result, failure(ErrorCode::BetNotSettleable));
+}
+
+#[test]
+fn settlement_deals_from_the_slot_hash() {
+    let hashes = slot_hashes(&[(BET_SLOT, [1; 32])]);
+    let (result, player) = settle_bet(bet_placed(), CONFIG, hashes, BET_SLOT + 1);
+
+    assert_eq!(result, Ok(()));
+    assert!(player.pending_bet.is_none());
+    assert_eq!(player.bets_placed, 1);
+    assert!(player.cards_dealt >= 1);
+    assert_eq!(
+        player.dealt_mask.count_ones(),
+        u32::from(player.cards_dealt)
+    );
+    assert_eq!(player.dealt_mask & 1 << player.current_card, 0);
+
+    // The next card comes from the slot hash, not the deck seed alone.
+    let deck = shuffle_deck(DECK_SEED, 0);
+    let draw = draw_seed(&[1; 32], &PLAYER, 0);
+    let order = arrange_deck(deck.len(), 0, 0, Some(draw));
+    let won = deck[order[1]].value > deck[0].value || deck[0].value == JOKER_VALUE;
+    assert_eq!(player.finished, !won);
+}
+
+#[test]
+fn expired_slot_hash_forfeits_the_bet() {
+    let hashes = slot_hashes(&[(BET_SLOT + 600, [1; 32])]);
+    let (result, player) = settle_bet(bet_placed(), CONFIG, hashes, BET_SLOT + 600);
+
+    assert_eq!(result, Ok(()));
+    assert!(player.finished);
+    assert!(matches!(player.status, GameStatus::Finished));
+    assert_eq!(player.cards_dealt, 0);
+}
+
+#[test]
+fn settlement_uses_the_config_the_bet_was_placed_under() {
+    let hashes = slot_hashes(&[(BET_SLOT, [1; 32])]);
+    let (result, _) = settle_bet(bet_placed(), Pubkey::new_unique(), hashes, BET_SLOT + 1);
+
+    assert_eq!(result, failure(ErrorCode::NoPendingBet));
+}
@@ -0,0 +1,104 @@
+use degame::*;
+
+mod common;
+
+const KING: u8 = 13;
+
+fn table() -> BetWindowTable {
+    let mut table = BetWindowTable { windows_seconds: [60; 13] };
+    table.windows_seconds[(KING - 2) as usize] = 5;
+    table
+}
+
+fn player_started_at(start_time: i64) -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.start_time = start_time;
+    player
+}
+
+fn deck(values: &[u8]) -> Vec<Card> {
+    values.iter().map(|&value| Card { suit: "Clubs".to_string(), value }).collect()
+}
+
+#[test]
+fn king_bet_expires_after_its_window() {
+    let player = player_started_at(1_000);
+    let deck = deck(&[KING, 4]);
+
+    assert!(!bet_window_lapsed(&player, &deck, &table(), 1_005));
+    assert!(bet_window_lapsed(&player, &deck, &table(), 1_006));
+}
+
+#[test]
+fn even_card_keeps_its_longer_window() {
+    let player = player_started_at(1_000);
+
+    assert!(!bet_window_lapsed(&player, &deck(&[7, 4]), &table(), 1_006));
+    assert!(bet_window_lapsed(&player, &deck(&[7, 4]), &table(), 1_061));
+}
+
+#[test]
+fn window_follows_the_current_card() {
+    let mut player = player_started_at(1_000);
+    player.cards_dealt = 1;
+
+    assert!(!bet_window_lapsed(&player, &deck(&[KING, 7, 4]), &table(), 1_006));
+}
+
+#[test]
+fn jokers_get_the_longest_window() {
+    assert_eq!(table().window_for(JOKER_VALUE), MAX_BET_WINDOW_SECONDS);
+}
+
+#[test]
+fn windows_must_be_within_bounds() {
+    table().validate().unwrap();
+
+    let mut short = table();
+    short.windows_seconds[0] = MIN_BET_WINDOW_SECONDS - 1;
+    assert_eq!(short.validate().unwrap_err(), ErrorCode::InvalidBetWindow.into());
+
+    let mut long = table();
+    long.windows_seconds[12] = MAX_BET_WINDOW_SECONDS + 1;
+    assert_eq!(long.validate().unwrap_err(), ErrorCode::InvalidBetWindow.into());
+}
+
+/// Bets after the window of the card showing lapsed under `policy`, with a
+/// 2x multiplier earned so far.
+fn bet_too_late(policy: BetTimeoutPolicy) -> Player {
+    use common::game::{config, dealt_player, high, place, BET_SLOT, NOW};
+
+    let config = CompetitionConfig {
+        bet_timeout_policy: policy,
+        ..config()
+    };
+    let player = Player {
+        start_time: NOW - i64::from(MAX_BET_WINDOW_SECONDS) - 1,
+        multiplier: 2.0,
+        peak_multiplier: 20_000,
+        ..dealt_player()
+    };
+
+    let (result, player, _) = place(player, &config, high(None), BET_SLOT);
+    assert_eq!(result, Ok(()));
+    assert!(player.finished);
+    assert_eq!(player.status, GameStatus::TimedOut);
+    assert!(player.pending_bet.is_none());
+    player
+}
+
+#[test]
+fn lapsed_window_finishes_the_game_with_its_score() {
+    let player = bet_too_late(BetTimeoutPolicy::FinishGame);
+
+    assert_eq!(player.multiplier, 2.0);
+    assert_eq!(player.peak_multiplier, 20_000);
+}
+
+#[test]
+fn lapsed_window_can_forfeit_the_multiplier() {
+    let player = bet_too_late(BetTimeoutPolicy::ForfeitMultiplier);
+
+    assert_eq!(player.multiplier, 0.0);
+    assert_eq!(player.peak_multiplier, 0);
+}
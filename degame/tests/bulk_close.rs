@@ -0,0 +1,143 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::entry;
+use common::program::{failure, Ledger, TestAccount, WithRemaining};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const WINNER: Pubkey = Pubkey::new_from_array([6; 32]);
+
+/// A competition whose leaderboard holds `WINNER`, with their prize
+/// `claimed` or not.
+fn ledger(claimed: bool) -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        leaderboard: vec![LeaderboardEntry {
+            claimed,
+            ..entry(WINNER, 1_000)
+        }],
+        ..common::state()
+    };
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+/// Adds a player account bound to `authority` that played in `STATE` and
+/// returns its address.
+fn add_player(ledger: &mut Ledger, authority: Pubkey, finished: bool, score_submitted: bool) -> Pubkey {
+    add_player_with(ledger, |player| {
+        player.authority = authority;
+        player.finished = finished;
+        player.score_submitted = score_submitted;
+    })
+}
+
+fn add_player_with(ledger: &mut Ledger, edit: impl FnOnce(&mut Player)) -> Pubkey {
+    let mut player = common::player();
+    player.state = STATE;
+    edit(&mut player);
+
+    let key = Pubkey::new_unique();
+    ledger.insert(TestAccount::program(key, &*player, player_space()));
+    key
+}
+
+fn bulk_close(ledger: &mut Ledger, signer: Pubkey, players: Vec<Pubkey>) -> ProgramResult {
+    ledger.process(
+        instruction::BulkClosePlayers {},
+        WithRemaining(accounts::BulkClosePlayers { state: STATE, admin: signer }, players),
+    )
+}
+
+#[test]
+fn finished_players_are_closed_to_the_admin() {
+    let mut ledger = ledger(true);
+    let players = vec![
+        add_player(&mut ledger, Pubkey::new_unique(), true, false),
+        add_player(&mut ledger, WINNER, true, true),
+    ];
+    let rent: u64 = players.iter().map(|key| ledger.get(key).lamports).sum();
+
+    assert_eq!(bulk_close(&mut ledger, ADMIN, players.clone()), Ok(()));
+    assert_eq!(ledger.get(&ADMIN).lamports, rent);
+    for key in &players {
+        let closed = ledger.get(key);
+        assert_eq!((closed.lamports, closed.owner), (0, system_program::ID));
+    }
+    assert_eq!(ledger.read::<State>(&STATE).accounts_closed, 2);
+}
+
+#[test]
+fn player_still_in_play_fails_the_batch() {
+    let mut ledger = ledger(true);
+    let players = vec![
+        add_player(&mut ledger, Pubkey::new_unique(), true, false),
+        add_player(&mut ledger, Pubkey::new_unique(), false, false),
+    ];
+
+    assert_eq!(
+        bulk_close(&mut ledger, ADMIN, players.clone()),
+        failure(ErrorCode::GameNotFinished)
+    );
+    assert_eq!(ledger.get(&players[0]).owner, degame::ID);
+}
+
+#[test]
+fn unclaimed_prize_keeps_the_winner_open() {
+    let mut ledger = ledger(false);
+    let players = vec![add_player(&mut ledger, WINNER, true, true)];
+
+    assert_eq!(
+        bulk_close(&mut ledger, ADMIN, players),
+        failure(ErrorCode::PrizeUnclaimed)
+    );
+}
+
+#[test]
+fn only_the_admin_closes_players() {
+    let mut ledger = ledger(true);
+    let stranger = Pubkey::new_unique();
+    ledger.insert(TestAccount::signer(stranger, 0));
+    let players = vec![add_player(&mut ledger, Pubkey::new_unique(), true, false)];
+
+    assert_eq!(
+        bulk_close(&mut ledger, stranger, players),
+        failure(ErrorCode::Unauthorized)
+    );
+}
+
+#[test]
+fn players_of_other_competitions_are_left_open() {
+    let mut ledger = ledger(true);
+    let players = vec![add_player_with(&mut ledger, |player| {
+        player.finished = true;
+        player.state = Pubkey::new_unique();
+    })];
+
+    assert_eq!(
+        bulk_close(&mut ledger, ADMIN, players.clone()),
+        failure(ErrorCode::PlayerNotInCompetition)
+    );
+    assert_eq!(ledger.get(&players[0]).owner, degame::ID);
+}
+
+#[test]
+fn unpaid_winnings_keep_the_player_open() {
+    let mut ledger = ledger(true);
+    let players = vec![add_player_with(&mut ledger, |player| {
+        player.finished = true;
+        player.pending_winnings = 500;
+    })];
+
+    assert_eq!(
+        bulk_close(&mut ledger, ADMIN, players),
+        failure(ErrorCode::WinningsUnpaid)
+    );
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, process, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const POOL: u64 = 10_000;
+
+/// Accounts for `cashout` by `signer` on the player bound to `AUTHORITY`,
+/// who has `pending_winnings` to collect from a pool of `POOL`.
+fn accounts(state: State, pending_winnings: u64, signer: Pubkey) -> Vec<TestAccount> {
+    let mut player = common::player();
+    player.authority = AUTHORITY;
+    player.pending_winnings = pending_winnings;
+    player.state = STATE;
+
+    vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(PLAYER, &*player, player_space()),
+        TestAccount::signer(signer, 0),
+    ]
+}
+
+fn pool() -> State {
+    State {
+        pool: POOL,
+        ..common::state()
+    }
+}
+
+fn cashout(accounts: &mut [TestAccount]) -> ProgramResult {
+    process(instruction::Cashout {}, accounts, 0)
+}
+
+#[test]
+fn winning_wager_credits_pending_winnings() {
+    let mut player = common::player();
+
+    assert_eq!(credit_winnings(&mut player, 100, 1.5).unwrap(), 150);
+    assert_eq!(credit_winnings(&mut player, 40, 2.0).unwrap(), 80);
+    assert_eq!(player.pending_winnings, 230);
+}
+
+#[test]
+fn pending_winnings_overflow_is_reported() {
+    let mut player = common::player();
+    player.pending_winnings = u64::MAX;
+
+    assert_eq!(
+        credit_winnings(&mut player, 1, 1.0).unwrap_err(),
+        ErrorCode::ArithmeticError.into()
+    );
+}
+
+#[test]
+fn cashout_pays_the_authority_from_the_pool() {
+    let mut accounts = accounts(pool(), 2_500, AUTHORITY);
+    let state_lamports = accounts[0].lamports;
+
+    assert_eq!(cashout(&mut accounts), Ok(()));
+    assert_eq!(accounts[0].read::<State>().pool, POOL - 2_500);
+    assert_eq!(accounts[0].lamports, state_lamports - 2_500);
+    assert_eq!(accounts[1].read::<Player>().pending_winnings, 0);
+    assert_eq!(accounts[2].lamports, 2_500);
+}
+
+#[test]
+fn other_signers_cannot_cash_out() {
+    let mut accounts = accounts(pool(), 2_500, Pubkey::new_unique());
+
+    assert_eq!(cashout(&mut accounts), failure(ErrorCode::Unauthorized));
+}
+
+#[test]
+fn winnings_cannot_be_cashed_out_of_another_competition() {
+    let mut accounts = accounts(pool(), 2_500, AUTHORITY);
+    accounts[0] = TestAccount::program(Pubkey::new_unique(), &pool(), state_space(MAX_LEADERBOARD_SIZE as u8));
+
+    assert_eq!(cashout(&mut accounts), failure(ErrorCode::PlayerNotInCompetition));
+}
+
+#[test]
+fn cashout_closes_at_finalization() {
+    let state = State {
+        finalized: true,
+        ..pool()
+    };
+    let mut accounts = accounts(state, 2_500, AUTHORITY);
+
+    assert_eq!(cashout(&mut accounts), failure(ErrorCode::AlreadyFinalized));
+}
+
+#[test]
+fn cashout_cannot_overdraw_the_pool() {
+    let mut accounts = accounts(pool(), POOL + 1, AUTHORITY);
+
+    assert_eq!(cashout(&mut accounts), failure(ErrorCode::InsufficientPool));
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::entry;
+use common::program::{failure, Ledger, TestAccount, WithRemaining};
+
+const NOW: i64 = 1_700_000_000;
+const FIRST: Pubkey = Pubkey::new_from_array([1; 32]);
+const SECOND: Pubkey = Pubkey::new_from_array([2; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([3; 32]);
+const PLAYER_STATS: Pubkey = Pubkey::new_from_array([4; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const WINNER: Pubkey = Pubkey::new_from_array([6; 32]);
+const POOL: u64 = 10_000;
+const BALANCE: u64 = 1_000_000_000;
+
+fn claim_escrow(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"claim_escrow", state.as_ref(), WINNER.as_ref()], &degame::ID).0
+}
+
+fn guarantee_reserve(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"guarantee_reserve", state.as_ref()], &degame::ID).0
+}
+
+/// A finalized tournament past its dispute window, with `WINNER` at
+/// `position` on its leaderboard.
+fn tournament(position: usize) -> State {
+    let mut leaderboard: Vec<_> = (0..=position).map(|_| entry(Pubkey::new_unique(), 1_000)).collect();
+    leaderboard[position].player = WINNER;
+    State {
+        admin: ADMIN,
+        config: CONFIG,
+        leaderboard_size: 3,
+        leaderboard,
+        finalized: true,
+        finalized_timestamp: NOW,
+        pool: POOL,
+        prize_pool: POOL,
+        dispute_window_bypassed: true,
+        ..common::state()
+    }
+}
+
+/// `WINNER` first in tournament `FIRST` and second in `SECOND`.
+fn ledger() -> Ledger {
+    let config = CompetitionConfig::new(common::config_args()).unwrap();
+    let mut player = common::player();
+    player.authority = WINNER;
+
+    let mut ledger = Ledger::new();
+    ledger.now = NOW;
+    for (key, position) in [(FIRST, 0), (SECOND, 1)] {
+        let mut state =
+            TestAccount::program(key, &tournament(position), state_space(MAX_LEADERBOARD_SIZE as u8));
+        state.lamports += POOL;
+        ledger.insert(state);
+    }
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::program(PLAYER_STATS, &*player, player_space()));
+    ledger.insert(TestAccount::signer(WINNER, BALANCE));
+    ledger
+}
+
+fn claim_all(ledger: &mut Ledger, claims: &[(Pubkey, u8)]) -> ProgramResult {
+    ledger.process(
+        instruction::ClaimAll {
+            claims: claims.iter().map(|&(state, position)| TournamentClaim { state, position }).collect(),
+        },
+        WithRemaining(
+            accounts::ClaimAll {
+                player: WINNER,
+                player_stats: PLAYER_STATS,
+                analytics_program: None,
+                system_program: system_program::ID,
+            },
+            claims
+                .iter()
+                .flat_map(|&(state, _)| {
+                    [state, CONFIG, ADMIN, claim_escrow(&state), guarantee_reserve(&state)]
+                })
+                .collect(),
+        ),
+    )
+}
+
+fn prize(ledger: &Ledger, state: &Pubkey, position: u8) -> u64 {
+    let config = ledger.read::<CompetitionConfig>(&CONFIG);
+    calculate_prize(&ledger.read::<State>(state), &config, position).unwrap().1
+}
+
+fn owed(ledger: &Ledger, state: &Pubkey) -> u64 {
+    ledger.read::<ClaimEscrow>(&claim_escrow(state)).owed
+}
+
+#[test]
+fn prizes_from_several_tournaments_are_claimed_together() {
+    let mut ledger = ledger();
+    let expected = [prize(&ledger, &FIRST, 0), prize(&ledger, &SECOND, 1)];
+
+    assert_eq!(claim_all(&mut ledger, &[(FIRST, 0), (SECOND, 1)]), Ok(()));
+    assert_eq!([owed(&ledger, &FIRST), owed(&ledger, &SECOND)], expected);
+    assert!(ledger.read::<State>(&FIRST).leaderboard[0].claimed);
+    assert!(ledger.read::<State>(&SECOND).leaderboard[1].claimed);
+}
+
+#[test]
+fn claimed_prizes_are_withdrawn_from_each_escrow() {
+    let mut ledger = ledger();
+    assert_eq!(claim_all(&mut ledger, &[(FIRST, 0)]), Ok(()));
+    let balance = ledger.get(&WINNER).lamports;
+    let expected = owed(&ledger, &FIRST);
+
+    let withdrawn = ledger.process(
+        instruction::WithdrawClaim {},
+        accounts::WithdrawClaim {
+            state: FIRST,
+            owner: WINNER,
+            claim_escrow: claim_escrow(&FIRST),
+            wallet: WINNER,
+            payout_wallet: None,
+        },
+    );
+    assert_eq!(withdrawn, Ok(()));
+    assert_eq!(ledger.get(&WINNER).lamports, balance + expected);
+    assert_eq!(owed(&ledger, &FIRST), 0);
+}
+
+#[test]
+fn claim_with_another_escrow_is_refused() {
+    let mut ledger = ledger();
+    let result = ledger.process(
+        instruction::ClaimAll {
+            claims: vec![TournamentClaim { state: FIRST, position: 0 }],
+        },
+        WithRemaining(
+            accounts::ClaimAll {
+                player: WINNER,
+                player_stats: PLAYER_STATS,
+                analytics_program: None,
+                system_program: system_program::ID,
+            },
+            vec![FIRST, CONFIG, ADMIN, claim_escrow(&SECOND), guarantee_reserve(&FIRST)],
+        ),
+    );
+
+    assert_eq!(result, failure(ErrorCode::ClaimAccountsMismatch));
+}
+
+#[test]
+fn claimed_prize_is_not_paid_again() {
+    let mut ledger = ledger();
+    assert_eq!(claim_all(&mut ledger, &[(FIRST, 0)]), Ok(()));
+
+    assert_eq!(
+        claim_all(&mut ledger, &[(SECOND, 1), (FIRST, 0)]),
+        failure(ErrorCode::PrizeAlreadyClaimed)
+    );
+    assert!(!ledger.read::<State>(&SECOND).leaderboard[1].claimed);
+}
+
+#[test]
+fn tournament_repeated_in_one_batch_pays_once() {
+    let mut ledger = ledger();
+
+    assert_eq!(
+        claim_all(&mut ledger, &[(FIRST, 0), (FIRST, 0)]),
+        failure(ErrorCode::PrizeAlreadyClaimed)
+    );
+    assert_eq!(ledger.get(&WINNER).lamports, BALANCE);
+    assert_eq!(ledger.get(&claim_escrow(&FIRST)).lamports, 0);
+}
+
+#[test]
+fn batch_claim_pays_the_fee_and_guarantee_like_a_single_claim() {
+    const RESERVE: u64 = 50_000;
+    const FLOOR: u64 = 8_000;
+
+    let mut ledger = ledger();
+    let mut config = ledger.read::<CompetitionConfig>(&CONFIG);
+    config.withdrawal_fee_bps = 100;
+    config.minimum_prize_guarantee[0] = FLOOR;
+    ledger.insert(TestAccount::program(CONFIG, &*config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    let reserve = GuaranteeReserve { state: FIRST, balance: RESERVE };
+    let mut reserve =
+        TestAccount::program(guarantee_reserve(&FIRST), &reserve, VERSIONED_HEADER_LEN + GuaranteeReserve::LEN);
+    reserve.lamports += RESERVE;
+    ledger.insert(reserve);
+    let (gross_prize, net_prize) = calculate_prize(&ledger.read::<State>(&FIRST), &config, 0).unwrap();
+
+    assert_eq!(claim_all(&mut ledger, &[(FIRST, 0)]), Ok(()));
+    assert_eq!(owed(&ledger, &FIRST), FLOOR);
+    assert_eq!(ledger.get(&ADMIN).lamports, gross_prize - net_prize);
+    assert_eq!(
+        ledger.read::<GuaranteeReserve>(&guarantee_reserve(&FIRST)).balance,
+        RESERVE - (FLOOR - net_prize)
+    );
+}
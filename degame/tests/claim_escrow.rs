@@ -0,0 +1,59 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+const OWNER: Pubkey = Pubkey::new_from_array([1; 32]);
+const PAYOUT: Pubkey = Pubkey::new_from_array([2; 32]);
+const STRANGER: Pubkey = Pubkey::new_from_array([3; 32]);
+
+fn escrow() -> ClaimEscrow {
+    ClaimEscrow {
+        state: Pubkey::default(),
+        owner: OWNER,
+        owed: 0,
+    }
+}
+
+#[test]
+fn claim_then_withdraw_pays_everything_owed() {
+    let mut escrow = escrow();
+    escrow.record(6_000).unwrap();
+    escrow.record(1_000).unwrap();
+
+    assert_eq!(escrow.withdraw(&OWNER, None).unwrap(), 7_000);
+    assert_eq!(escrow.owed, 0);
+    assert_eq!(
+        escrow.withdraw(&OWNER, None).unwrap_err(),
+        ErrorCode::NothingToWithdraw.into()
+    );
+}
+
+#[test]
+fn registered_payout_wallet_can_receive_the_withdrawal() {
+    let mut escrow = escrow();
+    escrow.record(5_000).unwrap();
+
+    assert_eq!(escrow.withdraw(&PAYOUT, Some(PAYOUT)).unwrap(), 5_000);
+}
+
+#[test]
+fn failed_withdraw_keeps_the_amount_owed_for_a_retry() {
+    let mut escrow = escrow();
+    escrow.record(5_000).unwrap();
+
+    assert_eq!(
+        escrow.withdraw(&STRANGER, Some(PAYOUT)).unwrap_err(),
+        ErrorCode::InvalidPayoutWallet.into()
+    );
+    assert_eq!(escrow.owed, 5_000);
+
+    assert_eq!(escrow.withdraw(&OWNER, Some(PAYOUT)).unwrap(), 5_000);
+    assert_eq!(escrow.owed, 0);
+}
+
+#[test]
+fn recording_past_u64_max_overflows() {
+    let mut escrow = escrow();
+    escrow.record(u64::MAX).unwrap();
+
+    assert_eq!(escrow.record(1).unwrap_err(), ErrorCode::ArithmeticError.into());
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::entry;
+use common::program::{failure, return_data, Ledger, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER_STATS: Pubkey = Pubkey::new_from_array([3; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const WINNER: Pubkey = Pubkey::new_from_array([6; 32]);
+const POOL: u64 = 10_000;
+
+fn claim_escrow() -> Pubkey {
+    Pubkey::find_program_address(&[b"claim_escrow", STATE.as_ref(), WINNER.as_ref()], &degame::ID).0
+}
+
+fn config() -> CompetitionConfig {
+    CompetitionConfig::new(common::config_args()).unwrap()
+}
+
+/// A competition finalized with `WINNER` in first place, past its dispute
+/// window.
+fn ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        leaderboard_size: 3,
+        leaderboard: vec![entry(WINNER, 1_000)],
+        finalized: true,
+        finalized_timestamp: NOW,
+        pool: POOL,
+        prize_pool: POOL,
+        dispute_window_bypassed: true,
+        ..common::state()
+    };
+    let mut player = common::player();
+    player.authority = WINNER;
+
+    let mut ledger = Ledger::new();
+    ledger.now = NOW;
+    let mut state = TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8));
+    state.lamports += POOL;
+    ledger.insert(state);
+    ledger.insert(TestAccount::program(CONFIG, &config(), VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::program(PLAYER_STATS, &*player, player_space()));
+    ledger.insert(TestAccount::signer(WINNER, 1_000_000_000));
+    ledger
+}
+
+fn claim(ledger: &mut Ledger, position: u8) -> ProgramResult {
+    ledger.process(
+        instruction::ClaimPrize { position },
+        accounts::ClaimPrize {
+            state: STATE,
+            config: CONFIG,
+            player: WINNER,
+            claim_escrow: claim_escrow(),
+            admin: ADMIN,
+            player_stats: PLAYER_STATS,
+            analytics_program: None,
+            guarantee_reserve: None,
+            system_program: system_program::ID,
+        },
+    )
+}
+
+#[test]
+fn claim_parks_the_prize_in_escrow() {
+    let mut ledger = ledger();
+
+    assert_eq!(claim(&mut ledger, 0), Ok(()));
+    // First place takes half the pool, with no withdrawal fee.
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, POOL / 2);
+    assert!(ledger.read::<State>(&STATE).leaderboard[0].claimed);
+}
+
+#[test]
+fn prize_is_claimed_only_once() {
+    let mut ledger = ledger();
+    assert_eq!(claim(&mut ledger, 0), Ok(()));
+    let owed = ledger.read::<ClaimEscrow>(&claim_escrow()).owed;
+
+    assert_eq!(claim(&mut ledger, 0), failure(ErrorCode::PrizeAlreadyClaimed));
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, owed);
+}
+
+#[test]
+fn preview_matches_the_claimed_amount() {
+    let mut ledger = ledger();
+    let mut config = config();
+    config.withdrawal_fee_bps = 100;
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+
+    let preview = ledger.process(
+        instruction::PreviewPrize { position: 0 },
+        accounts::PreviewPrize { state: STATE, config: CONFIG },
+    );
+    assert_eq!(preview, Ok(()));
+    let previewed: u64 = return_data();
+    assert_eq!(previewed, POOL / 2 - POOL / 2 / 100);
+
+    assert_eq!(claim(&mut ledger, 0), Ok(()));
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, previewed);
+}
+
+#[test]
+fn first_place_claim_records_the_win() {
+    let mut ledger = ledger();
+
+    assert_eq!(claim(&mut ledger, 0), Ok(()));
+    let competition_index = ledger.read::<State>(&STATE).competition_index;
+    assert_eq!(
+        ledger.read::<Player>(&PLAYER_STATS).last_win_competition_index,
+        Some(competition_index)
+    );
+}
+
+/// `ledger` with prizes payable only from pools of `min_pool_for_payout`.
+fn ledger_with_min_pool(min_pool_for_payout: u64) -> Ledger {
+    let mut ledger = ledger();
+    let config = CompetitionConfig { min_pool_for_payout, ..config() };
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn reclaim(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::ReclaimUndersizedPool {},
+        accounts::ReclaimUndersizedPool {
+            state: STATE,
+            config: CONFIG,
+            admin: ADMIN,
+        },
+    )
+}
+
+#[test]
+fn undersized_pool_pays_no_prizes() {
+    let mut ledger = ledger_with_min_pool(POOL + 1);
+
+    assert_eq!(claim(&mut ledger, 0), failure(ErrorCode::PoolBelowMinimum));
+    assert!(!ledger.read::<State>(&STATE).leaderboard[0].claimed);
+}
+
+#[test]
+fn admin_reclaims_an_undersized_pool() {
+    let mut ledger = ledger_with_min_pool(POOL + 1);
+
+    assert_eq!(reclaim(&mut ledger), Ok(()));
+    assert_eq!(ledger.read::<State>(&STATE).pool, 0);
+    assert_eq!(ledger.get(&ADMIN).lamports, POOL);
+}
+
+#[test]
+fn pool_at_the_minimum_pays_out() {
+    let mut ledger = ledger_with_min_pool(POOL);
+
+    assert_eq!(reclaim(&mut ledger), failure(ErrorCode::PoolAboveMinimum));
+    assert_eq!(claim(&mut ledger, 0), Ok(()));
+}
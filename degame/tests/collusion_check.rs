@@ -0,0 +1,29 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+const ALICE: Pubkey = Pubkey::new_from_array([1; 32]);
+const BOB: Pubkey = Pubkey::new_from_array([2; 32]);
+const SHARED: Pubkey = Pubkey::new_from_array([9; 32]);
+
+#[test]
+fn entries_sharing_a_payout_wallet_are_flagged() {
+    let wallets = [SHARED, ALICE, SHARED];
+
+    assert_eq!(shared_payout_wallets(&wallets), vec![(SHARED, vec![0, 2])]);
+}
+
+#[test]
+fn distinct_payout_wallets_are_not_flagged() {
+    assert!(shared_payout_wallets(&[ALICE, BOB, SHARED]).is_empty());
+    assert!(shared_payout_wallets(&[]).is_empty());
+}
+
+#[test]
+fn each_shared_wallet_is_reported_once() {
+    let wallets = [ALICE, BOB, ALICE, BOB, ALICE];
+
+    assert_eq!(
+        shared_payout_wallets(&wallets),
+        vec![(ALICE, vec![0, 2, 4]), (BOB, vec![1, 3])]
+    );
+}
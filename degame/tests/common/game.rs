@@ -0,0 +1,157 @@
+//! A dealt game to bet on through `place_bet` and `settle_bet`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::sysvar;
+use degame::*;
+
+use super::program::{process_in_slot, TestAccount};
+
+pub const NOW: i64 = 1_700_000_000;
+pub const BET_SLOT: u64 = 900;
+pub const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+pub const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+pub const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+pub const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+pub const DECK_SEED: u64 = 7;
+
+/// Raw `SlotHashes` data holding `entries`.
+pub fn slot_hashes(entries: &[(u64, [u8; 32])]) -> Vec<u8> {
+    let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+    for (slot, hash) in entries {
+        data.extend_from_slice(&slot.to_le_bytes());
+        data.extend_from_slice(hash);
+    }
+    data
+}
+
+/// Config with every bet window at its longest and everything else zeroed.
+pub fn config() -> CompetitionConfig {
+    let mut config = super::config();
+    config.bet_window_table.windows_seconds = [MAX_BET_WINDOW_SECONDS; 13];
+    config
+}
+
+/// Player bound to `AUTHORITY` whose standard game in `STATE` was dealt
+/// from `DECK_SEED`, with no bet placed yet.
+pub fn dealt_player() -> Player {
+    let mut player = super::player();
+    player.authority = AUTHORITY;
+    player.state = STATE;
+    player.start_time = NOW;
+    player.multiplier = 1.0;
+    player.status = GameStatus::Active;
+    player.deck_commitment = Some(commit_seed(DECK_SEED));
+    player.vrf_output = Some(DECK_SEED);
+    player.player_seed = Some(0);
+    (*player).clone()
+}
+
+pub fn player_account(player: Player) -> TestAccount {
+    let mut versioned = super::player();
+    *versioned = player;
+    TestAccount::program(PLAYER, &*versioned, player_space())
+}
+
+pub fn config_account(key: Pubkey, config: &CompetitionConfig) -> TestAccount {
+    TestAccount::program(key, config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN)
+}
+
+/// Places `bet` under `config` in `slot` and returns the result with the
+/// player and the competition afterwards.
+pub fn place(
+    player: Player,
+    config: &CompetitionConfig,
+    bet: instruction::PlaceBet,
+    slot: u64,
+) -> (ProgramResult, Player, State) {
+    let state = State {
+        config: CONFIG,
+        ..super::state()
+    };
+    let mut accounts = vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        config_account(CONFIG, config),
+        player_account(player),
+        TestAccount::signer(AUTHORITY, 1_000_000),
+        TestAccount::missing(Pubkey::new_unique()),
+        TestAccount::system_program(),
+    ];
+
+    let result = process_in_slot(bet, &mut accounts, NOW, slot);
+    (
+        result,
+        (*accounts[2].read::<Player>()).clone(),
+        (*accounts[0].read::<State>()).clone(),
+    )
+}
+
+/// A wager-free high bet with an optional side bet.
+pub fn high(side_bet: Option<SideBetType>) -> instruction::PlaceBet {
+    instruction::PlaceBet {
+        bet_type: BetType::High,
+        side_bet,
+        bet_amount: 0,
+    }
+}
+
+/// Settles the pending bet under the config at `config_key` in `slot`, with
+/// `hashes` as the `SlotHashes` sysvar.
+pub fn settle(
+    player: Player,
+    config: &CompetitionConfig,
+    config_key: Pubkey,
+    hashes: Vec<u8>,
+    slot: u64,
+) -> (ProgramResult, Player) {
+    let mut slot_hashes = TestAccount::missing(sysvar::slot_hashes::ID);
+    slot_hashes.data = hashes;
+    let mut accounts = vec![
+        player_account(player),
+        config_account(config_key, config),
+        slot_hashes,
+        TestAccount::missing(sysvar::instructions::ID),
+    ];
+
+    let result = process_in_slot(instruction::SettleBet {}, &mut accounts, NOW, slot);
+    (result, (*accounts[0].read::<Player>()).clone())
+}
+
+/// Settles the pending bet in the slot after it was placed, on `hash`.
+pub fn settle_on(player: Player, config: &CompetitionConfig, hash: [u8; 32]) -> (ProgramResult, Player) {
+    let slot = player.pending_bet.as_ref().unwrap().slot;
+    settle(player, config, CONFIG, slot_hashes(&[(slot, hash)]), slot + 1)
+}
+
+/// The deck `settle_bet` deals from when the pending bet's slot hash is
+/// `hash`; the bet compares cards `cards_dealt` and `cards_dealt + 1`.
+pub fn settled_deck(player: &Player, config: &CompetitionConfig, hash: [u8; 32]) -> Vec<Card> {
+    let deck = shuffle_deck(player.deck_seed().unwrap(), config.joker_probability_bps);
+    let draw = draw_seed(&hash, &PLAYER, player.cards_dealt);
+    let order = arrange_deck(deck.len(), player.dealt_mask, player.current_card, Some(draw));
+    order.iter().map(|&index| deck[index].clone()).collect()
+}
+
+/// A slot hash on which the pending bet compares a `current` and `next` card
+/// matching `deal`.
+pub fn hash_dealing(
+    player: &Player,
+    config: &CompetitionConfig,
+    deal: impl Fn(&Card, &Card) -> bool,
+) -> [u8; 32] {
+    let at = player.cards_dealt as usize;
+    (0..=u8::MAX)
+        .map(|byte| [byte; 32])
+        .find(|hash| {
+            let deck = settled_deck(player, config, *hash);
+            deal(&deck[at], &deck[at + 1])
+        })
+        .unwrap()
+}
+
+/// A slot hash on which a pending high bet wins without a joker.
+pub fn winning_hash(player: &Player, config: &CompetitionConfig) -> [u8; 32] {
+    hash_dealing(player, config, |current, next| {
+        current.value != JOKER_VALUE && next.value > current.value
+    })
+}
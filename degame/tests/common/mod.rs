@@ -0,0 +1,64 @@
+//! Fixtures shared by the integration tests. Every builder decodes an
+//! all-zero account body, so a new field needs no change here or in the
+//! tests; set only the fields a test is about, e.g.
+//! `State { finalized: true, ..common::state() }`.
+#![allow(dead_code)]
+
+pub mod game;
+pub mod program;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use degame::*;
+
+/// Versioned account around `T` as stored on chain, decoded from its body.
+pub fn versioned<T: AnchorDeserialize + Discriminator>(body: &[u8]) -> VersionedAccount<T> {
+    let data = [&T::DISCRIMINATOR[..], &[LAYOUT_VERSION], body].concat();
+    VersionedAccount::try_deserialize(&mut &data[..]).unwrap()
+}
+
+/// Competition with every field zeroed and an empty leaderboard.
+pub fn state() -> State {
+    State::deserialize(&mut &[0; State::BASE_LEN][..]).unwrap()
+}
+
+pub fn versioned_state() -> VersionedAccount<State> {
+    versioned(&[0; State::BASE_LEN])
+}
+
+/// All-zero config.
+pub fn config() -> CompetitionConfig {
+    CompetitionConfig::deserialize(&mut &[0; CompetitionConfig::LEN][..]).unwrap()
+}
+
+/// Player that has never started a game.
+pub fn player() -> VersionedAccount<Player> {
+    versioned(&[0; Player::LEN])
+}
+
+pub fn entry(player: Pubkey, score: u64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        player,
+        nickname: String::new(),
+        score,
+        side_bet_score: 0,
+        claimed: false,
+        finished_at: 0,
+    }
+}
+
+/// Settings that pass `CompetitionConfigArgs::validate`: one-lamport entry
+/// fee, standard prize schedule, the smallest allowed windows and a single
+/// round, with everything else zeroed.
+pub fn config_args() -> CompetitionConfigArgs {
+    let mut args =
+        CompetitionConfigArgs::deserialize(&mut &[0; CompetitionConfig::LEN][..]).unwrap();
+    args.entry_fee = 1;
+    args.score_formula.multiplier_weight = BPS_DENOMINATOR as u16;
+    args.prize_schedule = PrizeSchedule::STANDARD;
+    args.bet_window_table.windows_seconds = [MIN_BET_WINDOW_SECONDS; 13];
+    args.starting_multiplier_bps = BPS_DENOMINATOR;
+    args.prize_claim_window_hours = MIN_PRIZE_CLAIM_WINDOW_HOURS;
+    args.rounds_per_competition = 1;
+    args
+}
@@ -0,0 +1,367 @@
+//! Runs instructions through the program entrypoint against in-memory
+//! accounts, laid out the way the runtime passes them. The clock, rent,
+//! return data, event logs and the system program's transfers and account
+//! creation are stubbed; nothing else the runtime does is.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{
+    deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use degame::*;
+
+thread_local! {
+    static NOW: Cell<i64> = const { Cell::new(0) };
+    static SLOT: Cell<u64> = const { Cell::new(0) };
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            slot: SLOT.get(),
+            unix_timestamp: NOW.get(),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with_borrow_mut(|events| events.push(fields.concat()));
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.set(data.to_vec());
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        Some((degame::ID, RETURN_DATA.with_borrow(Clone::clone)))
+    }
+
+    /// Carries out system program transfers and account creation; any other
+    /// call fails.
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        const CREATE_ACCOUNT: [u8; 4] = 0u32.to_le_bytes();
+        const TRANSFER: [u8; 4] = 2u32.to_le_bytes();
+        if instruction.program_id != System::id() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let word = |at: usize| u64::from_le_bytes(instruction.data[at..at + 8].try_into().unwrap());
+        let find = |key: &Pubkey| account_infos.iter().find(|info| info.key == key).unwrap();
+        let (from, to) = (
+            find(&instruction.accounts[0].pubkey),
+            find(&instruction.accounts[1].pubkey),
+        );
+        if !from.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let amount = word(4);
+        let balance = from.lamports();
+        **from.try_borrow_mut_lamports()? = balance
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **to.try_borrow_mut_lamports()? += amount;
+
+        match instruction.data[..4].try_into().unwrap() {
+            TRANSFER => Ok(()),
+            CREATE_ACCOUNT => {
+                to.realloc(word(12) as usize, true)?;
+                to.assign(&Pubkey::new_from_array(instruction.data[20..52].try_into().unwrap()));
+                Ok(())
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+}
+
+impl TestAccount {
+    /// Program account of `space` bytes, header included, holding `body`.
+    pub fn program<T: AnchorSerialize + Discriminator>(
+        key: Pubkey,
+        body: &T,
+        space: usize,
+    ) -> Self {
+        let mut data = [&T::DISCRIMINATOR[..], &[LAYOUT_VERSION]].concat();
+        body.serialize(&mut data).unwrap();
+        data.resize(space, 0);
+        TestAccount {
+            key,
+            owner: degame::ID,
+            lamports: 1_000_000,
+            data,
+            ..Self::missing(key)
+        }
+    }
+
+    /// Address with no account behind it.
+    pub fn missing(key: Pubkey) -> Self {
+        TestAccount {
+            key,
+            owner: System::id(),
+            lamports: 0,
+            data: vec![],
+            is_signer: false,
+            is_writable: true,
+            executable: false,
+        }
+    }
+
+    /// Wallet signing the transaction.
+    pub fn signer(key: Pubkey, lamports: u64) -> Self {
+        TestAccount {
+            lamports,
+            is_signer: true,
+            ..Self::missing(key)
+        }
+    }
+
+    pub fn executable(key: Pubkey) -> Self {
+        TestAccount {
+            executable: true,
+            is_writable: false,
+            ..Self::missing(key)
+        }
+    }
+
+    /// Passed in place of an optional account that is left out.
+    pub fn none() -> Self {
+        Self::executable(degame::ID)
+    }
+
+    pub fn system_program() -> Self {
+        Self::executable(System::id())
+    }
+
+    pub fn read<T: AnchorDeserialize + Discriminator>(&self) -> VersionedAccount<T> {
+        super::versioned(&self.data[VERSIONED_HEADER_LEN..])
+    }
+}
+
+/// Runs `instruction` at unix time `now` and returns the program's result.
+pub fn process(
+    instruction: impl InstructionData,
+    accounts: &mut [TestAccount],
+    now: i64,
+) -> ProgramResult {
+    process_in_slot(instruction, accounts, now, 0)
+}
+
+/// `process` in `slot`.
+pub fn process_in_slot(
+    instruction: impl InstructionData,
+    accounts: &mut [TestAccount],
+    now: i64,
+    slot: u64,
+) -> ProgramResult {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+    NOW.set(now);
+    SLOT.set(slot);
+    RETURN_DATA.set(Vec::new());
+    EVENTS.set(Vec::new());
+
+    let (mut input, keys) = serialize(accounts, &instruction.data());
+    let result = {
+        let (program_id, infos, data) = unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+        degame::entry(program_id, &infos, data)
+    };
+
+    let bytes: Vec<u8> = input.iter().flat_map(|word| word.to_ne_bytes()).collect();
+    let word = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+    for (position, (account, &key)) in accounts.iter_mut().zip(&keys).enumerate() {
+        if keys[..position].contains(&key) {
+            continue;
+        }
+        account.owner = Pubkey::new_from_array(bytes[key + 32..key + 64].try_into().unwrap());
+        account.lamports = word(key + 64);
+        let data_len = word(key + 72) as usize;
+        account.data = bytes[key + 80..key + 80 + data_len].to_vec();
+    }
+    result
+}
+
+/// The data the last processed instruction returned, decoded as `T`.
+pub fn return_data<T: AnchorDeserialize>() -> T {
+    T::deserialize(&mut &RETURN_DATA.with_borrow(Clone::clone)[..]).unwrap()
+}
+
+/// Events of type `E` the last processed instruction emitted.
+pub fn emitted<E: AnchorDeserialize + Discriminator>() -> Vec<E> {
+    EVENTS.with_borrow(|events| {
+        events
+            .iter()
+            .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+            .map(|data| E::deserialize(&mut &data[8..]).unwrap())
+            .collect()
+    })
+}
+
+/// Lays `accounts` and `data` out the way the runtime passes them to a
+/// program, in words so the layout is aligned. Also returns the offset of
+/// each account's key, which its owner, lamports and data follow. An
+/// account passed again refers back to its first position.
+fn serialize(accounts: &[TestAccount], data: &[u8]) -> (Vec<u64>, Vec<usize>) {
+    let mut bytes = (accounts.len() as u64).to_le_bytes().to_vec();
+    let mut keys = Vec::with_capacity(accounts.len());
+    for (position, account) in accounts.iter().enumerate() {
+        if let Some(first) = accounts[..position].iter().position(|other| other.key == account.key) {
+            bytes.extend([first as u8, 0, 0, 0, 0, 0, 0, 0]);
+            keys.push(keys[first]);
+            continue;
+        }
+        bytes.extend([
+            NON_DUP_MARKER,
+            account.is_signer.into(),
+            account.is_writable.into(),
+            account.executable.into(),
+        ]);
+        bytes.extend([0; 4]);
+        keys.push(bytes.len());
+        bytes.extend(account.key.to_bytes());
+        bytes.extend(account.owner.to_bytes());
+        bytes.extend(account.lamports.to_le_bytes());
+        bytes.extend((account.data.len() as u64).to_le_bytes());
+        bytes.extend(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        bytes.extend(0u64.to_le_bytes());
+    }
+    bytes.extend((data.len() as u64).to_le_bytes());
+    bytes.extend(data);
+    bytes.extend(degame::ID.to_bytes());
+    bytes.resize(bytes.len().next_multiple_of(8), 0);
+
+    let words = bytes
+        .chunks(8)
+        .map(|word| u64::from_ne_bytes(word.try_into().unwrap()))
+        .collect();
+    (words, keys)
+}
+
+/// Accounts that live on across instructions, by address. Addresses it
+/// holds no account for are empty system accounts.
+pub struct Ledger {
+    accounts: HashMap<Pubkey, TestAccount>,
+    pub now: i64,
+    pub slot: u64,
+}
+
+impl Ledger {
+    /// Holds the system program, this program and the rent sysvar.
+    pub fn new() -> Self {
+        let mut rent = TestAccount::missing(sysvar::rent::ID);
+        let Rent { lamports_per_byte_year, exemption_threshold, burn_percent } = Rent::default();
+        rent.data = [
+            &lamports_per_byte_year.to_le_bytes()[..],
+            &exemption_threshold.to_le_bytes(),
+            &[burn_percent],
+        ]
+        .concat();
+
+        let mut ledger = Ledger {
+            accounts: HashMap::new(),
+            now: 0,
+            slot: 0,
+        };
+        ledger.insert(TestAccount::system_program());
+        ledger.insert(TestAccount::executable(degame::ID));
+        ledger.insert(rent);
+        ledger
+    }
+
+    pub fn insert(&mut self, account: TestAccount) {
+        self.accounts.insert(account.key, account);
+    }
+
+    pub fn get(&self, key: &Pubkey) -> TestAccount {
+        self.accounts
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| TestAccount::missing(*key))
+    }
+
+    pub fn read<T: AnchorDeserialize + Discriminator>(&self, key: &Pubkey) -> VersionedAccount<T> {
+        self.get(key).read()
+    }
+
+    /// Runs `instruction` on the accounts `accounts` names, signed and
+    /// writable as it marks them, at the ledger's time and slot. Changes are
+    /// kept only if it succeeds, as on chain.
+    pub fn process(
+        &mut self,
+        instruction: impl InstructionData,
+        accounts: impl ToAccountMetas,
+    ) -> ProgramResult {
+        // An account passed more than once is signed and writable if any of
+        // its positions are.
+        let metas = accounts.to_account_metas(None);
+        let flag = |key: &Pubkey, flag: fn(&AccountMeta) -> bool| {
+            metas.iter().any(|meta| meta.pubkey == *key && flag(meta))
+        };
+        let mut passed: Vec<TestAccount> = metas
+            .iter()
+            .map(|meta| TestAccount {
+                is_signer: flag(&meta.pubkey, |meta| meta.is_signer),
+                is_writable: flag(&meta.pubkey, |meta| meta.is_writable),
+                ..self.get(&meta.pubkey)
+            })
+            .collect();
+
+        process_in_slot(instruction, &mut passed, self.now, self.slot)?;
+        for account in passed.into_iter().rev() {
+            self.insert(account);
+        }
+        Ok(())
+    }
+}
+
+/// Instruction accounts followed by writable remaining accounts.
+pub struct WithRemaining<A>(pub A, pub Vec<Pubkey>);
+
+impl<A: ToAccountMetas> ToAccountMetas for WithRemaining<A> {
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        let mut metas = self.0.to_account_metas(is_signer);
+        metas.extend(self.1.iter().map(|key| AccountMeta::new(*key, false)));
+        metas
+    }
+}
+
+/// The error `process` returns when the program fails with `error`.
+pub fn failure(error: degame::ErrorCode) -> ProgramResult {
+    Err(ProgramError::Custom(error.into()))
+}
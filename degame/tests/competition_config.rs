@@ -0,0 +1,81 @@
+use degame::*;
+
+mod common;
+
+/// Puts one setting out of range.
+type Corruption = fn(&mut CompetitionConfigArgs);
+
+#[test]
+fn new_copies_the_settings() {
+    let mut args = common::config_args();
+    args.entry_fee = 5_000;
+    args.max_bets_per_game = 12;
+    args.dispute_window_hours = DEFAULT_DISPUTE_WINDOW_HOURS;
+
+    let config = CompetitionConfig::new(args).unwrap();
+
+    assert_eq!(config.entry_fee, 5_000);
+    assert_eq!(config.max_bets_per_game, 12);
+    assert_eq!(config.dispute_window_hours, DEFAULT_DISPUTE_WINDOW_HOURS);
+}
+
+#[test]
+fn new_emits_every_event() {
+    let config = CompetitionConfig::new(common::config_args()).unwrap();
+
+    assert_eq!(config.event_emission_mask, u8::MAX);
+}
+
+#[test]
+fn streak_bonus_defaults_when_unset() {
+    let config = CompetitionConfig::new(common::config_args()).unwrap();
+    assert_eq!(config.side_bet_streak_bonus, DEFAULT_SIDE_BET_STREAK_BONUS);
+
+    let mut args = common::config_args();
+    args.side_bet_streak_bonus = Some(9);
+    assert_eq!(
+        CompetitionConfig::new(args).unwrap().side_bet_streak_bonus,
+        9
+    );
+}
+
+#[test]
+fn new_rejects_invalid_settings() {
+    let cases: [(Corruption, ErrorCode); 8] = [
+        (|args| args.entry_fee = 0, ErrorCode::InvalidEntryFee),
+        (
+            |args| args.peak_weight = 10_001,
+            ErrorCode::InvalidPeakWeight,
+        ),
+        (
+            |args| args.withdrawal_fee_bps = 10_001,
+            ErrorCode::InvalidWithdrawalFee,
+        ),
+        (
+            |args| args.prize_claim_window_hours = 0,
+            ErrorCode::InvalidPrizeClaimWindow,
+        ),
+        (
+            |args| args.dispute_window_hours = MAX_DISPUTE_WINDOW_HOURS + 1,
+            ErrorCode::InvalidDisputeWindow,
+        ),
+        (
+            |args| args.rounds_per_competition = 0,
+            ErrorCode::InvalidRoundsPerCompetition,
+        ),
+        (
+            |args| args.max_deck_offset = MAX_DECK_OFFSET + 1,
+            ErrorCode::InvalidDeckOffset,
+        ),
+        (
+            |args| args.starting_multiplier_bps = BPS_DENOMINATOR - 1,
+            ErrorCode::InvalidStartingMultiplier,
+        ),
+    ];
+
+    for (corrupt, expected) in cases {
+        let mut args = common::config_args();
+        corrupt(&mut args);
+        assert_eq!(CompetitionConfig::new(args).err(), Some(expected.into()));
+    }
+}
@@ -0,0 +1,35 @@
+use degame::*;
+
+const NOW: i64 = 1_700_000_000;
+
+#[test]
+fn ordinary_competition_is_accepted() {
+    check_competition_times(NOW, NOW + 7 * 86_400).unwrap();
+    check_competition_times(0, MAX_COMPETITION_TIMESTAMP).unwrap();
+}
+
+#[test]
+fn negative_timestamps_are_rejected() {
+    assert_eq!(check_competition_times(-1, NOW).unwrap_err(), ErrorCode::InvalidTimestamp.into());
+    assert_eq!(
+        check_competition_times(i64::MIN, -5).unwrap_err(),
+        ErrorCode::InvalidTimestamp.into()
+    );
+}
+
+#[test]
+fn overflow_prone_timestamps_are_rejected() {
+    assert_eq!(
+        check_competition_times(NOW, MAX_COMPETITION_TIMESTAMP + 1).unwrap_err(),
+        ErrorCode::InvalidTimestamp.into()
+    );
+    assert_eq!(
+        check_competition_times(NOW, i64::MAX).unwrap_err(),
+        ErrorCode::InvalidTimestamp.into()
+    );
+}
+
+#[test]
+fn end_must_follow_start() {
+    assert_eq!(check_competition_times(NOW, NOW).unwrap_err(), ErrorCode::InvalidStartTime.into());
+}
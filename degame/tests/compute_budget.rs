@@ -0,0 +1,42 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use degame::*;
+
+#[test]
+fn request_scales_with_remaining_cards() {
+    assert_eq!(required_compute_units(0), 252_000);
+    assert_eq!(required_compute_units(40), 212_000);
+    assert_eq!(required_compute_units(STANDARD_DECK_SIZE), 200_000);
+    assert_eq!(required_compute_units(STANDARD_DECK_SIZE + 2), 200_000);
+}
+
+#[test]
+fn preceding_limit_must_cover_the_bet() {
+    let required = required_compute_units(10);
+    let request = request_compute_units(required);
+    assert_eq!(request.data, [&[2][..], &242_000u32.to_le_bytes()].concat());
+
+    check_compute_budget(&request, required).unwrap();
+    assert_eq!(
+        check_compute_budget(&request_compute_units(required - 1), required).unwrap_err(),
+        ErrorCode::InsufficientComputeBudget.into()
+    );
+}
+
+#[test]
+fn other_preceding_instructions_are_rejected() {
+    let transfer = Instruction {
+        program_id: Pubkey::default(),
+        accounts: vec![],
+        data: request_compute_units(300_000).data,
+    };
+    let mut price = request_compute_units(300_000);
+    price.data[0] = 3;
+
+    for previous in [transfer, price] {
+        assert_eq!(
+            check_compute_budget(&previous, 200_000).unwrap_err(),
+            ErrorCode::ComputeBudgetMustBeFirst.into()
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use degame::*;
+
+mod common;
+
+use common::game::*;
+
+const BONUS: u32 = 25;
+
+/// Player on the last card of the deck with `side_bet_score`.
+fn last_card(side_bet_score: i64) -> Player {
+    Player {
+        cards_dealt: STANDARD_DECK_SIZE - 1,
+        side_bet_score,
+        total_side_bet_score: side_bet_score,
+        ..dealt_player()
+    }
+}
+
+fn finish(player: Player) -> Player {
+    let config = CompetitionConfig {
+        deck_completion_bonus: BONUS,
+        ..config()
+    };
+    let (result, player, _) = place(player, &config, high(None), BET_SLOT);
+    assert_eq!(result, Ok(()));
+    player
+}
+
+#[test]
+fn surviving_the_deck_earns_the_bonus() {
+    let player = finish(last_card(3));
+
+    assert!(player.finished);
+    assert!(player.pending_bet.is_none());
+    assert_eq!(player.side_bet_score, 3 + i64::from(BONUS));
+    assert_eq!(player.total_side_bet_score, 3 + i64::from(BONUS));
+}
+
+#[test]
+fn bonus_needs_a_positive_side_bet_score() {
+    for score in [0, -2] {
+        let player = finish(last_card(score));
+
+        assert!(player.finished);
+        assert_eq!(player.side_bet_score, score);
+    }
+}
@@ -0,0 +1,44 @@
+use degame::*;
+
+mod common;
+
+fn card(suit: &str, value: u8) -> Card {
+    Card { suit: suit.to_string(), value }
+}
+
+#[test]
+fn shuffled_decks_hold_each_card_once() {
+    for seed in 0..20 {
+        check_deck_unique(&shuffle_deck(seed, BPS_DENOMINATOR as u16)).unwrap();
+    }
+}
+
+#[test]
+fn injected_duplicate_is_detected() {
+    let mut deck = shuffle_deck(1, 0);
+    deck[30] = deck[4].clone();
+
+    assert_eq!(check_deck_unique(&deck).unwrap_err(), ErrorCode::DeckCorrupt.into());
+}
+
+#[test]
+fn same_value_in_other_suits_is_fine() {
+    let deck = [
+        card("Hearts", 7),
+        card("Spades", 7),
+        card(JOKER_SUIT, JOKER_VALUE),
+        card(JOKER_SUIT, JOKER_VALUE),
+    ];
+    check_deck_unique(&deck).unwrap();
+}
+
+#[test]
+fn bets_refuse_a_corrupt_deck() {
+    let mut player = common::player();
+    let deck = [card("Hearts", 7), card("Hearts", 9), card("Hearts", 7)];
+
+    assert_eq!(
+        resolve_bet(&mut player, &deck, &BetType::High, None, false, SideBetCard::Current).err(),
+        Some(ErrorCode::DeckCorrupt.into())
+    );
+}
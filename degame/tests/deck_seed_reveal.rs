@@ -0,0 +1,168 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::keccak;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, process, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const SEED: u64 = 42;
+const RANDOMNESS: u64 = 0xdead_beef;
+
+fn seed_hash(seed: u64) -> [u8; 32] {
+    keccak::hash(&seed.to_le_bytes()).to_bytes()
+}
+
+/// State, config and `player` for a competition run by `ADMIN`, followed by
+/// `signer`.
+fn accounts(player: Player, signer: Pubkey) -> Vec<TestAccount> {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        ..common::state()
+    };
+    let mut versioned = common::player();
+    *versioned = player;
+
+    vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(
+            CONFIG,
+            &common::config(),
+            VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+        ),
+        TestAccount::program(PLAYER, &*versioned, player_space()),
+        TestAccount::signer(signer, 0),
+    ]
+}
+
+/// Player bound to `AUTHORITY` who has committed to `SEED`.
+fn committed() -> Player {
+    let mut player = common::player();
+    player.authority = AUTHORITY;
+    player.state = STATE;
+    player.seed_hash = Some(seed_hash(SEED));
+    player.status = GameStatus::AwaitingRandomness;
+    (*player).clone()
+}
+
+fn receive_randomness(accounts: &mut [TestAccount]) -> ProgramResult {
+    let instruction = instruction::ReceiveRandomness {
+        randomness: RANDOMNESS,
+        proof: [0; VRF_PROOF_LEN],
+        alpha: [0; 32],
+    };
+    process(instruction, accounts, NOW)
+}
+
+fn reveal_deck_seed(accounts: &mut [TestAccount], seed: u64) -> ProgramResult {
+    process(instruction::RevealDeckSeed { seed }, accounts, NOW)
+}
+
+fn commit_deck_seed(accounts: &mut [TestAccount]) -> ProgramResult {
+    let instruction = instruction::CommitDeckSeed {
+        seed_hash: seed_hash(7),
+    };
+    process(instruction, &mut accounts[2..], NOW)
+}
+
+#[test]
+fn randomness_waits_for_the_seed_reveal() {
+    let mut accounts = accounts(committed(), ADMIN);
+
+    assert_eq!(receive_randomness(&mut accounts), Ok(()));
+    let player = accounts[2].read::<Player>();
+    assert_eq!(player.vrf_output, Some(RANDOMNESS));
+    assert_eq!(player.deck_commitment, None);
+    assert!(matches!(player.status, GameStatus::AwaitingRandomness));
+}
+
+#[test]
+fn reveal_deals_from_randomness_and_seed() {
+    let mut player = committed();
+    player.vrf_output = Some(RANDOMNESS);
+    let mut accounts = accounts(player, AUTHORITY);
+
+    assert_eq!(reveal_deck_seed(&mut accounts, SEED), Ok(()));
+    let player = accounts[2].read::<Player>();
+    assert_eq!(player.deck_commitment, Some(commit_seed(RANDOMNESS ^ SEED)));
+    assert_eq!(player.player_seed, Some(SEED));
+    assert!(matches!(player.status, GameStatus::Active));
+}
+
+#[test]
+fn randomness_needs_a_committed_seed() {
+    let mut player = committed();
+    player.seed_hash = None;
+    let mut accounts = accounts(player, ADMIN);
+
+    assert_eq!(
+        receive_randomness(&mut accounts),
+        failure(ErrorCode::SeedNotCommitted)
+    );
+}
+
+#[test]
+fn seed_cannot_be_recommitted_after_randomness() {
+    let mut player = committed();
+    player.vrf_output = Some(RANDOMNESS);
+    let mut accounts = accounts(player, AUTHORITY);
+
+    assert_eq!(
+        commit_deck_seed(&mut accounts),
+        failure(ErrorCode::RandomnessAlreadyReceived)
+    );
+}
+
+#[test]
+fn reveal_waits_for_randomness() {
+    let mut accounts = accounts(committed(), AUTHORITY);
+
+    assert_eq!(
+        reveal_deck_seed(&mut accounts, SEED),
+        failure(ErrorCode::RandomnessNotReceived)
+    );
+}
+
+#[test]
+fn reveal_must_match_the_commitment() {
+    let mut player = committed();
+    player.vrf_output = Some(RANDOMNESS);
+    let mut accounts = accounts(player, AUTHORITY);
+
+    assert_eq!(
+        reveal_deck_seed(&mut accounts, SEED + 1),
+        failure(ErrorCode::SeedCommitmentMismatch)
+    );
+}
+
+#[test]
+fn only_the_player_reveals() {
+    let mut player = committed();
+    player.vrf_output = Some(RANDOMNESS);
+    let mut accounts = accounts(player, Pubkey::new_unique());
+
+    assert_eq!(
+        reveal_deck_seed(&mut accounts, SEED),
+        failure(ErrorCode::Unauthorized)
+    );
+}
+
+#[test]
+fn only_the_admin_of_the_players_competition_delivers_randomness() {
+    let mut player = committed();
+    player.state = Pubkey::new_unique();
+    let mut accounts = accounts(player, ADMIN);
+
+    assert_eq!(
+        receive_randomness(&mut accounts),
+        failure(ErrorCode::PlayerNotInCompetition)
+    );
+}
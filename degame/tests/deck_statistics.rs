@@ -0,0 +1,30 @@
+use degame::*;
+
+fn ordered_deck() -> Vec<Card> {
+    ["Hearts", "Diamonds", "Clubs", "Spades"]
+        .iter()
+        .flat_map(|suit| (2..=14).map(|value| Card { suit: suit.to_string(), value }))
+        .collect()
+}
+
+#[test]
+fn ordered_deck_is_skewed_and_low_entropy() {
+    let stats = calculate_deck_statistics(&ordered_deck());
+
+    // Each quarter holds a single suit: (4 * 13 - 13)^2 / 52 + 3 * 13^2 / 52 = 39.
+    assert_eq!(stats.chi_squared_bps, 4 * 390_000);
+    assert_eq!((stats.runs_up, stats.runs_down), (4, 3));
+    // 48 steps of +1 and 3 of -12.
+    let expected = -(48.0f64 / 51.0) * (48.0f64 / 51.0).log2() - (3.0f64 / 51.0) * (3.0f64 / 51.0).log2();
+    assert_eq!(stats.entropy_estimate_bps, (expected * 10_000.0) as u64);
+}
+
+#[test]
+fn shuffled_deck_is_more_random_than_ordered() {
+    let ordered = calculate_deck_statistics(&ordered_deck());
+    let shuffled = calculate_deck_statistics(&shuffle_deck(42, 0));
+
+    assert!(shuffled.chi_squared_bps < ordered.chi_squared_bps);
+    assert!(shuffled.entropy_estimate_bps > ordered.entropy_estimate_bps);
+    assert!(shuffled.runs_up + shuffled.runs_down > ordered.runs_up + ordered.runs_down);
+}
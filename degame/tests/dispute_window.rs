@@ -0,0 +1,109 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const FINALIZED: i64 = 1_700_000_000;
+const HOUR: i64 = 3600;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const POOL: u64 = 10_000;
+
+fn finalized_state() -> State {
+    State {
+        leaderboard_size: 3,
+        finalized: true,
+        finalized_timestamp: FINALIZED,
+        ..common::state()
+    }
+}
+
+fn config() -> CompetitionConfig {
+    let mut config = common::config();
+    config.prize_claim_window_hours = 48;
+    config.dispute_window_hours = DEFAULT_DISPUTE_WINDOW_HOURS;
+    config
+}
+
+#[test]
+fn claims_wait_for_the_dispute_window() {
+    let state = finalized_state();
+
+    assert_eq!(
+        check_dispute_window(&state, &config(), FINALIZED).unwrap_err(),
+        ErrorCode::DisputeWindowActive.into()
+    );
+    assert_eq!(
+        check_dispute_window(&state, &config(), FINALIZED + 24 * HOUR - 1).unwrap_err(),
+        ErrorCode::DisputeWindowActive.into()
+    );
+    assert!(check_dispute_window(&state, &config(), FINALIZED + 24 * HOUR).is_ok());
+}
+
+#[test]
+fn bypass_allows_immediate_claims() {
+    let mut state = finalized_state();
+    state.dispute_window_bypassed = true;
+
+    assert!(check_dispute_window(&state, &config(), FINALIZED).is_ok());
+}
+
+#[test]
+fn zero_hour_window_never_blocks() {
+    let mut config = config();
+    config.dispute_window_hours = 0;
+
+    assert!(check_dispute_window(&finalized_state(), &config, FINALIZED).is_ok());
+}
+
+#[test]
+fn claim_window_starts_after_the_dispute_window() {
+    assert_eq!(prize_window_end(&finalized_state(), &config()), FINALIZED + 72 * HOUR);
+}
+
+/// A competition finalized at `FINALIZED` with a house share of its pool.
+fn house_ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        pool: POOL,
+        prize_pool: POOL,
+        ..finalized_state()
+    };
+    let mut args = common::config_args();
+    args.prize_schedule = PrizeSchedule::new([45, 25, 15], 15);
+    args.prize_claim_window_hours = 48;
+    args.dispute_window_hours = DEFAULT_DISPUTE_WINDOW_HOURS;
+    let config = CompetitionConfig::new(args).unwrap();
+
+    let mut ledger = Ledger::new();
+    ledger.now = FINALIZED;
+    let mut state = TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8));
+    state.lamports += POOL;
+    ledger.insert(state);
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn claim_house_prize(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::ClaimHousePrize {},
+        accounts::ClaimHousePrize { state: STATE, config: CONFIG, admin: ADMIN },
+    )
+}
+
+#[test]
+fn house_prize_waits_for_the_dispute_window() {
+    let mut ledger = house_ledger();
+    assert_eq!(claim_house_prize(&mut ledger), failure(ErrorCode::DisputeWindowActive));
+    assert_eq!(ledger.get(&ADMIN).lamports, 0);
+
+    ledger.now = FINALIZED + 24 * HOUR;
+    assert_eq!(claim_house_prize(&mut ledger), Ok(()));
+    assert_eq!(ledger.get(&ADMIN).lamports, POOL * 15 / 100);
+}
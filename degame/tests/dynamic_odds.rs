@@ -0,0 +1,26 @@
+use degame::*;
+
+fn deck(values: &[u8]) -> Vec<Card> {
+    values
+        .iter()
+        .map(|&value| Card { suit: "Hearts".to_string(), value })
+        .collect()
+}
+
+#[test]
+fn depleted_deck_pays_more_for_unlikely_bets() {
+    // Only one of the four cards left beats the 7.
+    let deck = deck(&[7, 8, 2, 3, 4]);
+
+    assert_eq!(bet_multiplier_gain(&deck, 0, BetType::High, false).unwrap(), 1.5);
+    assert_eq!(bet_multiplier_gain(&deck, 0, BetType::High, true).unwrap(), 4.0);
+    assert_eq!(bet_multiplier_gain(&deck, 0, BetType::Low, false).unwrap(), 1.7);
+    assert_eq!(bet_multiplier_gain(&deck, 0, BetType::Low, true).unwrap(), 4.0 / 3.0);
+}
+
+#[test]
+fn unwinnable_bet_keeps_the_static_gain() {
+    let deck = deck(&[2, 14, 14]);
+
+    assert_eq!(bet_multiplier_gain(&deck, 0, BetType::Low, true).unwrap(), 4.0);
+}
@@ -0,0 +1,30 @@
+use degame::*;
+
+mod common;
+
+fn state(entries_closed: bool) -> State {
+    State {
+        leaderboard_size: 3,
+        entries_closed,
+        ..common::state()
+    }
+}
+
+#[test]
+fn new_games_need_open_entries() {
+    state(false).check_entries_open().unwrap();
+    assert_eq!(state(true).check_entries_open().unwrap_err(), ErrorCode::EntriesClosed.into());
+}
+
+#[test]
+fn games_in_progress_keep_playing() {
+    let state = state(true);
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    let deck = shuffle_deck(3, 0);
+    let bet = if deck[1].value > deck[0].value { BetType::High } else { BetType::Low };
+
+    assert!(state.entries_closed);
+    resolve_bet(&mut player, &deck, &bet, None, false, SideBetCard::Current).unwrap();
+    assert_eq!(player.cards_dealt, 1);
+}
@@ -0,0 +1,57 @@
+use degame::*;
+
+mod common;
+
+fn state() -> State {
+    State {
+        leaderboard_size: 3,
+        ..common::state()
+    }
+}
+
+fn new_player() -> VersionedAccount<Player> {
+    common::player()
+}
+
+/// What `start_game` does for an admitted player.
+fn enter(state: &mut State, player: &mut Player, max_entries: u32) -> anchor_lang::Result<()> {
+    check_entry_cap(state, player, max_entries)?;
+    player.total_games += 1;
+    if player.total_games == 1 {
+        state.participants += 1;
+    }
+    Ok(())
+}
+
+#[test]
+fn entry_past_the_cap_is_rejected() {
+    let mut state = state();
+    let mut players: Vec<_> = (0..3).map(|_| new_player()).collect();
+    for player in players.iter_mut() {
+        enter(&mut state, player, 3).unwrap();
+    }
+    assert_eq!(state.participants, 3);
+
+    assert_eq!(
+        enter(&mut state, &mut new_player(), 3).unwrap_err(),
+        ErrorCode::TournamentFull.into()
+    );
+    assert_eq!(state.participants, 3);
+}
+
+#[test]
+fn players_already_in_keep_playing() {
+    let mut state = state();
+    let mut player = new_player();
+    enter(&mut state, &mut player, 1).unwrap();
+
+    enter(&mut state, &mut player, 1).unwrap();
+    assert_eq!(player.total_games, 2);
+}
+
+#[test]
+fn zero_means_no_cap() {
+    let mut state = state();
+    state.participants = u32::MAX - 1;
+    check_entry_cap(&state, &new_player(), 0).unwrap();
+}
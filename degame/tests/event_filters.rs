@@ -0,0 +1,45 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::keccak;
+use degame::*;
+
+mod common;
+
+const PLAYER: Pubkey = Pubkey::new_from_array([7; 32]);
+const OTHER: Pubkey = Pubkey::new_from_array([8; 32]);
+
+#[test]
+fn filter_tag_is_reproducible() {
+    assert_eq!(
+        filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_500),
+        filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_500)
+    );
+
+    let preimage = [&[FILTER_EVENT_BET_PLACED][..], &PLAYER.to_bytes(), &22_500u64.to_le_bytes()];
+    let hash = keccak::hashv(&preimage).to_bytes();
+    assert_eq!(filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_500)[..], hash[..8]);
+}
+
+#[test]
+fn filter_tag_depends_on_every_input() {
+    let tag = filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_500);
+
+    assert_ne!(tag, filter_tag(1 << 1, &PLAYER, 22_500));
+    assert_ne!(tag, filter_tag(FILTER_EVENT_BET_PLACED, &OTHER, 22_500));
+    assert_ne!(tag, filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_501));
+}
+
+#[test]
+fn bet_placed_leads_with_its_filter_tag() {
+    let mut player = common::player();
+    player.multiplier = 2.25;
+    let outcome = BetOutcome {
+        correct: true,
+        multiplier_gain: 1.5,
+        side_bet_result: None,
+        side_bet_streak_milestone: None,
+        hot_card: None,
+    };
+    let event = bet_placed_event(&player, PLAYER, BetType::High, &outcome).unwrap();
+
+    assert_eq!(event.filter_tag, filter_tag(FILTER_EVENT_BET_PLACED, &PLAYER, 22_500));
+}
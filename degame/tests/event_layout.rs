@@ -0,0 +1,668 @@
+//! Golden Borsh layouts for every emitted event. Off-chain decoders depend on
+//! these bytes, so any field reordering or type change must fail here first.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::Event;
+use degame::*;
+
+const PLAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+const OTHER: Pubkey = Pubkey::new_from_array([2; 32]);
+
+fn assert_layout<E: Event>(event: E, name: &str, fields: &[&[u8]]) {
+    let data = event.data();
+    let discriminator = hash(format!("event:{name}").as_bytes()).to_bytes();
+
+    assert_eq!(data[..8], discriminator[..8], "{name} discriminator");
+    assert_eq!(data[8..], fields.concat(), "{name} layout");
+}
+
+#[test]
+fn game_initialized() {
+    assert_layout(
+        GameInitialized { admin: PLAYER, entry_fee: 100, start_time: -5, end_time: 60 },
+        "GameInitialized",
+        &[&PLAYER.to_bytes(), &100u64.to_le_bytes(), &(-5i64).to_le_bytes(), &60i64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn randomness_requested() {
+    assert_layout(RandomnessRequested { seed: 42 }, "RandomnessRequested", &[&42u64.to_le_bytes()]);
+}
+
+#[test]
+fn randomness_received() {
+    assert_layout(
+        RandomnessReceived { deck_commitment: [7; 32] },
+        "RandomnessReceived",
+        &[&[7; 32]],
+    );
+}
+
+#[test]
+fn game_started() {
+    assert_layout(
+        GameStarted { player: PLAYER, game_id: 3 },
+        "GameStarted",
+        &[&PLAYER.to_bytes(), &3u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn bet_placed() {
+    assert_layout(
+        BetPlaced {
+            filter_tag: [7; 8],
+            player: PLAYER,
+            game_id: 3,
+            bet_type: BetType::Low,
+            multiplier_gain: 1.5,
+            side_bet_result: Some(-1),
+            multiplier: 15_000,
+            outcome: vec![1, 1],
+        },
+        "BetPlaced",
+        &[
+            &[7; 8],
+            &PLAYER.to_bytes(),
+            &3u64.to_le_bytes(),
+            &[1],
+            &1.5f64.to_le_bytes(),
+            &[1],
+            &(-1i64).to_le_bytes(),
+            &15_000u64.to_le_bytes(),
+            &2u32.to_le_bytes(),
+            &[1, 1],
+        ],
+    );
+    assert_layout(
+        BetPlaced {
+            filter_tag: [7; 8],
+            player: PLAYER,
+            game_id: 3,
+            bet_type: BetType::High,
+            multiplier_gain: 2.0,
+            side_bet_result: None,
+            multiplier: 20_000,
+            outcome: vec![],
+        },
+        "BetPlaced",
+        &[
+            &[7; 8],
+            &PLAYER.to_bytes(),
+            &3u64.to_le_bytes(),
+            &[0],
+            &2.0f64.to_le_bytes(),
+            &[0],
+            &20_000u64.to_le_bytes(),
+            &0u32.to_le_bytes(),
+        ],
+    );
+}
+
+#[test]
+fn game_over() {
+    assert_layout(
+        GameOver {
+            player: PLAYER,
+            game_id: 3,
+            final_multiplier: 4.0,
+            peak_multiplier: 40_000,
+            side_bet_score: -2,
+            reason: GameEndReason::BetLimitReached,
+            final_multiplier_scaled: 40_000,
+        },
+        "GameOver",
+        &[
+            &PLAYER.to_bytes(),
+            &3u64.to_le_bytes(),
+            &4.0f64.to_le_bytes(),
+            &40_000u64.to_le_bytes(),
+            &(-2i64).to_le_bytes(),
+            &[2],
+            &40_000u64.to_le_bytes(),
+        ],
+    );
+    assert_layout(
+        GameOver {
+            player: PLAYER,
+            game_id: 3,
+            final_multiplier: 0.0,
+            peak_multiplier: 0,
+            side_bet_score: 0,
+            reason: GameEndReason::BetTimeExpired,
+            final_multiplier_scaled: 0,
+        },
+        "GameOver",
+        &[
+            &PLAYER.to_bytes(),
+            &3u64.to_le_bytes(),
+            &0.0f64.to_le_bytes(),
+            &0u64.to_le_bytes(),
+            &0i64.to_le_bytes(),
+            &[3],
+            &0u64.to_le_bytes(),
+        ],
+    );
+}
+
+#[test]
+fn score_submitted() {
+    assert_layout(
+        ScoreSubmitted { player: PLAYER, game_id: 3, nickname: "ace".to_string(), score: 15_000 },
+        "ScoreSubmitted",
+        &[&PLAYER.to_bytes(), &3u64.to_le_bytes(), &3u32.to_le_bytes(), b"ace", &15_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn score_skipped() {
+    assert_layout(
+        ScoreSkipped { account: PLAYER, reason: ScoreSkipReason::UnknownAuthority },
+        "ScoreSkipped",
+        &[&PLAYER.to_bytes(), &[3]],
+    );
+}
+
+#[test]
+fn scores_batch_submitted() {
+    assert_layout(
+        ScoresBatchSubmitted { submitted: 8, skipped: 2 },
+        "ScoresBatchSubmitted",
+        &[&[8, 2]],
+    );
+}
+
+#[test]
+fn leaderboard_finalized() {
+    let entry = LeaderboardEntry {
+        player: PLAYER,
+        nickname: "ace".to_string(),
+        score: 15_000,
+        side_bet_score: -1,
+        claimed: true,
+        finished_at: 900,
+    };
+
+    let prize = ProvisionalPrize { position: 0, player: PLAYER, amount: 495 };
+
+    assert_layout(
+        LeaderboardFinalized {
+            timestamp: 1_000,
+            leaderboard: vec![entry],
+            claim_deadline: 87_400,
+            prizes: vec![prize],
+            multiplier_scale: MULTIPLIER_SCALE,
+        },
+        "LeaderboardFinalized",
+        &[
+            &1_000i64.to_le_bytes(),
+            &1u32.to_le_bytes(),
+            &PLAYER.to_bytes(),
+            &3u32.to_le_bytes(),
+            b"ace",
+            &15_000u64.to_le_bytes(),
+            &(-1i64).to_le_bytes(),
+            &[1],
+            &900i64.to_le_bytes(),
+            &87_400i64.to_le_bytes(),
+            &1u32.to_le_bytes(),
+            &[0],
+            &PLAYER.to_bytes(),
+            &495u64.to_le_bytes(),
+            &10_000u64.to_le_bytes(),
+        ],
+    );
+}
+
+#[test]
+fn prize_claimed() {
+    assert_layout(
+        PrizeClaimed { player: PLAYER, position: 1, gross_prize: 300, net_prize: 297 },
+        "PrizeClaimed",
+        &[&PLAYER.to_bytes(), &1u64.to_le_bytes(), &300u64.to_le_bytes(), &297u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn accounts_closed_batch() {
+    assert_layout(
+        AccountsClosedBatch { count: 4, rent_reclaimed: 9_000 },
+        "AccountsClosedBatch",
+        &[&[4], &9_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn payout_wallet_set() {
+    assert_layout(
+        PayoutWalletSet { player: PLAYER, wallet: OTHER },
+        "PayoutWalletSet",
+        &[&PLAYER.to_bytes(), &OTHER.to_bytes()],
+    );
+}
+
+#[test]
+fn account_layout_upgraded() {
+    assert_layout(
+        AccountLayoutUpgraded { account: PLAYER, old_len: 100, new_len: 120 },
+        "AccountLayoutUpgraded",
+        &[&PLAYER.to_bytes(), &100u32.to_le_bytes(), &120u32.to_le_bytes()],
+    );
+}
+
+#[test]
+fn leaderboard_data_anchored() {
+    assert_layout(
+        LeaderboardDataAnchored {
+            state: PLAYER,
+            competition_index: 5,
+            ipfs_cid: [9; IPFS_CID_LEN],
+            data_hash: [8; 32],
+        },
+        "LeaderboardDataAnchored",
+        &[&PLAYER.to_bytes(), &5u32.to_le_bytes(), &[9; IPFS_CID_LEN], &[8; 32]],
+    );
+}
+
+#[test]
+fn winnings_settled() {
+    assert_layout(
+        WinningsSettled { player: PLAYER, amount: 500 },
+        "WinningsSettled",
+        &[&PLAYER.to_bytes(), &500u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn jackpot_claimed() {
+    assert_layout(
+        JackpotClaimed { state: OTHER, player: PLAYER, amount: 500 },
+        "JackpotClaimed",
+        &[&OTHER.to_bytes(), &PLAYER.to_bytes(), &500u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn game_refunded() {
+    assert_layout(
+        GameRefunded { player: PLAYER, game_id: 7, amount: 500 },
+        "GameRefunded",
+        &[&PLAYER.to_bytes(), &7u64.to_le_bytes(), &500u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn position_predicted() {
+    assert_layout(
+        PositionPredicted { player: PLAYER, predicted_position: 2, stake: 50 },
+        "PositionPredicted",
+        &[&PLAYER.to_bytes(), &[2], &50u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn position_bet_settled() {
+    assert_layout(
+        PositionBetSettled { player: PLAYER, predicted_position: 0, actual_position: Some(1), payout: 0 },
+        "PositionBetSettled",
+        &[&PLAYER.to_bytes(), &[0], &[1, 1], &0u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn health_status() {
+    assert_layout(
+        HealthStatus {
+            program_version: 1,
+            state_key: PLAYER,
+            pool: 1_000,
+            participants: 12,
+            finalized: false,
+            clock: 77,
+            slot: 88,
+        },
+        "HealthStatus",
+        &[
+            &[1],
+            &PLAYER.to_bytes(),
+            &1_000u64.to_le_bytes(),
+            &12u32.to_le_bytes(),
+            &[0],
+            &77i64.to_le_bytes(),
+            &88u64.to_le_bytes(),
+        ],
+    );
+}
+
+#[test]
+fn cooldown_waived() {
+    assert_layout(CooldownWaived { player: PLAYER }, "CooldownWaived", &[&PLAYER.to_bytes()]);
+}
+
+#[test]
+fn ban_list_updated() {
+    assert_layout(
+        BanListUpdated { player: PLAYER, banned: true },
+        "BanListUpdated",
+        &[&PLAYER.to_bytes(), &[1]],
+    );
+}
+
+#[test]
+fn nickname_set() {
+    assert_layout(
+        NicknameSet { player: PLAYER, nickname: "ace".to_string() },
+        "NicknameSet",
+        &[&PLAYER.to_bytes(), &3u32.to_le_bytes(), b"ace"],
+    );
+}
+
+#[test]
+fn bet_odds() {
+    assert_layout(
+        BetOdds {
+            high_numerator: 4,
+            low_numerator: 0,
+            tie_numerator: 0,
+            denominator: 4,
+            current_card_value: 2,
+            current_card_suit: "Spades".to_string(),
+        },
+        "BetOdds",
+        &[
+            &4u64.to_le_bytes(),
+            &0u64.to_le_bytes(),
+            &0u64.to_le_bytes(),
+            &4u64.to_le_bytes(),
+            &[2],
+            &6u32.to_le_bytes(),
+            b"Spades",
+        ],
+    );
+}
+
+#[test]
+fn analytics_call_failed() {
+    assert_layout(
+        AnalyticsCallFailed { event_type: 3, player: PLAYER },
+        "AnalyticsCallFailed",
+        &[&[3], &PLAYER.to_bytes()],
+    );
+}
+
+#[test]
+fn pool_full() {
+    assert_layout(
+        PoolFull { current_pool: 3_000, max_pool: 3_000 },
+        "PoolFull",
+        &[&3_000u64.to_le_bytes(), &3_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn deck_statistics() {
+    assert_layout(
+        DeckStatistics { chi_squared_bps: 12, runs_up: 3, runs_down: 4, entropy_estimate_bps: 30_000 },
+        "DeckStatistics",
+        &[&12u64.to_le_bytes(), &3u32.to_le_bytes(), &4u32.to_le_bytes(), &30_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn game_tagged() {
+    assert_layout(
+        GameTagged { state: PLAYER, tag: GameTag::Sponsored },
+        "GameTagged",
+        &[&PLAYER.to_bytes(), &[4]],
+    );
+}
+
+#[test]
+fn game_tag_matched() {
+    assert_layout(GameTagMatched { state: PLAYER }, "GameTagMatched", &[&PLAYER.to_bytes()]);
+}
+
+#[test]
+fn spectator_update() {
+    assert_layout(
+        SpectatorUpdate { player: PLAYER, spectator_count: 2, last_bet_type: BetType::Low, new_multiplier: 15_000 },
+        "SpectatorUpdate",
+        &[&PLAYER.to_bytes(), &2u32.to_le_bytes(), &[1], &15_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn leaderboard_snapshot() {
+    assert_layout(
+        LeaderboardSnapshot { epoch: 7, page: 1, entries: vec![] },
+        "LeaderboardSnapshot",
+        &[&7u32.to_le_bytes(), &[1], &0u32.to_le_bytes()],
+    );
+}
+
+#[test]
+fn group_created() {
+    assert_layout(
+        GroupCreated { group: PLAYER, group_id: 4, members: vec![PLAYER] },
+        "GroupCreated",
+        &[&PLAYER.to_bytes(), &4u64.to_le_bytes(), &1u32.to_le_bytes(), &PLAYER.to_bytes()],
+    );
+}
+
+#[test]
+fn group_leaderboard_finalized() {
+    assert_layout(
+        GroupLeaderboardFinalized {
+            state: PLAYER,
+            entries: vec![GroupEntry { group: PLAYER, group_score: 40_500 }],
+            pool: 9_000,
+        },
+        "GroupLeaderboardFinalized",
+        &[
+            &PLAYER.to_bytes(),
+            &1u32.to_le_bytes(),
+            &PLAYER.to_bytes(),
+            &40_500u64.to_le_bytes(),
+            &9_000u64.to_le_bytes(),
+        ],
+    );
+}
+
+#[test]
+fn group_prize_claimed() {
+    assert_layout(
+        GroupPrizeClaimed { group: PLAYER, member: PLAYER, position: 0, amount: 1_500 },
+        "GroupPrizeClaimed",
+        &[&PLAYER.to_bytes(), &PLAYER.to_bytes(), &[0], &1_500u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn leaderboard_resized() {
+    assert_layout(
+        LeaderboardResized { state: PLAYER, old_size: 3, new_size: 1 },
+        "LeaderboardResized",
+        &[&PLAYER.to_bytes(), &[3], &[1]],
+    );
+}
+
+#[test]
+fn side_bet_streak_bonus() {
+    assert_layout(
+        SideBetStreakBonus { player: PLAYER, streak: 3, bonus: 5 },
+        "SideBetStreakBonus",
+        &[&PLAYER.to_bytes(), &[3], &5i32.to_le_bytes()],
+    );
+}
+
+#[test]
+fn score_adjusted() {
+    assert_layout(
+        ScoreAdjusted { player: PLAYER, old_score: 20_000, new_score: 35_000, reason: "fix".to_string() },
+        "ScoreAdjusted",
+        &[
+            &PLAYER.to_bytes(),
+            &20_000u64.to_le_bytes(),
+            &35_000u64.to_le_bytes(),
+            &3u32.to_le_bytes(),
+            b"fix",
+        ],
+    );
+}
+
+#[test]
+fn leaderboard_position_changed() {
+    assert_layout(
+        LeaderboardPositionChanged { player: PLAYER, old_position: 1, new_position: 0 },
+        "LeaderboardPositionChanged",
+        &[&PLAYER.to_bytes(), &[1], &[0]],
+    );
+}
+
+#[test]
+fn special_prize_set() {
+    assert_layout(
+        SpecialPrizeSet { recipient: PLAYER, amount: 1_000, reason: "mvp".to_string() },
+        "SpecialPrizeSet",
+        &[&PLAYER.to_bytes(), &1_000u64.to_le_bytes(), &3u32.to_le_bytes(), b"mvp"],
+    );
+}
+
+#[test]
+fn special_prize_claimed() {
+    assert_layout(
+        SpecialPrizeClaimed { recipient: PLAYER, amount: 1_000 },
+        "SpecialPrizeClaimed",
+        &[&PLAYER.to_bytes(), &1_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn house_prize_claimed() {
+    assert_layout(
+        HousePrizeClaimed { state: PLAYER, amount: 1_500 },
+        "HousePrizeClaimed",
+        &[&PLAYER.to_bytes(), &1_500u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn rules_hash_updated() {
+    assert_layout(
+        RulesHashUpdated { old_hash: [1; 32], new_hash: [2; 32], timestamp: 1_000 },
+        "RulesHashUpdated",
+        &[&[1; 32], &[2; 32], &1_000i64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn rules_verified() {
+    assert_layout(RulesVerified { matches: true }, "RulesVerified", &[&[1]]);
+}
+
+#[test]
+fn game_over_hook_failed() {
+    assert_layout(
+        GameOverHookFailed { player: PLAYER, game_id: 12 },
+        "GameOverHookFailed",
+        &[&PLAYER.to_bytes(), &12u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn winner_nft_minted() {
+    let mint = Pubkey::new_from_array([2; 32]);
+    assert_layout(
+        WinnerNftMinted { player: PLAYER, mint, position: 1 },
+        "WinnerNftMinted",
+        &[&PLAYER.to_bytes(), &mint.to_bytes(), &[1]],
+    );
+}
+
+#[test]
+fn entries_closed() {
+    assert_layout(EntriesClosed { state: PLAYER }, "EntriesClosed", &[&PLAYER.to_bytes()]);
+}
+
+#[test]
+fn leaderboard_root_updated() {
+    assert_layout(
+        LeaderboardRootUpdated { root: [4; 32], entry_count: 5 },
+        "LeaderboardRootUpdated",
+        &[&[4; 32], &5u32.to_le_bytes()],
+    );
+}
+
+#[test]
+fn certificate_issued() {
+    assert_layout(
+        CertificateIssued { player: PLAYER, best_score: 30_000, games_completed: 2 },
+        "CertificateIssued",
+        &[&PLAYER.to_bytes(), &30_000u64.to_le_bytes(), &[2]],
+    );
+}
+
+#[test]
+fn hot_card_activated() {
+    assert_layout(
+        HotCardActivated { player: PLAYER, card_value: 5, normal_gain: 12_000, boosted_gain: 24_000 },
+        "HotCardActivated",
+        &[&PLAYER.to_bytes(), &[5], &12_000u64.to_le_bytes(), &24_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn guarantee_shortfall() {
+    assert_layout(
+        GuaranteeShortfall { position: 0, shortfall_amount: 5_950 },
+        "GuaranteeShortfall",
+        &[&[0], &5_950u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn claim_withdrawn() {
+    assert_layout(
+        ClaimWithdrawn { player: PLAYER, wallet: OTHER, amount: 7_000 },
+        "ClaimWithdrawn",
+        &[&PLAYER.to_bytes(), &OTHER.to_bytes(), &7_000u64.to_le_bytes()],
+    );
+}
+
+#[test]
+fn collusion_suspected() {
+    assert_layout(
+        CollusionSuspected { wallet: OTHER, positions: vec![0, 2] },
+        "CollusionSuspected",
+        &[&OTHER.to_bytes(), &2u32.to_le_bytes(), &[0, 2]],
+    );
+}
+
+#[test]
+fn prizes_released() {
+    assert_layout(
+        PrizesReleased { state: OTHER },
+        "PrizesReleased",
+        &[&OTHER.to_bytes()],
+    );
+}
+
+#[test]
+fn dispute_window_bypassed() {
+    assert_layout(
+        DisputeWindowBypassed { state: OTHER },
+        "DisputeWindowBypassed",
+        &[&OTHER.to_bytes()],
+    );
+}
+
+#[test]
+fn bet_forfeited() {
+    assert_layout(
+        BetForfeited { player: PLAYER, slot: 900 },
+        "BetForfeited",
+        &[&PLAYER.to_bytes(), &900u64.to_le_bytes()],
+    );
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::game::*;
+use common::program::{emitted, failure, process, TestAccount};
+
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+
+fn update_event_mask(signer: Pubkey, mask: u8) -> (ProgramResult, u8) {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        ..common::state()
+    };
+    let mut accounts = [
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        config_account(CONFIG, &config()),
+        TestAccount::signer(signer, 0),
+    ];
+
+    let result = process(instruction::UpdateEventMask { mask }, &mut accounts, 0);
+    (result, accounts[1].read::<CompetitionConfig>().event_emission_mask)
+}
+
+/// Wins a high bet under a config emitting the events in `mask`.
+fn win_bet(mask: u8) -> Vec<BetPlaced> {
+    let config = CompetitionConfig {
+        event_emission_mask: mask,
+        ..config()
+    };
+    let (result, player, _) = place(dealt_player(), &config, high(None), BET_SLOT);
+    assert_eq!(result, Ok(()));
+
+    let hash = winning_hash(&player, &config);
+    let (result, player) = settle_on(player, &config, hash);
+    assert_eq!(result, Ok(()));
+    assert!(!player.finished);
+    emitted()
+}
+
+/// Wins a high bet on a hot card under a config emitting the events in
+/// `mask`.
+fn win_hot_card(mask: u8) -> Vec<HotCardActivated> {
+    let config = CompetitionConfig {
+        event_emission_mask: mask,
+        ..config()
+    };
+    let (result, mut player, _) = place(dealt_player(), &config, high(None), BET_SLOT);
+    assert_eq!(result, Ok(()));
+
+    let hash = winning_hash(&player, &config);
+    let showing = settled_deck(&player, &config, hash)[player.cards_dealt as usize].value;
+    player.hot_values = [showing; 2];
+    let (result, _) = settle_on(player, &config, hash);
+    assert_eq!(result, Ok(()));
+    emitted()
+}
+
+fn is_red(card: &Card) -> bool {
+    card.suit == "Hearts" || card.suit == "Diamonds"
+}
+
+/// Wins a high bet and the red side bet that completes a streak of three,
+/// under a config emitting the events in `mask`.
+fn win_streak_bonus(mask: u8) -> Vec<SideBetStreakBonus> {
+    let config = CompetitionConfig {
+        event_emission_mask: mask,
+        max_side_bets_per_game: STANDARD_DECK_SIZE,
+        ..config()
+    };
+    let player = Player {
+        side_bet_streak: 2,
+        ..dealt_player()
+    };
+    let side_bet = Some(SideBetType::Color { red: true });
+    let (result, player, _) = place(player, &config, high(side_bet), BET_SLOT);
+    assert_eq!(result, Ok(()));
+
+    let hash = hash_dealing(&player, &config, |current, next| {
+        current.value != JOKER_VALUE && next.value > current.value && is_red(current) && is_red(next)
+    });
+    let (result, player) = settle_on(player, &config, hash);
+    assert_eq!(result, Ok(()));
+    assert_eq!(player.side_bet_streak, 3);
+    emitted()
+}
+
+#[test]
+fn new_competitions_emit_every_event() {
+    let config = CompetitionConfig::new(common::config_args()).unwrap();
+
+    assert_eq!(config.event_emission_mask, u8::MAX);
+    for bit in [
+        EVENT_BIT_BET_PLACED,
+        EVENT_BIT_HOT_CARD_ACTIVATED,
+        EVENT_BIT_SIDE_BET_STREAK_BONUS,
+        EVENT_BIT_GAME_STARTED,
+        EVENT_BIT_RANDOMNESS_RECEIVED,
+        EVENT_BIT_GAME_OVER,
+        EVENT_BIT_SCORE_SUBMITTED,
+    ] {
+        assert!(config.emits(bit));
+    }
+}
+
+#[test]
+fn admin_sets_the_mask() {
+    assert_eq!(update_event_mask(ADMIN, 0b1000_0010), (Ok(()), 0b1000_0010));
+}
+
+#[test]
+fn only_the_admin_sets_the_mask() {
+    let (result, mask) = update_event_mask(Pubkey::new_unique(), 0b1000_0010);
+
+    assert_eq!(result, failure(ErrorCode::Unauthorized));
+    assert_eq!(mask, 0);
+}
+
+#[test]
+fn cleared_bit_silences_its_event() {
+    assert_eq!(win_bet(1 << EVENT_BIT_BET_PLACED).len(), 1);
+    assert!(win_bet(!(1 << EVENT_BIT_BET_PLACED)).is_empty());
+}
+
+#[test]
+fn cleared_bit_silences_the_streak_bonus() {
+    assert_eq!(win_streak_bonus(1 << EVENT_BIT_SIDE_BET_STREAK_BONUS).len(), 1);
+    assert!(win_streak_bonus(!(1 << EVENT_BIT_SIDE_BET_STREAK_BONUS)).is_empty());
+}
+
+#[test]
+fn cleared_bit_silences_hot_cards() {
+    assert_eq!(win_hot_card(1 << EVENT_BIT_HOT_CARD_ACTIVATED).len(), 1);
+    assert!(win_hot_card(!(1 << EVENT_BIT_HOT_CARD_ACTIVATED)).is_empty());
+}
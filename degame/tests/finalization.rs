@@ -0,0 +1,96 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+
+fn entry(player: Pubkey) -> LeaderboardEntry {
+    LeaderboardEntry {
+        player,
+        nickname: String::new(),
+        score: 1,
+        side_bet_score: 0,
+        claimed: false,
+        finished_at: 0,
+    }
+}
+
+/// All-zero config, with only the fields under test set.
+fn config(prize_claim_window_hours: u16, withdrawal_fee_bps: u16) -> CompetitionConfig {
+    let mut config = common::config();
+    config.prize_claim_window_hours = prize_claim_window_hours;
+    config.withdrawal_fee_bps = withdrawal_fee_bps;
+    config.prize_schedule = PrizeSchedule::STANDARD;
+    config
+}
+
+#[test]
+fn finalization_reports_deadline_and_prizes() {
+    let winners = [1, 2, 3].map(|seed| Pubkey::new_from_array([seed; 32]));
+    let state = State {
+        leaderboard_size: 3,
+        leaderboard: winners.iter().copied().map(entry).collect(),
+        finalized: true,
+        finalized_timestamp: 1_000,
+        pool: 10_000,
+        prize_pool: 10_000,
+        participants: 3,
+        ..common::state()
+    };
+    let config = config(24, 100);
+
+    assert_eq!(prize_window_end(&state, &config), 1_000 + 24 * 3600);
+
+    let prizes = provisional_prizes(&state, &config).unwrap();
+    let paid: Vec<_> = prizes.iter().map(|prize| (prize.position, prize.player, prize.amount)).collect();
+    assert_eq!(paid, vec![(0, winners[0], 4_950), (1, winners[1], 2_970), (2, winners[2], 1_980)]);
+}
+
+fn finalize(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::FinalizeLeaderboard {},
+        accounts::FinalizeLeaderboard {
+            state: STATE,
+            config: CONFIG,
+            admin: ADMIN,
+            special_prize_escrow: None,
+            system_program: None,
+        },
+    )
+}
+
+#[test]
+fn finalizing_twice_keeps_the_claim_window() {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        leaderboard_size: 1,
+        leaderboard: vec![entry(Pubkey::new_unique())],
+        pool: 10_000,
+        ..common::state()
+    };
+    let mut ledger = Ledger::new();
+    ledger.now = 1_000;
+    ledger.insert(TestAccount::program(STATE, &state, state_space(1)));
+    ledger.insert(TestAccount::program(
+        CONFIG,
+        &config(24, 0),
+        VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+    ));
+    ledger.insert(TestAccount::signer(ADMIN, 1_000_000_000));
+
+    assert_eq!(finalize(&mut ledger), Ok(()));
+    ledger.now = 5_000;
+    assert_eq!(finalize(&mut ledger), failure(ErrorCode::AlreadyFinalized));
+
+    let state = ledger.read::<State>(&STATE);
+    assert!(state.finalized);
+    assert_eq!(state.finalized_timestamp, 1_000);
+    assert_eq!(prize_window_end(&state, &config(24, 0)), 1_000 + 24 * 3600);
+}
@@ -0,0 +1,277 @@
+//! A whole game played through the instructions, with the blockhash
+//! fallback standing in for an oracle.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::{keccak, system_program, sysvar};
+use degame::*;
+
+mod common;
+
+use common::game::slot_hashes;
+use common::program::{failure, return_data, Ledger, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const PLAYER_SEED: u64 = 42;
+const ENTRY_FEE: u64 = 1_000;
+
+/// Creates competition `state` with randomness from `BlockhashFallback` and
+/// returns its config address.
+fn initialize(ledger: &mut Ledger, state: Pubkey) -> Pubkey {
+    let config = Pubkey::find_program_address(&[b"config", state.as_ref()], &degame::ID).0;
+
+    let mut args = common::config_args();
+    args.entry_fee = ENTRY_FEE;
+    args.randomness_source = RandomnessSource::BlockhashFallback;
+    args.max_side_bets_per_game = STANDARD_DECK_SIZE;
+    let initialize = instruction::Initialize {
+        start_time: NOW,
+        end_time: NOW + 86_400,
+        competition_index: 0,
+        game_tags: vec![],
+        config: args,
+    };
+    let result = ledger.process(
+        initialize,
+        accounts::Initialize {
+            state,
+            config,
+            admin: ADMIN,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+    );
+    assert_eq!(result, Ok(()));
+    config
+}
+
+struct Game {
+    ledger: Ledger,
+    config: Pubkey,
+    player: Pubkey,
+}
+
+impl Game {
+    /// A competition whose randomness comes from `BlockhashFallback`, with
+    /// `AUTHORITY`'s player account created.
+    fn new() -> Self {
+        let mut ledger = Ledger::new();
+        ledger.now = NOW;
+        ledger.insert(TestAccount::signer(ADMIN, 1_000_000_000));
+        ledger.insert(TestAccount::signer(AUTHORITY, 1_000_000_000));
+        let player = Pubkey::find_program_address(&[b"player", AUTHORITY.as_ref()], &degame::ID).0;
+        let config = initialize(&mut ledger, STATE);
+
+        let result = ledger.process(
+            instruction::CreatePlayer {},
+            accounts::CreatePlayer {
+                player,
+                authority: AUTHORITY,
+                system_program: system_program::ID,
+            },
+        );
+        assert_eq!(result, Ok(()));
+
+        Game { ledger, config, player }
+    }
+
+    fn player(&self) -> Player {
+        (*self.ledger.read::<Player>(&self.player)).clone()
+    }
+
+    /// Starts the player's next game and deals it from the fallback
+    /// randomness and `PLAYER_SEED`.
+    fn deal(&mut self, randomness: u64) {
+        let game_id = self.player().game_id + 1;
+        let (state, config, player) = (STATE, self.config, self.player);
+        let blacklist_entry = Pubkey::find_program_address(
+            &[b"blacklist", STATE.as_ref(), AUTHORITY.as_ref()],
+            &degame::ID,
+        )
+        .0;
+        let ban_list = Pubkey::find_program_address(&[b"banlist", STATE.as_ref()], &degame::ID).0;
+
+        let steps = [
+            self.ledger.process(
+                instruction::StartGame { game_id, deck_offset: 0 },
+                accounts::StartGame {
+                    state,
+                    config,
+                    player,
+                    authority: AUTHORITY,
+                    blacklist_entry,
+                    ban_list,
+                    analytics_program: None,
+                    system_program: system_program::ID,
+                },
+            ),
+            self.ledger.process(
+                instruction::CommitDeckSeed {
+                    seed_hash: keccak::hash(&PLAYER_SEED.to_le_bytes()).to_bytes(),
+                },
+                accounts::CommitDeckSeed { player, authority: AUTHORITY },
+            ),
+            // No oracle is called, so any program will do.
+            self.ledger.process(
+                instruction::RequestRandomness {},
+                accounts::RequestRandomness {
+                    state,
+                    config,
+                    randomness_program: Pubkey::new_unique(),
+                    randomness_account: Pubkey::new_unique(),
+                    admin: ADMIN,
+                    player,
+                },
+            ),
+            self.ledger.process(
+                instruction::ReceiveRandomness {
+                    randomness,
+                    proof: [0; VRF_PROOF_LEN],
+                    alpha: [0; 32],
+                },
+                accounts::ReceiveRandomness { state, config, player, authority: ADMIN },
+            ),
+            self.ledger.process(
+                instruction::RevealDeckSeed { seed: PLAYER_SEED },
+                accounts::RevealDeckSeed { state, config, player, authority: AUTHORITY },
+            ),
+        ];
+        assert_eq!(steps, [Ok(()), Ok(()), Ok(()), Ok(()), Ok(())]);
+    }
+
+    /// Bets on the more likely side of the current card, with an even-card
+    /// side bet, and settles the bet in the next slot.
+    fn bet(&mut self) {
+        let player = self.player();
+        let deck = shuffle_deck(player.deck_seed().unwrap(), 0);
+        let bet_type = if deck[player.current_card as usize].value < 8 {
+            BetType::High
+        } else {
+            BetType::Low
+        };
+
+        let result = self.ledger.process(
+            instruction::PlaceBet {
+                bet_type,
+                side_bet: Some(SideBetType::Parity { even: true }),
+                bet_amount: 0,
+            },
+            accounts::PlaceBet {
+                state: STATE,
+                config: self.config,
+                player: self.player,
+                authority: AUTHORITY,
+                player_wallet: AUTHORITY,
+                system_program: system_program::ID,
+            },
+        );
+        assert_eq!(result, Ok(()));
+
+        let mut hashes = TestAccount::missing(sysvar::slot_hashes::ID);
+        hashes.data = slot_hashes(&[(self.ledger.slot, [self.ledger.slot as u8; 32])]);
+        self.ledger.insert(hashes);
+        self.ledger.slot += 1;
+
+        let result = self.ledger.process(
+            instruction::SettleBet {},
+            accounts::SettleBet {
+                player: self.player,
+                config: self.config,
+                slot_hashes: sysvar::slot_hashes::ID,
+                instructions: sysvar::instructions::ID,
+            },
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    fn submit(&mut self, state: Pubkey, config: Pubkey) -> ProgramResult {
+        self.ledger.process(
+            instruction::SubmitScore {},
+            accounts::SubmitScore {
+                state,
+                config,
+                player: self.player,
+                authority: AUTHORITY,
+                analytics_program: None,
+                group: None,
+                group_leaderboard: None,
+            },
+        )
+    }
+
+    fn play(&mut self, randomness: u64) {
+        self.deal(randomness);
+        while !self.player().finished {
+            self.bet();
+        }
+        self.ledger.now += MIN_RANDOMNESS_INTERVAL;
+    }
+}
+
+#[test]
+fn fallback_randomness_deals_a_game_without_an_oracle() {
+    let mut game = Game::new();
+
+    game.deal(7);
+
+    let player = game.player();
+    assert!(matches!(player.status, GameStatus::Active));
+    assert_eq!(player.deck_seed(), Some(7 ^ PLAYER_SEED));
+    assert_eq!(player.deck_commitment, Some(commit_seed(7 ^ PLAYER_SEED)));
+    assert_eq!(game.ledger.read::<State>(&STATE).pool, ENTRY_FEE);
+}
+
+#[test]
+fn fallback_game_plays_through_to_the_leaderboard() {
+    let mut game = Game::new();
+
+    game.play(7);
+    let config = game.config;
+
+    assert_eq!(game.submit(STATE, config), Ok(()));
+    let player = game.player();
+    assert!(player.score_submitted);
+    assert!(player.bets_placed > 0);
+    let state = game.ledger.read::<State>(&STATE);
+    assert_eq!(state.leaderboard.len(), 1);
+    assert_eq!(state.leaderboard[0].player, AUTHORITY);
+}
+
+#[test]
+fn game_is_submitted_only_to_its_own_competition() {
+    let mut game = Game::new();
+    let other = Pubkey::new_unique();
+    let other_config = initialize(&mut game.ledger, other);
+
+    game.play(7);
+
+    assert_eq!(game.player().state, STATE);
+    assert_eq!(game.submit(other, other_config), failure(ErrorCode::PlayerNotInCompetition));
+    assert!(game.ledger.read::<State>(&other).leaderboard.is_empty());
+}
+
+#[test]
+fn summary_totals_the_games_played() {
+    let mut game = Game::new();
+    for randomness in [7, 8, 9] {
+        game.play(randomness);
+    }
+
+    let result = game.ledger.process(
+        instruction::GetPlayerSummary {},
+        accounts::GetPlayerSummary { player: game.player },
+    );
+
+    assert_eq!(result, Ok(()));
+    let summary: PlayerSummary = return_data();
+    let player = game.player();
+    assert_eq!(summary.total_games, 3);
+    assert_eq!(summary.daily_games_remaining, MAX_DAILY_GAMES - 3);
+    assert_eq!(summary.best_multiplier, player.best_multiplier);
+    assert_eq!(summary.total_side_bet_score, player.total_side_bet_score);
+    assert_ne!(summary.total_side_bet_score, 0);
+    assert!(summary.best_multiplier > 1.0);
+}
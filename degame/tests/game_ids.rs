@@ -0,0 +1,26 @@
+use degame::*;
+
+mod common;
+
+fn player(total_games: u32, game_id: u64) -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.total_games = total_games;
+    player.game_id = game_id;
+    player
+}
+
+#[test]
+fn first_game_may_use_any_id() {
+    check_game_id(&player(0, 0), 0).unwrap();
+    check_game_id(&player(0, 0), 42).unwrap();
+}
+
+#[test]
+fn reused_or_older_game_id_is_rejected() {
+    let player = player(3, 42);
+
+    for game_id in [42, 41, 0] {
+        assert_eq!(check_game_id(&player, game_id).unwrap_err(), ErrorCode::GameIdReused.into());
+    }
+    check_game_id(&player, 43).unwrap();
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AnchorDeserialize;
+use degame::*;
+
+const HOOK: Pubkey = Pubkey::new_from_array([6; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+const TROPHY: Pubkey = Pubkey::new_from_array([7; 32]);
+const LEDGER: Pubkey = Pubkey::new_from_array([8; 32]);
+
+/// Stand-in for a hook program: accepts only `on_game_over` calls and returns
+/// what it was told about the finished game.
+fn mock_hook(instruction: &Instruction) -> Option<GameOverHookArgs> {
+    if instruction.program_id != HOOK
+        || instruction.data[..8] != GAME_OVER_HOOK_DISCRIMINATOR
+        || instruction.accounts[0].pubkey != PLAYER
+    {
+        return None;
+    }
+    GameOverHookArgs::deserialize(&mut &instruction.data[8..]).ok()
+}
+
+fn args() -> GameOverHookArgs {
+    GameOverHookArgs {
+        player: PLAYER,
+        game_id: 12,
+        final_multiplier: 4.0,
+        side_bet_score: -3,
+        reason: GameEndReason::LostBet,
+        final_score: 4 * MULTIPLIER_SCALE,
+    }
+}
+
+#[test]
+fn hook_receives_the_finished_game() {
+    let instruction = build_game_over_hook_instruction(HOOK, &args(), &[]).unwrap();
+    let received = mock_hook(&instruction).unwrap();
+
+    assert_eq!(received.player, PLAYER);
+    assert_eq!(received.game_id, 12);
+    assert_eq!(received.final_multiplier, 4.0);
+    assert_eq!(received.side_bet_score, -3);
+    assert_eq!(received.final_score, 4 * MULTIPLIER_SCALE);
+    assert!(matches!(received.reason, GameEndReason::LostBet));
+}
+
+#[test]
+fn hook_accounts_are_forwarded_without_signing() {
+    let (mut trophy_lamports, mut ledger_lamports) = (0, 0);
+    let (mut trophy_data, mut ledger_data) = (vec![], vec![]);
+    let accounts = [
+        AccountInfo::new(&TROPHY, true, true, &mut trophy_lamports, &mut trophy_data, &HOOK, false, 0),
+        AccountInfo::new(&LEDGER, false, false, &mut ledger_lamports, &mut ledger_data, &HOOK, false, 0),
+    ];
+
+    let instruction = build_game_over_hook_instruction(HOOK, &args(), &accounts).unwrap();
+    let metas = &instruction.accounts;
+
+    assert_eq!(metas.len(), 3);
+    assert!(!metas[0].is_writable);
+    assert_eq!((metas[1].pubkey, metas[1].is_writable), (TROPHY, true));
+    assert_eq!((metas[2].pubkey, metas[2].is_writable), (LEDGER, false));
+    assert!(metas.iter().all(|meta| !meta.is_signer));
+}
+
+#[test]
+fn hook_rejects_other_programs() {
+    let instruction =
+        build_game_over_hook_instruction(Pubkey::new_from_array([9; 32]), &args(), &[]).unwrap();
+    assert!(mock_hook(&instruction).is_none());
+}
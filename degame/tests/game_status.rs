@@ -0,0 +1,51 @@
+use degame::*;
+
+mod common;
+
+fn player() -> VersionedAccount<Player> {
+    common::player()
+}
+
+#[test]
+fn lapsed_bet_windows_time_the_game_out() {
+    assert_eq!(GameStatus::ended_by(GameEndReason::BetTimeExpired), GameStatus::TimedOut);
+}
+
+#[test]
+fn other_endings_finish_the_game() {
+    for reason in [
+        GameEndReason::LostBet,
+        GameEndReason::DeckExhausted,
+        GameEndReason::BetLimitReached,
+    ] {
+        assert_eq!(GameStatus::ended_by(reason), GameStatus::Finished);
+    }
+}
+
+#[test]
+fn finishing_marks_the_player_finished() {
+    let mut player = player();
+    player.status = GameStatus::Active;
+    player.finish(GameEndReason::BetTimeExpired);
+
+    assert!(player.finished);
+    assert_eq!(player.status, GameStatus::TimedOut);
+}
+
+#[cfg(feature = "test-rng")]
+#[test]
+fn status_follows_the_game_lifecycle() {
+    let mut player = player();
+    // As `start_game` leaves it.
+    player.status = GameStatus::AwaitingRandomness;
+
+    inject_test_seed(&mut player, 42, 0).unwrap();
+    assert_eq!(player.status, GameStatus::Active);
+
+    // A rejected injection, like a failed bet, leaves the game resumable.
+    assert!(inject_test_seed(&mut player, 42, 0).is_err());
+    assert_eq!(player.status, GameStatus::Active);
+
+    player.finish(GameEndReason::LostBet);
+    assert_eq!(player.status, GameStatus::Finished);
+}
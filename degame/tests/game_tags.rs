@@ -0,0 +1,29 @@
+use degame::*;
+
+mod common;
+
+fn state() -> State {
+    State {
+        leaderboard_size: 3,
+        ..common::state()
+    }
+}
+
+#[test]
+fn at_most_three_tags() {
+    let mut state = state();
+    for tag in [GameTag::Beginner, GameTag::Tournament, GameTag::Sponsored] {
+        state.add_tag(tag).unwrap();
+    }
+
+    assert_eq!(state.game_tags, vec![GameTag::Beginner, GameTag::Tournament, GameTag::Sponsored]);
+    assert_eq!(state.add_tag(GameTag::Private).unwrap_err(), ErrorCode::TooManyTags.into());
+}
+
+#[test]
+fn duplicate_tag_is_rejected() {
+    let mut state = state();
+    state.add_tag(GameTag::Practice).unwrap();
+
+    assert_eq!(state.add_tag(GameTag::Practice).unwrap_err(), ErrorCode::DuplicateGameTag.into());
+}
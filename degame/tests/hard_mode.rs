@@ -0,0 +1,38 @@
+use degame::*;
+
+mod common;
+
+const SEED: u64 = 7;
+
+/// A player ten cards into a hard-mode deck, as `start_game` leaves them.
+fn hard_mode_player() -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    player.deck_offset = 10;
+    player.cards_dealt = 10;
+    player
+}
+
+#[test]
+fn first_card_dealt_is_at_the_offset() {
+    let deck = shuffle_deck(SEED, 0);
+    let mut player = hard_mode_player();
+    let bet = if deck[11].value > deck[10].value { BetType::High } else { BetType::Low };
+
+    let outcome = resolve_bet(&mut player, &deck, &bet, None, false, SideBetCard::Current).unwrap();
+
+    assert_eq!(outcome.correct, deck[11].value != deck[10].value);
+    assert_eq!(player.cards_dealt, 11);
+}
+
+#[test]
+fn hard_mode_bonus_is_added_to_the_score() {
+    let state = common::versioned_state();
+    let mut config = common::config();
+    config.score_formula.multiplier_weight = BPS_DENOMINATOR as u16;
+    config.hard_mode_bonus_per_card = 500;
+    let player = hard_mode_player();
+
+    assert_eq!(hard_mode_bonus(&player, &config), Some(5_000));
+    assert_eq!(calculate_total_score(&player, &state, &config, 0).unwrap(), 10_000 + 5_000);
+}
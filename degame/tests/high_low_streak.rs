@@ -0,0 +1,80 @@
+use degame::*;
+
+mod common;
+
+fn card(value: u8) -> Card {
+    Card { suit: "Clubs".to_string(), value }
+}
+
+fn deck(values: &[u8]) -> Vec<Card> {
+    values.iter().copied().map(card).collect()
+}
+
+fn bet(player: &mut Player, deck: &[Card], bet_type: BetType, side_bet: Option<SideBetType>) -> BetOutcome {
+    resolve_bet(player, deck, &bet_type, side_bet, false, SideBetCard::Current).unwrap()
+}
+
+/// Player who won a high bet on the first card of `deck`, predicting the
+/// next bet is `correct`.
+fn predicted(deck: &[Card], correct: bool) -> Player {
+    let mut player = common::player();
+    player.multiplier = 1.0;
+
+    let outcome = bet(&mut player, deck, BetType::High, Some(SideBetType::HighLowStreak { correct }));
+    assert!(outcome.correct);
+    assert_eq!(outcome.side_bet_result, None);
+    assert_eq!(player.pending_streak_bet, Some(correct));
+    (*player).clone()
+}
+
+#[test]
+fn correct_prediction_pays_on_the_next_bet() {
+    let deck = deck(&[2, 9, 5, 13]);
+    let mut player = predicted(&deck, true);
+
+    let outcome = bet(&mut player, &deck, BetType::Low, None);
+    assert!(outcome.correct);
+    assert_eq!(outcome.side_bet_result, Some(STREAK_SIDE_BET_PAYOUT));
+    assert_eq!(player.pending_streak_bet, None);
+}
+
+#[test]
+fn predicting_a_loss_pays_when_the_bet_loses() {
+    let deck = deck(&[2, 9, 5, 13]);
+    let mut player = predicted(&deck, false);
+
+    let outcome = bet(&mut player, &deck, BetType::High, None);
+    assert!(!outcome.correct);
+    assert_eq!(outcome.side_bet_result, Some(STREAK_SIDE_BET_PAYOUT));
+}
+
+#[test]
+fn wrong_prediction_loses() {
+    let deck = deck(&[2, 9, 5, 13]);
+    let mut player = predicted(&deck, true);
+
+    let outcome = bet(&mut player, &deck, BetType::High, None);
+    assert!(!outcome.correct);
+    assert_eq!(outcome.side_bet_result, Some(-1));
+    assert_eq!(player.pending_streak_bet, None);
+}
+
+#[test]
+fn joker_settles_the_streak_as_correct() {
+    let deck = deck(&[2, JOKER_VALUE, 5, 9]);
+    let mut player = predicted(&deck, true);
+
+    let outcome = bet(&mut player, &deck, BetType::Low, None);
+    assert_eq!(outcome.side_bet_result, Some(STREAK_SIDE_BET_PAYOUT));
+}
+
+#[test]
+fn new_streak_bet_is_placed_as_the_last_settles() {
+    let deck = deck(&[2, 9, 5, 13]);
+    let mut player = predicted(&deck, true);
+
+    let streak = Some(SideBetType::HighLowStreak { correct: false });
+    let outcome = bet(&mut player, &deck, BetType::Low, streak);
+    assert_eq!(outcome.side_bet_result, Some(STREAK_SIDE_BET_PAYOUT));
+    assert_eq!(player.pending_streak_bet, Some(false));
+}
@@ -0,0 +1,50 @@
+use degame::*;
+
+mod common;
+
+fn card(value: u8) -> Card {
+    Card { suit: "Clubs".to_string(), value }
+}
+
+fn player(hot_values: [u8; 2]) -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    player.hot_values = hot_values;
+    player
+}
+
+#[test]
+fn hot_values_are_the_first_and_middle_cards() {
+    let deck = shuffle_deck(11, 0);
+    assert_eq!(hot_values(&deck), [deck[0].value, deck[HOT_CARD_MIDDLE_INDEX].value]);
+}
+
+#[test]
+fn hot_card_later_in_the_deck_doubles_the_gain() {
+    // The first card's value, 5, comes round again as the third card.
+    let deck = vec![card(5), card(9), card(5), card(12), card(2)];
+    let mut player = player(hot_values(&deck));
+    player.cards_dealt = 2;
+
+    let normal = bet_multiplier_gain(&deck, 2, BetType::High, false).unwrap();
+    let outcome = resolve_bet(&mut player, &deck, &BetType::High, None, false, SideBetCard::Current)
+        .unwrap();
+
+    assert!(outcome.correct);
+    assert_eq!(outcome.hot_card, Some(5));
+    assert_eq!(outcome.multiplier_gain, normal * 2.0);
+}
+
+#[test]
+fn other_cards_keep_the_normal_gain() {
+    let deck = vec![card(5), card(9), card(5), card(12), card(2)];
+    let mut player = player([5, 2]);
+    player.cards_dealt = 1;
+
+    let normal = bet_multiplier_gain(&deck, 1, BetType::Low, false).unwrap();
+    let outcome = resolve_bet(&mut player, &deck, &BetType::Low, None, false, SideBetCard::Current)
+        .unwrap();
+
+    assert_eq!(outcome.hot_card, None);
+    assert_eq!(outcome.multiplier_gain, normal);
+}
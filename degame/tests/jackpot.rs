@@ -0,0 +1,133 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{emitted, failure, process, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const JACKPOT: u64 = 7_500;
+const THRESHOLD_BPS: u64 = 5 * MULTIPLIER_SCALE;
+
+/// Accounts for `claim_jackpot` on a finished game that ended at
+/// `multiplier`, in a competition holding `JACKPOT` that pays out above
+/// `threshold_bps`.
+fn accounts(multiplier: f64, threshold_bps: u64) -> Vec<TestAccount> {
+    let state = State {
+        config: CONFIG,
+        pool: 10_000,
+        jackpot: JACKPOT,
+        ..common::state()
+    };
+    let mut config = common::config();
+    config.jackpot_threshold_bps = threshold_bps;
+    let mut player = common::player();
+    player.authority = AUTHORITY;
+    player.state = STATE;
+    player.finished = true;
+    player.multiplier = multiplier;
+
+    vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN),
+        TestAccount::program(PLAYER, &*player, player_space()),
+        TestAccount::signer(AUTHORITY, 0),
+    ]
+}
+
+fn claim_jackpot(accounts: &mut [TestAccount]) -> ProgramResult {
+    process(instruction::ClaimJackpot {}, accounts, 0)
+}
+
+#[test]
+fn game_above_the_threshold_takes_the_whole_jackpot() {
+    let mut accounts = accounts(5.5, THRESHOLD_BPS);
+    let state_lamports = accounts[0].lamports;
+
+    assert_eq!(claim_jackpot(&mut accounts), Ok(()));
+    let state = accounts[0].read::<State>();
+    assert_eq!(state.jackpot, 0);
+    assert_eq!(state.pool, 10_000);
+    assert_eq!(accounts[0].lamports, state_lamports - JACKPOT);
+    assert_eq!(accounts[3].lamports, JACKPOT);
+    assert!(accounts[2].read::<Player>().jackpot_claimed);
+
+    let [event] = &emitted::<JackpotClaimed>()[..] else { panic!("expected one JackpotClaimed") };
+    assert_eq!((event.state, event.player, event.amount), (STATE, PLAYER, JACKPOT));
+}
+
+#[test]
+fn a_game_wins_the_jackpot_once() {
+    let mut accounts = accounts(5.5, THRESHOLD_BPS);
+    assert_eq!(claim_jackpot(&mut accounts), Ok(()));
+
+    let mut state = accounts[0].read::<State>();
+    state.jackpot = JACKPOT;
+    accounts[0] = TestAccount::program(STATE, &*state, state_space(MAX_LEADERBOARD_SIZE as u8));
+    assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotNotWon));
+}
+
+#[test]
+fn game_at_or_below_the_threshold_wins_nothing() {
+    for multiplier in [5.0, 2.0] {
+        let mut accounts = accounts(multiplier, THRESHOLD_BPS);
+
+        assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotNotWon));
+    }
+}
+
+#[test]
+fn jackpot_without_a_threshold_cannot_be_won() {
+    let mut accounts = accounts(50.0, 0);
+
+    assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotNotWon));
+}
+
+#[test]
+fn unfinished_game_cannot_claim() {
+    let mut accounts = accounts(5.5, THRESHOLD_BPS);
+    let mut player = accounts[2].read::<Player>();
+    player.finished = false;
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+
+    assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotNotWon));
+}
+
+#[test]
+fn refunded_game_cannot_claim() {
+    let mut accounts = accounts(5.5, THRESHOLD_BPS);
+    let mut player = accounts[2].read::<Player>();
+    player.status = GameStatus::Refunded;
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+
+    assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotNotWon));
+}
+
+#[test]
+fn empty_jackpot_cannot_be_claimed() {
+    let mut accounts = accounts(5.5, THRESHOLD_BPS);
+    let mut state = accounts[0].read::<State>();
+    state.jackpot = 0;
+    accounts[0] = TestAccount::program(STATE, &*state, state_space(MAX_LEADERBOARD_SIZE as u8));
+
+    assert_eq!(claim_jackpot(&mut accounts), failure(ErrorCode::JackpotEmpty));
+}
+
+#[test]
+fn entry_fee_split_keeps_every_lamport() {
+    assert_eq!(split_entry_fee(1_000, 2_500).unwrap(), (750, 250));
+    assert_eq!(split_entry_fee(999, 1).unwrap(), (999, 0));
+    assert_eq!(split_entry_fee(1_000, 0).unwrap(), (1_000, 0));
+}
+
+#[test]
+fn contribution_above_the_whole_fee_is_refused() {
+    let mut args = common::config_args();
+    args.jackpot_contribution_bps = BPS_DENOMINATOR as u16 + 1;
+
+    assert_eq!(args.validate().unwrap_err(), ErrorCode::InvalidJackpotContribution.into());
+}
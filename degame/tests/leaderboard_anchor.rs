@@ -0,0 +1,99 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const COMPETITION_INDEX: u32 = 4;
+const CID: [u8; IPFS_CID_LEN] = [b'b'; IPFS_CID_LEN];
+const LEADERBOARD_DATA: &[u8] = b"1. alice 42000\n2. bob 31000\n";
+
+fn anchor_key() -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"anchor", STATE.as_ref(), &COMPETITION_INDEX.to_le_bytes()],
+        &degame::ID,
+    )
+    .0
+}
+
+fn ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        competition_index: COMPETITION_INDEX,
+        ..common::state()
+    };
+
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::signer(ADMIN, 1_000_000_000));
+    ledger
+}
+
+fn anchor(ledger: &mut Ledger, admin: Pubkey, hash: [u8; 32]) -> ProgramResult {
+    ledger.process(
+        instruction::AnchorLeaderboardData { cid: CID, hash },
+        accounts::AnchorLeaderboardData {
+            state: STATE,
+            leaderboard_anchor: anchor_key(),
+            admin,
+            system_program: system_program::ID,
+        },
+    )
+}
+
+fn verify(ledger: &mut Ledger, raw_data: &[u8]) -> ProgramResult {
+    ledger.process(
+        instruction::VerifyLeaderboardData { raw_data: raw_data.to_vec() },
+        accounts::VerifyLeaderboardData {
+            state: STATE,
+            leaderboard_anchor: anchor_key(),
+        },
+    )
+}
+
+#[test]
+fn anchor_stores_the_cid_and_hash() {
+    let mut ledger = ledger();
+    let data_hash = hash(LEADERBOARD_DATA).to_bytes();
+
+    assert_eq!(anchor(&mut ledger, ADMIN, data_hash), Ok(()));
+    let stored = ledger.read::<LeaderboardAnchor>(&anchor_key());
+    assert_eq!((stored.ipfs_cid, stored.data_hash), (CID, data_hash));
+}
+
+#[test]
+fn anchored_data_verifies() {
+    let mut ledger = ledger();
+    assert_eq!(anchor(&mut ledger, ADMIN, hash(LEADERBOARD_DATA).to_bytes()), Ok(()));
+
+    assert_eq!(verify(&mut ledger, LEADERBOARD_DATA), Ok(()));
+}
+
+#[test]
+fn altered_data_does_not_verify() {
+    let mut ledger = ledger();
+    assert_eq!(anchor(&mut ledger, ADMIN, hash(LEADERBOARD_DATA).to_bytes()), Ok(()));
+
+    assert_eq!(
+        verify(&mut ledger, b"1. alice 42000\n2. bob 99000\n"),
+        failure(ErrorCode::LeaderboardDataMismatch)
+    );
+}
+
+#[test]
+fn only_the_admin_anchors_data() {
+    let mut ledger = ledger();
+    let stranger = Pubkey::new_unique();
+    ledger.insert(TestAccount::signer(stranger, 1_000_000_000));
+
+    assert_eq!(
+        anchor(&mut ledger, stranger, hash(LEADERBOARD_DATA).to_bytes()),
+        failure(ErrorCode::Unauthorized)
+    );
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use degame::*;
+
+const PLAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+#[test]
+fn legacy_entries_gain_default_finished_at() {
+    let legacy = LegacyState {
+        admin: Pubkey::new_from_array([2; 32]),
+        config: Pubkey::new_from_array([3; 32]),
+        start_time: 10,
+        end_time: 20,
+        leaderboard_size: 3,
+        leaderboard: vec![LegacyLeaderboardEntry {
+            player: PLAYER,
+            nickname: "ace".to_string(),
+            score: 15_000,
+            side_bet_score: -1,
+            claimed: true,
+        }],
+        finalized: true,
+        finalized_timestamp: 30,
+        pool: 1_000,
+        accounts_closed: 0,
+        participants: 4,
+        competition_index: 5,
+    };
+    let mut data = legacy.try_to_vec().unwrap();
+    // Stored accounts are zero-padded to their allocated size.
+    data.resize(data.len() + 64, 0);
+
+    let state = decode_legacy_state(&data).unwrap();
+
+    assert_eq!(state.leaderboard.len(), 1);
+    let entry = &state.leaderboard[0];
+    assert_eq!((entry.player, entry.nickname.as_str()), (PLAYER, "ace"));
+    assert_eq!((entry.score, entry.side_bet_score, entry.claimed), (15_000, -1, true));
+    assert_eq!(entry.finished_at, 0);
+    assert_eq!((state.finalized_timestamp, state.pool, state.competition_index), (30, 1_000, 5));
+    assert_eq!(state.prize_pool, 1_000);
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+fn entry(seed: u8, score: u64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        player: Pubkey::new_from_array([seed; 32]),
+        nickname: String::new(),
+        score,
+        side_bet_score: 0,
+        claimed: false,
+        finished_at: 0,
+    }
+}
+
+fn state(scores: &[u64]) -> VersionedAccount<State> {
+    let mut state = common::versioned_state();
+    state.leaderboard_size = DEFAULT_LEADERBOARD_SIZE;
+    for (seed, score) in scores.iter().enumerate() {
+        state.upsert_entry(entry(seed as u8, *score));
+    }
+    state.rank_leaderboard();
+    state
+}
+
+#[test]
+fn pages_split_the_ranked_leaderboard() {
+    let state = state(&[10, 40, 30, 20, 50]);
+    let epoch = state.leaderboard_snapshot_epoch;
+
+    let scores = |page| {
+        state.leaderboard_page(page, 2, epoch).unwrap().iter().map(|e| e.score).collect::<Vec<_>>()
+    };
+    assert_eq!(scores(0), [50, 40]);
+    assert_eq!(scores(1), [30]);
+    assert!(scores(2).is_empty());
+    assert_eq!(state.leaderboard_page(0, 0, epoch).err(), Some(ErrorCode::InvalidPageSize.into()));
+}
+
+#[test]
+fn submission_between_pages_is_detected() {
+    let mut state = state(&[10, 40, 30]);
+    let epoch = state.leaderboard_snapshot_epoch;
+    state.leaderboard_page(0, 2, epoch).unwrap();
+
+    state.upsert_entry(entry(9, 35));
+    state.rank_leaderboard();
+
+    assert_eq!(
+        state.leaderboard_page(1, 2, epoch).err(),
+        Some(ErrorCode::LeaderboardModified.into())
+    );
+    let epoch = state.leaderboard_snapshot_epoch;
+    assert_eq!(state.leaderboard_page(0, 2, epoch).unwrap()[1].score, 35);
+}
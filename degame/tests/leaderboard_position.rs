@@ -0,0 +1,22 @@
+use degame::*;
+
+#[test]
+fn positions_on_the_leaderboard_are_accepted() {
+    let first = LeaderboardPosition::new(0, 3).unwrap();
+    let last = LeaderboardPosition::new(2, 3).unwrap();
+
+    assert!(first.is_first());
+    assert_eq!((last.get(), last.index()), (2, 2));
+    assert!(!last.is_first());
+}
+
+#[test]
+fn out_of_range_position_cannot_be_built() {
+    assert_eq!(LeaderboardPosition::new(3, 3).unwrap_err(), ErrorCode::NotOnLeaderboard.into());
+    assert_eq!(LeaderboardPosition::new(u8::MAX, 3).unwrap_err(), ErrorCode::NotOnLeaderboard.into());
+}
+
+#[test]
+fn empty_leaderboard_has_no_positions() {
+    assert_eq!(LeaderboardPosition::new(0, 0).unwrap_err(), ErrorCode::NotOnLeaderboard.into());
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+fn entry(seed: u8, score: u64, finished_at: i64, side_bet_score: i64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        side_bet_score,
+        finished_at,
+        ..common::entry(Pubkey::new_from_array([seed; 32]), score)
+    }
+}
+
+fn ranked(entries: Vec<LeaderboardEntry>) -> Vec<u8> {
+    let mut state = State {
+        leaderboard_size: MAX_LEADERBOARD_SIZE as u8,
+        ..common::state()
+    };
+    for entry in entries {
+        state.upsert_entry(entry);
+    }
+    state.rank_leaderboard();
+    state.leaderboard.iter().map(|entry| entry.player.to_bytes()[0]).collect()
+}
+
+#[test]
+fn score_ranks_first() {
+    assert_eq!(ranked(vec![entry(1, 10, 100, 9), entry(2, 20, 200, 0)]), vec![2, 1]);
+}
+
+#[test]
+fn tied_scores_rank_the_earlier_finisher_first() {
+    assert_eq!(ranked(vec![entry(1, 10, 200, 9), entry(2, 10, 100, 0)]), vec![2, 1]);
+}
+
+#[test]
+fn side_bets_break_ties_on_score_and_finish() {
+    assert_eq!(ranked(vec![entry(1, 10, 100, 1), entry(2, 10, 100, 5)]), vec![2, 1]);
+}
+
+#[test]
+fn resubmitting_does_not_lose_the_earlier_finish() {
+    let entries = vec![
+        entry(1, 10, 100, 0),
+        entry(2, 10, 200, 0),
+        // Resubmitted entries move to the end of the board before ranking.
+        entry(1, 10, 100, 0),
+    ];
+
+    assert_eq!(ranked(entries), vec![1, 2]);
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator};
+use degame::*;
+
+mod common;
+
+use common::program::{Ledger, TestAccount};
+
+fn state() -> VersionedAccount<State> {
+    let data = [&State::DISCRIMINATOR[..], &[LAYOUT_VERSION], &[0; State::BASE_LEN]].concat();
+    VersionedAccount::try_deserialize(&mut &data[..]).unwrap()
+}
+
+fn entry(seed: u8) -> LeaderboardEntry {
+    LeaderboardEntry {
+        player: Pubkey::new_from_array([seed; 32]),
+        nickname: "n".repeat(MAX_NICKNAME_LEN),
+        score: seed.into(),
+        side_bet_score: 0,
+        claimed: false,
+        finished_at: 0,
+    }
+}
+
+#[test]
+fn growing_the_board_reallocs_to_fit_it() {
+    let mut state = state();
+    state.resize_leaderboard(1).unwrap();
+    state.resize_leaderboard(3).unwrap();
+    assert_eq!(state.leaderboard_size, 3);
+    assert!(state_space(3) > state_space(1));
+
+    for seed in 1..=4 {
+        state.upsert_entry(entry(seed));
+    }
+    state.rank_leaderboard();
+    assert_eq!(state.leaderboard.len(), 3);
+
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    assert!(data.len() > state_space(1) && data.len() <= state_space(3));
+}
+
+#[test]
+fn size_must_stay_within_bounds() {
+    let mut state = state();
+    for size in [0, MAX_LEADERBOARD_SIZE as u8 + 1] {
+        assert_eq!(state.resize_leaderboard(size).unwrap_err(), ErrorCode::InvalidLeaderboardSize.into());
+    }
+}
+
+#[test]
+fn size_is_locked_once_play_begins() {
+    let mut funded = state();
+    funded.pool = 1;
+    let mut scored = state();
+    scored.upsert_entry(entry(1));
+
+    for mut state in [funded, scored] {
+        assert_eq!(state.resize_leaderboard(2).unwrap_err(), ErrorCode::LeaderboardLocked.into());
+    }
+}
+
+#[test]
+fn a_new_competition_grows_its_board_past_the_default_size() {
+    let admin = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+    let mut state = state();
+    state.admin = admin;
+    state.leaderboard_size = DEFAULT_LEADERBOARD_SIZE;
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(key, &*state, state_space(DEFAULT_LEADERBOARD_SIZE)));
+    ledger.insert(TestAccount::signer(admin, 1_000_000_000));
+
+    let size = MAX_LEADERBOARD_SIZE as u8;
+    ledger
+        .process(
+            instruction::SetLeaderboardSize { size },
+            accounts::SetLeaderboardSize { state: key, admin, system_program: system_program::ID },
+        )
+        .unwrap();
+    assert_eq!(ledger.get(&key).data.len(), state_space(size));
+
+    let mut state = ledger.read::<State>(&key);
+    assert_eq!(state.leaderboard_size, size);
+    for seed in 1..=size + 1 {
+        state.upsert_entry(entry(seed));
+    }
+    state.rank_leaderboard();
+    assert_eq!(state.leaderboard.len(), MAX_LEADERBOARD_SIZE);
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= state_space(size));
+}
@@ -0,0 +1,218 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const HOUR: i64 = 3600;
+const STATE: Pubkey = Pubkey::new_from_array([21; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([22; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([25; 32]);
+
+fn state() -> State {
+    State {
+        leaderboard_size: 3,
+        finalized: true,
+        pool: 10_000,
+        prize_pool: 10_000,
+        ..common::state()
+    }
+}
+
+fn player(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n + 1; 32])
+}
+
+/// Leaves of five players, ranked by score descending.
+fn leaves() -> Vec<[u8; 32]> {
+    (0..5u8)
+        .map(|position| {
+            let score = 500 - u64::from(position) * 50;
+            leaderboard_leaf(&player(position), score, position)
+        })
+        .collect()
+}
+
+/// Levels of the tree, leaves first. A node without a sibling moves up as is.
+fn levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { merkle_parent(pair[0], pair[1]) } else { pair[0] })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn proof(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+fn merkle_state() -> (State, Vec<[u8; 32]>) {
+    let levels = levels(leaves());
+    let mut state = state();
+    state.leaderboard_root = Some(levels.last().unwrap()[0]);
+    state.leaderboard_root_entries = 5;
+    let proof = proof(&levels, 1);
+    (state, proof)
+}
+
+fn second() -> LeaderboardPosition {
+    LeaderboardPosition::new(1, 5).unwrap()
+}
+
+#[test]
+fn valid_proof_claims_the_position() {
+    let (mut state, proof) = merkle_state();
+
+    state.claim_merkle_position(second(), &player(1), 450, &proof).unwrap();
+    assert_eq!(state.merkle_claimed, 0b10);
+}
+
+#[test]
+fn tampered_proof_is_rejected() {
+    let (mut state, mut proof) = merkle_state();
+    proof[0][0] ^= 1;
+
+    assert_eq!(
+        state.claim_merkle_position(second(), &player(1), 450, &proof).unwrap_err(),
+        ErrorCode::InvalidMerkleProof.into()
+    );
+    assert_eq!(state.merkle_claimed, 0);
+}
+
+#[test]
+fn proof_is_bound_to_player_score_and_position() {
+    let (mut state, proof) = merkle_state();
+    let first = LeaderboardPosition::new(0, 5).unwrap();
+
+    let forgeries = [
+        (second(), player(2), 450),
+        (second(), player(1), 451),
+        (first, player(1), 450),
+    ];
+    for (position, player, score) in forgeries {
+        assert_eq!(
+            state.claim_merkle_position(position, &player, score, &proof).unwrap_err(),
+            ErrorCode::InvalidMerkleProof.into()
+        );
+    }
+}
+
+#[test]
+fn position_is_claimed_once() {
+    let (mut state, proof) = merkle_state();
+    state.claim_merkle_position(second(), &player(1), 450, &proof).unwrap();
+
+    assert_eq!(
+        state.claim_merkle_position(second(), &player(1), 450, &proof).unwrap_err(),
+        ErrorCode::PrizeAlreadyClaimed.into()
+    );
+}
+
+#[test]
+fn claims_need_a_published_root() {
+    let (_, proof) = merkle_state();
+
+    assert_eq!(
+        state().claim_merkle_position(second(), &player(1), 450, &proof).unwrap_err(),
+        ErrorCode::InvalidMerkleProof.into()
+    );
+}
+
+/// The Merkle competition, finalized at `NOW` under a 24-hour dispute window,
+/// with the second player's wallet funded.
+fn ledger() -> Ledger {
+    let (state, _) = merkle_state();
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        finalized_timestamp: NOW,
+        ..state
+    };
+    let mut args = common::config_args();
+    args.dispute_window_hours = DEFAULT_DISPUTE_WINDOW_HOURS;
+    let config = CompetitionConfig::new(args).unwrap();
+
+    let mut ledger = Ledger::new();
+    ledger.now = NOW;
+    let mut state_account = TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8));
+    state_account.lamports += state.pool;
+    ledger.insert(state_account);
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::signer(player(1), 1_000_000_000));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn claim_escrow() -> Pubkey {
+    Pubkey::find_program_address(&[b"claim_escrow", STATE.as_ref(), player(1).as_ref()], &degame::ID).0
+}
+
+fn claim_second(ledger: &mut Ledger) -> ProgramResult {
+    let (_, proof) = merkle_state();
+    ledger.process(
+        instruction::ClaimPrizeMerkle { position: 1, score: 450, proof },
+        accounts::ClaimPrizeMerkle {
+            state: STATE,
+            config: CONFIG,
+            player: player(1),
+            claim_escrow: claim_escrow(),
+            admin: ADMIN,
+            analytics_program: None,
+            guarantee_reserve: None,
+            system_program: system_program::ID,
+        },
+    )
+}
+
+#[test]
+fn merkle_claims_wait_for_the_dispute_window() {
+    let mut ledger = ledger();
+    assert_eq!(claim_second(&mut ledger), failure(ErrorCode::DisputeWindowActive));
+    assert_eq!(ledger.read::<State>(&STATE).merkle_claimed, 0);
+
+    ledger.now = NOW + i64::from(DEFAULT_DISPUTE_WINDOW_HOURS) * HOUR;
+    assert_eq!(claim_second(&mut ledger), Ok(()));
+    assert_eq!(ledger.read::<State>(&STATE).merkle_claimed, 0b10);
+}
+
+#[test]
+fn merkle_prize_is_parked_in_the_claim_escrow() {
+    let mut ledger = ledger();
+    ledger.now = NOW + i64::from(DEFAULT_DISPUTE_WINDOW_HOURS) * HOUR;
+    let state = ledger.read::<State>(&STATE);
+    let config = ledger.read::<CompetitionConfig>(&CONFIG);
+    let (_, net_prize) = calculate_prize(&state, &config, 1).unwrap();
+
+    assert_eq!(claim_second(&mut ledger), Ok(()));
+    let escrow = ledger.read::<ClaimEscrow>(&claim_escrow());
+    assert_eq!((escrow.owner, escrow.owed), (player(1), net_prize));
+}
+
+#[test]
+fn held_prizes_cannot_be_claimed_through_the_merkle_root() {
+    let mut ledger = ledger();
+    ledger.now = NOW + i64::from(DEFAULT_DISPUTE_WINDOW_HOURS) * HOUR;
+    let mut state = ledger.read::<State>(&STATE);
+    state.prizes_held = true;
+    ledger.insert(TestAccount::program(STATE, &*state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+
+    assert_eq!(claim_second(&mut ledger), failure(ErrorCode::PrizesHeldForReview));
+    assert_eq!(ledger.read::<State>(&STATE).merkle_claimed, 0);
+}
@@ -0,0 +1,21 @@
+use degame::*;
+
+#[test]
+fn competition_score_sums_the_best_rounds() {
+    let mut scores = Vec::new();
+    let mut total = 0;
+    for score in [100, 200, 50, 300, 150] {
+        scores.push(score);
+        total = top_rounds_total(&mut scores, 3).unwrap();
+    }
+
+    assert_eq!(total, 200 + 300 + 150);
+    assert_eq!(scores, vec![300, 200, 150]);
+}
+
+#[test]
+fn single_round_keeps_the_best_game() {
+    let mut scores = vec![100, 400, 250];
+
+    assert_eq!(top_rounds_total(&mut scores, 1).unwrap(), 400);
+}
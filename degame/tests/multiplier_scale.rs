@@ -0,0 +1,45 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+const PLAYER: Pubkey = Pubkey::new_from_array([7; 32]);
+
+fn player(multiplier: f64) -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.multiplier = multiplier;
+    player.peak_multiplier = multiplier_to_bps(multiplier);
+    player
+}
+
+#[test]
+fn one_x_is_the_scale() {
+    assert_eq!(multiplier_to_bps(1.0), MULTIPLIER_SCALE);
+}
+
+#[test]
+fn bet_placed_carries_the_stored_multiplier() {
+    let player = player(2.25);
+    let outcome = BetOutcome {
+        correct: true,
+        multiplier_gain: 1.5,
+        side_bet_result: None,
+        side_bet_streak_milestone: None,
+        hot_card: None,
+    };
+    let event = bet_placed_event(&player, PLAYER, BetType::High, &outcome).unwrap();
+
+    assert_eq!(event.multiplier, player.peak_multiplier);
+    assert_eq!(event.multiplier, 22_500);
+}
+
+#[test]
+fn game_over_carries_the_stored_multipliers() {
+    let mut player = player(3.0);
+    player.multiplier = 1.5;
+    let event = game_over_event(&player, PLAYER, GameEndReason::LostBet);
+
+    assert_eq!(event.peak_multiplier, player.peak_multiplier);
+    assert_eq!(event.final_multiplier_scaled, multiplier_to_bps(player.multiplier));
+    assert_eq!(event.final_multiplier_scaled, 15_000);
+}
@@ -0,0 +1,39 @@
+use degame::*;
+
+mod common;
+
+#[test]
+fn side_bet_points_keep_non_negative_scores() {
+    assert_eq!(side_bet_points(0, 1).unwrap(), 0);
+    assert_eq!(side_bet_points(i64::MAX, 1).unwrap(), i64::MAX as u64);
+}
+
+#[test]
+fn unweighted_side_bets_score_nothing() {
+    assert_eq!(side_bet_points(i64::MAX, 0).unwrap(), 0);
+    assert_eq!(side_bet_points(i64::MIN, 0).unwrap(), 0);
+}
+
+/// Player on a 1.0 multiplier with a deeply negative side bet score, scored
+/// with side bets weighted `side_bet_weight`.
+fn score(side_bet_weight: u16) -> anchor_lang::Result<u64> {
+    let state = common::versioned_state();
+    let mut config = common::config();
+    config.score_formula.multiplier_weight = BPS_DENOMINATOR as u16 - side_bet_weight;
+    config.score_formula.side_bet_weight = side_bet_weight;
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    player.side_bet_score = i64::MIN;
+
+    calculate_total_score(&player, &state, &config, 0)
+}
+
+#[test]
+fn deeply_negative_side_score_is_rejected_at_submission() {
+    assert_eq!(score(1_000).unwrap_err(), ErrorCode::NegativeScore.into());
+}
+
+#[test]
+fn negative_side_score_is_ignored_when_side_bets_are_unweighted() {
+    assert_eq!(score(0).unwrap(), BPS_DENOMINATOR);
+}
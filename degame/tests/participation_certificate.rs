@@ -0,0 +1,72 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+
+/// Player with two games and `competition_bets` bets in competition 4 of
+/// `STATE`, after playing plenty elsewhere.
+fn player(competition_bets: u32) -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.total_bets = 100;
+    player.total_games = 20;
+    player.state = STATE;
+    player.activity_competition_index = 4;
+    player.competition_bets = competition_bets;
+    player.competition_games = 2;
+    player.scores_competition_index = 4;
+    player.competition_scores = vec![30_000, 12_000];
+    player
+}
+
+#[test]
+fn five_bets_earn_a_certificate() {
+    let certificate = participation_certificate(&player(5), &STATE, 4, 5, NOW).unwrap();
+
+    assert_eq!(certificate.games_completed, 2);
+    assert_eq!(certificate.best_score, 30_000);
+    assert_eq!(certificate.issued_at, NOW);
+}
+
+#[test]
+fn too_few_bets_earn_nothing() {
+    assert_eq!(
+        participation_certificate(&player(4), &STATE, 4, 5, NOW).err(),
+        Some(ErrorCode::NotEnoughBetsForCertificate.into())
+    );
+}
+
+#[test]
+fn play_in_another_competition_does_not_count() {
+    for (state, competition_index) in [(STATE, 5), (Pubkey::new_unique(), 4)] {
+        assert_eq!(
+            participation_certificate(&player(5), &state, competition_index, 5, NOW).err(),
+            Some(ErrorCode::NotEnoughBetsForCertificate.into())
+        );
+        let certificate = participation_certificate(&player(5), &state, competition_index, 0, NOW).unwrap();
+        assert_eq!((certificate.games_completed, certificate.best_score), (0, 0));
+    }
+}
+
+#[test]
+fn entering_a_new_competition_restarts_the_count() {
+    let mut player = player(5);
+    player.enter_competition(STATE, 4);
+    assert_eq!((player.competition_games, player.competition_bets), (2, 5));
+
+    player.enter_competition(Pubkey::new_unique(), 4);
+    assert_eq!((player.competition_games, player.competition_bets), (0, 0));
+}
+
+#[test]
+fn one_certificate_address_per_player_and_competition() {
+    let (state, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let address = |state: &Pubkey, wallet: &Pubkey| {
+        Pubkey::find_program_address(&[b"cert", state.as_ref(), wallet.as_ref()], &ID).0
+    };
+
+    assert_eq!(address(&state, &wallet), address(&state, &wallet));
+    assert_ne!(address(&state, &wallet), address(&Pubkey::new_unique(), &wallet));
+}
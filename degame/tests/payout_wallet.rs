@@ -0,0 +1,101 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::system_program;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const OWNER: Pubkey = Pubkey::new_from_array([2; 32]);
+const PAYOUT: Pubkey = Pubkey::new_from_array([3; 32]);
+const OWED: u64 = 5_000;
+
+fn claim_escrow() -> Pubkey {
+    Pubkey::find_program_address(&[b"claim_escrow", STATE.as_ref(), OWNER.as_ref()], &degame::ID).0
+}
+
+fn payout_wallet() -> Pubkey {
+    Pubkey::find_program_address(&[b"payout", OWNER.as_ref()], &degame::ID).0
+}
+
+/// A competition where `OWNER` has claimed `OWED` lamports of prizes into
+/// their escrow.
+fn ledger() -> Ledger {
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(
+        STATE,
+        &common::state(),
+        state_space(MAX_LEADERBOARD_SIZE as u8),
+    ));
+    let escrow = ClaimEscrow {
+        state: STATE,
+        owner: OWNER,
+        owed: OWED,
+    };
+    let mut escrow = TestAccount::program(claim_escrow(), &escrow, VERSIONED_HEADER_LEN + ClaimEscrow::LEN);
+    escrow.lamports += OWED;
+    ledger.insert(escrow);
+    ledger.insert(TestAccount::signer(OWNER, 1_000_000_000));
+    ledger
+}
+
+fn register(ledger: &mut Ledger, wallet: Pubkey) {
+    let result = ledger.process(
+        instruction::SetPayoutWallet { wallet },
+        accounts::SetPayoutWallet {
+            payout_wallet: payout_wallet(),
+            player: OWNER,
+            system_program: system_program::ID,
+        },
+    );
+    assert_eq!(result, Ok(()));
+}
+
+fn withdraw(ledger: &mut Ledger, wallet: Pubkey, registered: bool) -> ProgramResult {
+    ledger.process(
+        instruction::WithdrawClaim {},
+        accounts::WithdrawClaim {
+            state: STATE,
+            owner: OWNER,
+            claim_escrow: claim_escrow(),
+            wallet,
+            payout_wallet: registered.then(payout_wallet),
+        },
+    )
+}
+
+#[test]
+fn claim_is_paid_to_the_registered_payout_wallet() {
+    let mut ledger = ledger();
+    register(&mut ledger, PAYOUT);
+
+    assert_eq!(withdraw(&mut ledger, PAYOUT, true), Ok(()));
+    assert_eq!(ledger.get(&PAYOUT).lamports, OWED);
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, 0);
+}
+
+#[test]
+fn unregistered_wallet_is_rejected() {
+    let mut ledger = ledger();
+    register(&mut ledger, PAYOUT);
+
+    assert_eq!(
+        withdraw(&mut ledger, Pubkey::new_unique(), true),
+        failure(ErrorCode::InvalidPayoutWallet)
+    );
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, OWED);
+}
+
+#[test]
+fn payout_wallet_must_be_registered_first() {
+    let mut ledger = ledger();
+
+    assert_eq!(
+        withdraw(&mut ledger, PAYOUT, false),
+        failure(ErrorCode::InvalidPayoutWallet)
+    );
+    assert_eq!(withdraw(&mut ledger, OWNER, false), Ok(()));
+    assert_eq!(ledger.read::<ClaimEscrow>(&claim_escrow()).owed, 0);
+}
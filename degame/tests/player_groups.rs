@@ -0,0 +1,65 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+const STATE: Pubkey = Pubkey::new_from_array([9; 32]);
+
+fn wallet(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+fn group(group_id: u64, members: Vec<Pubkey>) -> PlayerGroup {
+    PlayerGroup { group_id, state: STATE, members, group_score: 0, claimed: 0 }
+}
+
+fn group_leaderboard() -> GroupLeaderboard {
+    GroupLeaderboard { state: STATE, entries: vec![], pool: 0, finalized: false }
+}
+
+#[test]
+fn group_score_is_the_sum_of_member_scores() {
+    let members = vec![wallet(1), wallet(2), wallet(3)];
+    let mut group = group(1, members.clone());
+    let mut leaderboard = group_leaderboard();
+    let key = wallet(100);
+
+    for (member, score) in members.iter().zip([12_000, 8_500, 20_000]) {
+        add_group_score(&mut group, &mut leaderboard, key, member, score).unwrap();
+    }
+
+    assert_eq!(group.group_score, 40_500);
+    assert_eq!(leaderboard.entries.len(), 1);
+    assert_eq!(leaderboard.entries[0].group, key);
+    assert_eq!(leaderboard.entries[0].group_score, 40_500);
+}
+
+#[test]
+fn outsiders_cannot_score_for_a_group() {
+    let mut group = group(1, vec![wallet(1)]);
+    let mut leaderboard = group_leaderboard();
+
+    assert_eq!(
+        add_group_score(&mut group, &mut leaderboard, wallet(100), &wallet(2), 1_000).unwrap_err(),
+        ErrorCode::NotGroupMember.into()
+    );
+    assert_eq!(group.group_score, 0);
+}
+
+#[test]
+fn group_leaderboard_keeps_the_top_groups() {
+    let mut leaderboard = group_leaderboard();
+    for (seed, score) in [(1, 100), (2, 400), (3, 300), (4, 200)] {
+        let mut group = group(seed.into(), vec![wallet(seed)]);
+        add_group_score(&mut group, &mut leaderboard, wallet(100 + seed), &wallet(seed), score).unwrap();
+    }
+
+    let scores: Vec<_> = leaderboard.entries.iter().map(|entry| entry.group_score).collect();
+    assert_eq!(scores, [400, 300, 200]);
+}
+
+#[test]
+fn group_prizes_split_equally_between_members() {
+    // 50% of 9_000 lamports between three members.
+    assert_eq!(group_member_prize(9_000, 0, 3).unwrap(), 1_500);
+    assert_eq!(group_member_prize(9_000, 2, 3).unwrap(), 600);
+    assert_eq!(group_member_prize(9_000, 3, 3).unwrap_err(), ErrorCode::NotOnLeaderboard.into());
+}
@@ -0,0 +1,23 @@
+use degame::*;
+
+const ENTRY_FEE: u64 = 1_000;
+
+#[test]
+fn fourth_joiner_exceeds_the_cap() {
+    let max_pool_size = 3 * ENTRY_FEE;
+    let mut pool = 0;
+    for _ in 0..3 {
+        pool = pool_with_deposit(pool, ENTRY_FEE, max_pool_size).unwrap();
+    }
+
+    assert_eq!(pool, max_pool_size);
+    assert_eq!(
+        pool_with_deposit(pool, ENTRY_FEE, max_pool_size).unwrap_err(),
+        ErrorCode::PoolFull.into()
+    );
+}
+
+#[test]
+fn zero_cap_is_unlimited() {
+    assert_eq!(pool_with_deposit(u64::MAX - 1, 1, 0).unwrap(), u64::MAX);
+}
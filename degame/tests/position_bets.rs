@@ -0,0 +1,152 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::entry;
+use common::program::{failure, process, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const BETTOR: Pubkey = Pubkey::new_from_array([2; 32]);
+const END_TIME: i64 = 1_000;
+const STAKE: u64 = 100;
+
+fn escrow_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"position_bet", STATE.as_ref(), BETTOR.as_ref()], &degame::ID).0
+}
+
+fn vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"position_bet_vault", STATE.as_ref()], &degame::ID).0
+}
+
+fn state(finalized: bool) -> State {
+    State {
+        end_time: END_TIME,
+        leaderboard_size: 3,
+        leaderboard: vec![entry(Pubkey::new_unique(), 300), entry(BETTOR, 200)],
+        finalized,
+        pool: 10_000,
+        prize_pool: 10_000,
+        ..common::state()
+    }
+}
+
+fn predict(now: i64) -> (ProgramResult, Vec<TestAccount>) {
+    let mut accounts = vec![
+        TestAccount::program(STATE, &state(false), state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::missing(escrow_address()),
+        TestAccount::signer(BETTOR, 10_000_000),
+        TestAccount::system_program(),
+    ];
+    let result = process(
+        instruction::PredictMyPosition {
+            predicted_position: 1,
+            stake: STAKE,
+        },
+        &mut accounts,
+        now,
+    );
+    (result, accounts)
+}
+
+/// Accounts for claiming a bet of `STAKE` on `predicted_position` against a
+/// vault holding `vault_balance`.
+fn claim_accounts(predicted_position: u8, vault_balance: u64) -> Vec<TestAccount> {
+    let escrow = PositionBetEscrow {
+        player: BETTOR,
+        predicted_position,
+        stake: STAKE,
+    };
+    let vault = PositionBetVault {
+        state: STATE,
+        balance: vault_balance,
+    };
+    let space = VERSIONED_HEADER_LEN + PositionBetVault::LEN;
+
+    let mut accounts = vec![
+        TestAccount::program(STATE, &state(true), state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(escrow_address(), &escrow, VERSIONED_HEADER_LEN + PositionBetEscrow::LEN),
+        TestAccount::program(vault_address(), &vault, space),
+        TestAccount::signer(BETTOR, 0),
+    ];
+    accounts[2].lamports += vault_balance;
+    accounts
+}
+
+fn claim(accounts: &mut [TestAccount]) -> ProgramResult {
+    process(instruction::ClaimPositionBet {}, accounts, END_TIME)
+}
+
+#[test]
+fn prediction_stakes_into_the_prize_pool() {
+    let (result, accounts) = predict(END_TIME - 1);
+
+    assert_eq!(result, Ok(()));
+    let escrow = accounts[1].read::<PositionBetEscrow>();
+    assert_eq!((escrow.player, escrow.predicted_position, escrow.stake), (BETTOR, 1, STAKE));
+    assert_eq!(accounts[1].owner, degame::ID);
+    assert_eq!(accounts[0].read::<State>().pool, 10_000 + STAKE);
+    // The escrow holds only its rent.
+    assert_eq!(accounts[2].lamports, 10_000_000 - STAKE - accounts[1].lamports);
+}
+
+#[test]
+fn predictions_close_when_the_competition_ends() {
+    assert_eq!(predict(END_TIME).0, failure(ErrorCode::PredictionsClosed));
+}
+
+#[test]
+fn winning_bet_is_paid_from_the_vault() {
+    let mut accounts = claim_accounts(1, 1_000);
+    let escrow_lamports = accounts[1].lamports;
+    let vault_lamports = accounts[2].lamports;
+
+    assert_eq!(claim(&mut accounts), Ok(()));
+    let payout = STAKE * POSITION_BET_PAYOUT_MULTIPLE;
+    assert_eq!(accounts[3].lamports, escrow_lamports + payout);
+    assert_eq!(accounts[2].lamports, vault_lamports - payout);
+    assert_eq!(accounts[2].read::<PositionBetVault>().balance, 1_000 - payout);
+    assert_eq!(accounts[0].read::<State>().pool, 10_000);
+    assert_eq!(accounts[1].lamports, 0);
+}
+
+#[test]
+fn lost_stake_stays_in_the_prize_pool() {
+    let mut accounts = claim_accounts(0, 1_000);
+    let escrow_lamports = accounts[1].lamports;
+    let vault_lamports = accounts[2].lamports;
+
+    assert_eq!(claim(&mut accounts), Ok(()));
+    // Only the escrow's rent comes back.
+    assert_eq!(accounts[3].lamports, escrow_lamports);
+    assert_eq!(accounts[2].lamports, vault_lamports);
+    assert_eq!(accounts[2].read::<PositionBetVault>().balance, 1_000);
+    assert_eq!(accounts[0].read::<State>().pool, 10_000);
+}
+
+#[test]
+fn winnings_beyond_the_vault_are_rejected() {
+    let mut accounts = claim_accounts(1, STAKE * POSITION_BET_PAYOUT_MULTIPLE - 1);
+
+    assert_eq!(claim(&mut accounts), failure(ErrorCode::InsufficientPositionBetVault));
+}
+
+#[test]
+fn prizes_are_split_from_the_pool_at_finalization() {
+    let config = CompetitionConfig {
+        prize_schedule: PrizeSchedule::STANDARD,
+        ..common::config()
+    };
+    let finalized = State {
+        pool: 4_000,
+        ..state(true)
+    };
+    let open = State {
+        pool: 4_000,
+        ..state(false)
+    };
+
+    assert_eq!(calculate_prize(&finalized, &config, 0).unwrap().0, 5_000);
+    assert_eq!(calculate_prize(&open, &config, 0).unwrap().0, 2_000);
+}
@@ -0,0 +1,54 @@
+use degame::*;
+
+mod common;
+
+/// A one-player competition whose pool is a single small deposit.
+fn state() -> State {
+    State {
+        leaderboard_size: 3,
+        finalized: true,
+        pool: 100,
+        prize_pool: 100,
+        participants: 1,
+        ..common::state()
+    }
+}
+
+fn config() -> CompetitionConfig {
+    let mut config = common::config();
+    config.prize_schedule = PrizeSchedule::STANDARD;
+    config.minimum_prize_guarantee[..3].copy_from_slice(&[10_000, 5_000, 2_000]);
+    config
+}
+
+fn first_place_top_up(reserve: u64) -> (u64, u64, u64) {
+    let config = config();
+    let (_, net_prize) = calculate_prize(&state(), &config, 0).unwrap();
+    let (top_up, shortfall) =
+        guarantee_top_up(net_prize, config.minimum_prize_guarantee[0], reserve);
+    (net_prize, top_up, shortfall)
+}
+
+#[test]
+fn reserve_pays_up_to_the_guarantee() {
+    let (net_prize, top_up, shortfall) = first_place_top_up(50_000);
+
+    assert_eq!(net_prize, 50);
+    assert_eq!(net_prize + top_up, 10_000);
+    assert_eq!(shortfall, 0);
+}
+
+#[test]
+fn thin_reserve_pays_what_it_has() {
+    let (net_prize, top_up, shortfall) = first_place_top_up(4_000);
+
+    assert_eq!(top_up, 4_000);
+    assert_eq!(net_prize + top_up + shortfall, 10_000);
+    assert_eq!(shortfall, 5_950);
+}
+
+#[test]
+fn prizes_above_the_guarantee_are_untouched() {
+    assert_eq!(guarantee_top_up(12_000, 10_000, 50_000), (0, 0));
+    assert_eq!(guarantee_top_up(12_000, 0, 0), (0, 0));
+}
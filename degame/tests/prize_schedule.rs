@@ -0,0 +1,37 @@
+use degame::*;
+
+mod common;
+
+fn house_schedule() -> PrizeSchedule {
+    PrizeSchedule::new([45, 25, 15], 15)
+}
+
+#[test]
+fn schedule_must_sum_to_one_hundred() {
+    PrizeSchedule::STANDARD.validate().unwrap();
+    house_schedule().validate().unwrap();
+
+    for schedule in [
+        PrizeSchedule::new([45, 25, 15], 0),
+        PrizeSchedule::new([50, 30, 20], 15),
+    ] {
+        assert_eq!(schedule.validate().unwrap_err(), ErrorCode::InvalidPrizeSchedule.into());
+    }
+}
+
+#[test]
+fn house_share_and_player_prizes_cover_the_pool() {
+    let mut state = common::versioned_state();
+    state.pool = 10_000;
+    let mut config = common::config();
+    config.prize_schedule = house_schedule();
+
+    let prizes: Vec<_> = (0..3)
+        .map(|position| calculate_prize(&state, &config, position).unwrap().0)
+        .collect();
+    let house = house_prize(&state, &config).unwrap();
+
+    assert_eq!(prizes, [4_500, 2_500, 1_500]);
+    assert_eq!(house, 1_500);
+    assert_eq!(prizes.iter().sum::<u64>() + house, state.pool);
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, return_data, Ledger, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const PROOF: [u8; VRF_PROOF_LEN] = [7; VRF_PROOF_LEN];
+const ALPHA: [u8; 32] = [9; 32];
+
+/// A player who committed their seed and awaits the VRF output.
+fn ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        ..common::state()
+    };
+    let mut player = common::player();
+    player.state = STATE;
+    player.seed_hash = Some([1; 32]);
+
+    let mut ledger = Ledger::new();
+    ledger.now = NOW;
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::program(
+        CONFIG,
+        &common::config(),
+        VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+    ));
+    ledger.insert(TestAccount::program(PLAYER, &*player, player_space()));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn receive(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::ReceiveRandomness {
+            randomness: 11,
+            proof: PROOF,
+            alpha: ALPHA,
+        },
+        accounts::ReceiveRandomness {
+            state: STATE,
+            config: CONFIG,
+            player: PLAYER,
+            authority: ADMIN,
+        },
+    )
+}
+
+fn get_proof(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::GetRandomnessProof {},
+        accounts::GetPlayerSummary { player: PLAYER },
+    )
+}
+
+#[test]
+fn proof_is_stored_with_the_vrf_output() {
+    let mut ledger = ledger();
+
+    assert_eq!(receive(&mut ledger), Ok(()));
+    let player = ledger.read::<Player>(&PLAYER);
+    let proof = player.randomness_proof.clone().unwrap();
+    assert_eq!((proof.proof, proof.alpha), (PROOF, ALPHA));
+    assert_eq!(player.vrf_output, Some(11));
+}
+
+#[test]
+fn proof_is_returned_for_auditors() {
+    let mut ledger = ledger();
+    assert_eq!(receive(&mut ledger), Ok(()));
+
+    assert_eq!(get_proof(&mut ledger), Ok(()));
+    let proof: RandomnessProof = return_data();
+    assert_eq!((proof.proof, proof.alpha), (PROOF, ALPHA));
+}
+
+#[test]
+fn no_proof_before_the_randomness_arrives() {
+    let mut ledger = ledger();
+
+    assert_eq!(get_proof(&mut ledger), failure(ErrorCode::RandomnessNotReceived));
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+
+/// A player who committed their seed and awaits the VRF output.
+fn ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        ..common::state()
+    };
+    let mut player = common::player();
+    player.state = STATE;
+    player.seed_hash = Some([1; 32]);
+
+    let mut ledger = Ledger::new();
+    ledger.now = NOW;
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::program(
+        CONFIG,
+        &common::config(),
+        VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+    ));
+    ledger.insert(TestAccount::program(PLAYER, &*player, player_space()));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn receive(ledger: &mut Ledger) -> ProgramResult {
+    ledger.process(
+        instruction::ReceiveRandomness {
+            randomness: 11,
+            proof: [0; VRF_PROOF_LEN],
+            alpha: [0; 32],
+        },
+        accounts::ReceiveRandomness {
+            state: STATE,
+            config: CONFIG,
+            player: PLAYER,
+            authority: ADMIN,
+        },
+    )
+}
+
+/// Clears the delivered randomness, as starting the next game does.
+fn await_randomness_again(ledger: &mut Ledger) {
+    let mut player = ledger.read::<Player>(&PLAYER);
+    player.vrf_output = None;
+    player.randomness_proof = None;
+    ledger.insert(TestAccount::program(PLAYER, &*player, player_space()));
+}
+
+#[test]
+fn delivery_is_timestamped() {
+    let mut ledger = ledger();
+
+    assert_eq!(receive(&mut ledger), Ok(()));
+    assert_eq!(ledger.read::<Player>(&PLAYER).last_randomness_at, NOW);
+}
+
+#[test]
+fn second_delivery_within_the_interval_is_refused() {
+    let mut ledger = ledger();
+    assert_eq!(receive(&mut ledger), Ok(()));
+    await_randomness_again(&mut ledger);
+    ledger.now += MIN_RANDOMNESS_INTERVAL - 1;
+
+    assert_eq!(receive(&mut ledger), failure(ErrorCode::RandomnessRateLimited));
+    assert_eq!(ledger.read::<Player>(&PLAYER).vrf_output, None);
+}
+
+#[test]
+fn delivery_after_the_interval_is_accepted() {
+    let mut ledger = ledger();
+    assert_eq!(receive(&mut ledger), Ok(()));
+    await_randomness_again(&mut ledger);
+    ledger.now += MIN_RANDOMNESS_INTERVAL;
+
+    assert_eq!(receive(&mut ledger), Ok(()));
+    assert_eq!(ledger.read::<Player>(&PLAYER).last_randomness_at, NOW + MIN_RANDOMNESS_INTERVAL);
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::keccak;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, process, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+const ORACLE: Pubkey = Pubkey::new_from_array([6; 32]);
+const SEED: u64 = 42;
+
+/// Accounts for `request_randomness` signed by `signer` in a competition
+/// run by `ADMIN` that falls back to blockhash randomness.
+fn accounts(signer: Pubkey) -> Vec<TestAccount> {
+    accounts_with_source(signer, RandomnessSource::BlockhashFallback)
+}
+
+fn accounts_with_source(signer: Pubkey, source: RandomnessSource) -> Vec<TestAccount> {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        ..common::state()
+    };
+    let mut config = common::config();
+    config.randomness_source = source;
+    let mut player = common::player();
+    player.state = STATE;
+    player.seed_hash = Some(keccak::hash(&SEED.to_le_bytes()).to_bytes());
+
+    vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(
+            CONFIG,
+            &config,
+            VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+        ),
+        TestAccount::executable(ORACLE),
+        TestAccount::missing(Pubkey::new_unique()),
+        TestAccount::signer(signer, 0),
+        TestAccount::program(PLAYER, &*player, player_space()),
+    ]
+}
+
+fn request_randomness(accounts: &mut [TestAccount]) -> ProgramResult {
+    process(instruction::RequestRandomness {}, accounts, 0)
+}
+
+#[test]
+fn admin_requests_randomness() {
+    let mut accounts = accounts(ADMIN);
+
+    assert_eq!(request_randomness(&mut accounts), Ok(()));
+}
+
+#[test]
+fn request_keeps_the_player_seed_secret() {
+    let mut accounts = accounts(ADMIN);
+
+    assert_eq!(request_randomness(&mut accounts), Ok(()));
+    assert_eq!(accounts[5].read::<Player>().player_seed, None);
+}
+
+#[test]
+fn request_needs_a_committed_seed() {
+    let mut accounts = accounts(ADMIN);
+    let mut player = accounts[5].read::<Player>();
+    player.seed_hash = None;
+    accounts[5] = TestAccount::program(PLAYER, &*player, player_space());
+
+    assert_eq!(
+        request_randomness(&mut accounts),
+        failure(ErrorCode::SeedNotCommitted)
+    );
+}
+
+#[test]
+fn only_the_admin_requests_randomness() {
+    let mut accounts = accounts(Pubkey::new_unique());
+
+    assert_eq!(
+        request_randomness(&mut accounts),
+        failure(ErrorCode::Unauthorized)
+    );
+}
+
+#[test]
+fn oracle_must_be_the_configured_program() {
+    let source = RandomnessSource::SwitchboardVrf {
+        program_id: Pubkey::new_unique(),
+    };
+    let mut accounts = accounts_with_source(ADMIN, source);
+
+    assert_eq!(
+        request_randomness(&mut accounts),
+        failure(ErrorCode::InvalidRandomnessProgram)
+    );
+}
+
+#[test]
+fn each_source_builds_its_own_request() {
+    let (oracle, admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut oracle_lamports, mut admin_lamports) = (0, 0);
+    let (mut oracle_data, mut admin_data) = (vec![], vec![]);
+    let accounts = [
+        AccountInfo::new(&oracle, false, true, &mut oracle_lamports, &mut oracle_data, &ORACLE, false, 0),
+        AccountInfo::new(&admin, true, true, &mut admin_lamports, &mut admin_data, &ORACLE, false, 0),
+    ];
+
+    let switchboard = RandomnessSource::SwitchboardVrf { program_id: ORACLE };
+    let request = build_randomness_instruction(&switchboard, SEED, &accounts).unwrap();
+    assert_eq!(request.program_id, ORACLE);
+    assert_eq!(request.data, SEED.to_le_bytes());
+
+    let pyth = RandomnessSource::PythEntropy { program_id: ORACLE };
+    let request = build_randomness_instruction(&pyth, SEED, &accounts).unwrap();
+    assert_eq!(request.program_id, ORACLE);
+    assert_eq!((request.data.len(), &request.data[..8]), (32, &SEED.to_le_bytes()[..]));
+
+    let fallback = RandomnessSource::BlockhashFallback;
+    assert!(build_randomness_instruction(&fallback, SEED, &accounts).is_none());
+    assert_eq!(fallback.program_id(), None);
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{emitted, failure, process, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const STARTED_AT: i64 = 1_700_000_000;
+const TIMEOUT: u32 = 600;
+const ENTRY_FEE: u64 = 1_000;
+
+/// Accounts for `refund_failed_game` on a game started at `STARTED_AT` that
+/// is still waiting for its randomness, in a competition sending a quarter of
+/// each entry fee to a jackpot holding `jackpot`.
+fn accounts(state: State, jackpot: u64) -> Vec<TestAccount> {
+    let state = State {
+        config: CONFIG,
+        pool: 10_000,
+        jackpot,
+        ..state
+    };
+    let mut config = common::config();
+    config.entry_fee = ENTRY_FEE;
+    config.jackpot_contribution_bps = 2_500;
+    config.randomness_timeout_seconds = TIMEOUT;
+    let mut player = common::player();
+    player.authority = AUTHORITY;
+    player.state = STATE;
+    player.game_id = 7;
+    player.start_time = STARTED_AT;
+    player.status = GameStatus::AwaitingRandomness;
+
+    vec![
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN),
+        TestAccount::program(PLAYER, &*player, player_space()),
+        TestAccount::signer(AUTHORITY, 0),
+    ]
+}
+
+fn refund(accounts: &mut [TestAccount], now: i64) -> ProgramResult {
+    process(instruction::RefundFailedGame {}, accounts, now)
+}
+
+fn edit_player(accounts: &mut [TestAccount], edit: impl FnOnce(&mut Player)) {
+    let mut player = accounts[2].read::<Player>();
+    edit(&mut player);
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+}
+
+#[test]
+fn timed_out_game_gets_its_entry_fee_back() {
+    let mut accounts = accounts(common::state(), 5_000);
+    let state_lamports = accounts[0].lamports;
+
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), Ok(()));
+    let state = accounts[0].read::<State>();
+    assert_eq!(state.pool, 10_000 - 750);
+    assert_eq!(state.jackpot, 5_000 - 250);
+    assert_eq!(accounts[0].lamports, state_lamports - ENTRY_FEE);
+    assert_eq!(accounts[3].lamports, ENTRY_FEE);
+
+    let player = accounts[2].read::<Player>();
+    assert!(player.finished && player.score_submitted);
+    assert_eq!(player.status, GameStatus::Refunded);
+
+    let [event] = &emitted::<GameRefunded>()[..] else { panic!("expected one GameRefunded") };
+    assert_eq!((event.player, event.game_id, event.amount), (PLAYER, 7, ENTRY_FEE));
+}
+
+#[test]
+fn claimed_jackpot_share_is_refunded_from_the_pool() {
+    let mut accounts = accounts(common::state(), 100);
+
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), Ok(()));
+    let state = accounts[0].read::<State>();
+    assert_eq!(state.pool, 10_000 - 900);
+    assert_eq!(state.jackpot, 0);
+}
+
+#[test]
+fn refund_waits_for_the_timeout() {
+    let mut accounts = accounts(common::state(), 0);
+
+    assert_eq!(
+        refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT) - 1),
+        failure(ErrorCode::RandomnessTimeoutPending)
+    );
+    assert_eq!(accounts[3].lamports, 0);
+}
+
+#[test]
+fn game_whose_randomness_arrived_is_not_refunded() {
+    let mut accounts = accounts(common::state(), 0);
+    edit_player(&mut accounts, |player| player.vrf_output = Some(42));
+
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), failure(ErrorCode::GameNotRefundable));
+}
+
+#[test]
+fn game_is_refunded_once() {
+    let mut accounts = accounts(common::state(), 0);
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), Ok(()));
+
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), failure(ErrorCode::GameNotRefundable));
+}
+
+#[test]
+fn finalized_pool_is_not_refunded_from() {
+    let finalized = State { finalized: true, ..common::state() };
+    let mut accounts = accounts(finalized, 0);
+
+    assert_eq!(refund(&mut accounts, STARTED_AT + i64::from(TIMEOUT)), failure(ErrorCode::AlreadyFinalized));
+}
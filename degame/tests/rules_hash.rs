@@ -0,0 +1,16 @@
+use anchor_lang::solana_program::keccak;
+use degame::*;
+
+mod common;
+
+const RULES: &[u8] = br#"{"game":"high-low","deck":52,"prizes":[50,30,20]}"#;
+
+#[test]
+fn only_the_committed_rules_document_matches() {
+    let mut config = common::config();
+    config.rules_hash = keccak::hash(RULES).to_bytes();
+
+    assert!(rules_match(&config, RULES));
+    assert!(!rules_match(&config, br#"{"game":"high-low","deck":52,"prizes":[60,30,10]}"#));
+    assert!(!rules_match(&config, b""));
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+fn wallet(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+fn state(scores: &[u64]) -> VersionedAccount<State> {
+    let mut state = common::versioned_state();
+    state.leaderboard_size = MAX_LEADERBOARD_SIZE as u8;
+    for (seed, score) in scores.iter().enumerate() {
+        state.upsert_entry(LeaderboardEntry {
+            player: wallet(seed as u8),
+            nickname: String::new(),
+            score: *score,
+            side_bet_score: 0,
+            claimed: false,
+            finished_at: 0,
+        });
+    }
+    state.rank_leaderboard();
+    state
+}
+
+#[test]
+fn upward_adjustment_promotes_and_re_sorts() {
+    let mut state = state(&[30_000, 20_000, 10_000]);
+
+    let adjustment = state.adjust_entry_score(&wallet(1), 15_000).unwrap();
+
+    assert_eq!(
+        adjustment,
+        ScoreAdjustment { old_score: 20_000, new_score: 35_000, old_position: 1, new_position: 0 }
+    );
+    let order: Vec<_> = state.leaderboard.iter().map(|entry| entry.player).collect();
+    assert_eq!(order, [wallet(1), wallet(0), wallet(2)]);
+}
+
+#[test]
+fn downward_adjustment_clamps_at_zero() {
+    let mut state = state(&[30_000, 20_000]);
+
+    let adjustment = state.adjust_entry_score(&wallet(0), -50_000).unwrap();
+
+    assert_eq!(adjustment.new_score, 0);
+    assert_eq!((adjustment.old_position, adjustment.new_position), (0, 1));
+}
+
+#[test]
+fn unknown_player_cannot_be_adjusted() {
+    let mut state = state(&[30_000]);
+
+    assert_eq!(
+        state.adjust_entry_score(&wallet(9), 1).unwrap_err(),
+        ErrorCode::NotOnLeaderboard.into()
+    );
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount, WithRemaining};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+
+fn ledger() -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        leaderboard_size: MAX_LEADERBOARD_SIZE as u8,
+        ..common::state()
+    };
+    let mut args = common::config_args();
+    args.score_formula.multiplier_weight = 9_000;
+    args.score_formula.side_bet_weight = 1_000;
+    let config = CompetitionConfig::new(args).unwrap();
+
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+/// Adds a player account and returns its address.
+fn add_player(ledger: &mut Ledger, edit: impl FnOnce(&mut Player)) -> Pubkey {
+    let mut player = common::player();
+    player.authority = Pubkey::new_unique();
+    player.state = STATE;
+    player.finished = true;
+    player.multiplier = 2.0;
+    edit(&mut player);
+
+    let key = Pubkey::new_unique();
+    ledger.insert(TestAccount::program(key, &*player, player_space()));
+    key
+}
+
+fn submit(ledger: &mut Ledger, signer: Pubkey, players: Vec<Pubkey>) -> ProgramResult {
+    ledger.process(
+        instruction::SubmitScoresBatch {},
+        WithRemaining(
+            accounts::SubmitScoresBatch {
+                state: STATE,
+                config: CONFIG,
+                admin: signer,
+            },
+            players,
+        ),
+    )
+}
+
+#[test]
+fn finished_games_are_submitted_and_the_rest_skipped() {
+    let mut ledger = ledger();
+    let valid = [add_player(&mut ledger, |_| {}), add_player(&mut ledger, |_| {})];
+    let skipped = [
+        add_player(&mut ledger, |player| player.finished = false),
+        add_player(&mut ledger, |player| player.score_submitted = true),
+        add_player(&mut ledger, |player| player.authority = Pubkey::default()),
+        add_player(&mut ledger, |player| player.side_bet_score = -1),
+        add_player(&mut ledger, |player| player.state = Pubkey::new_unique()),
+        Pubkey::new_unique(),
+    ];
+
+    assert_eq!(submit(&mut ledger, ADMIN, [&valid[..], &skipped].concat()), Ok(()));
+
+    let state = ledger.read::<State>(&STATE);
+    let mut ranked: Vec<_> = state.leaderboard.iter().map(|entry| entry.player).collect();
+    let mut expected: Vec<_> = valid.iter().map(|key| ledger.read::<Player>(key).authority).collect();
+    ranked.sort();
+    expected.sort();
+    assert_eq!(ranked, expected);
+    for key in valid {
+        assert!(ledger.read::<Player>(&key).score_submitted);
+    }
+    assert!(!ledger.read::<Player>(&skipped[0]).score_submitted);
+}
+
+#[test]
+fn only_the_admin_submits_a_batch() {
+    let mut ledger = ledger();
+    let stranger = Pubkey::new_unique();
+    ledger.insert(TestAccount::signer(stranger, 0));
+    let players = vec![add_player(&mut ledger, |_| {})];
+
+    assert_eq!(submit(&mut ledger, stranger, players), failure(ErrorCode::Unauthorized));
+}
+
+#[test]
+fn batch_is_capped() {
+    let mut ledger = ledger();
+    let players = (0..=MAX_SCORE_BATCH).map(|_| add_player(&mut ledger, |_| {})).collect();
+
+    assert_eq!(submit(&mut ledger, ADMIN, players), failure(ErrorCode::BatchTooLarge));
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, Ledger, TestAccount};
+
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const ADMIN: Pubkey = Pubkey::new_from_array([5; 32]);
+
+const MULTIPLIER_ONLY: ScoreFormula = ScoreFormula {
+    multiplier_weight: 10_000,
+    side_bet_weight: 0,
+    time_bonus_weight: 0,
+    streak_bonus_weight: 0,
+    cards_remaining_weight: 0,
+};
+
+const EQUAL: ScoreFormula = ScoreFormula {
+    multiplier_weight: 2_000,
+    side_bet_weight: 2_000,
+    time_bonus_weight: 2_000,
+    streak_bonus_weight: 2_000,
+    cards_remaining_weight: 2_000,
+};
+
+/// Player through the whole deck with `multiplier` and `side_bet_score`.
+fn player(multiplier: f64, side_bet_score: i64) -> Player {
+    Player {
+        multiplier,
+        side_bet_score,
+        cards_dealt: STANDARD_DECK_SIZE,
+        ..(*common::player()).clone()
+    }
+}
+
+/// Scores of a high-multiplier player and a side-bet player under `formula`.
+fn scores(formula: &ScoreFormula) -> [u64; 2] {
+    let state = common::versioned_state();
+    let config = CompetitionConfig {
+        score_formula: formula.clone(),
+        ..common::config()
+    };
+    [player(3.0, 0), player(2.0, 20_000)]
+        .map(|player| calculate_total_score(&player, &state, &config, 0).unwrap())
+}
+
+#[test]
+fn weights_must_sum_to_the_whole() {
+    assert!(MULTIPLIER_ONLY.validate().is_ok());
+    assert!(EQUAL.validate().is_ok());
+
+    for multiplier_weight in [0, 9_999, 10_001] {
+        let formula = ScoreFormula {
+            multiplier_weight,
+            ..MULTIPLIER_ONLY
+        };
+        assert_eq!(formula.validate().unwrap_err(), ErrorCode::InvalidScoreFormula.into());
+    }
+}
+
+#[test]
+fn multiplier_only_formula_ranks_by_multiplier() {
+    assert_eq!(scores(&MULTIPLIER_ONLY), [30_000, 20_000]);
+}
+
+#[test]
+fn equal_weights_blend_the_components() {
+    assert_eq!(scores(&EQUAL), [6_000, 4_000 + 4_000]);
+}
+
+fn ledger(finalized: bool) -> Ledger {
+    let state = State {
+        admin: ADMIN,
+        config: CONFIG,
+        finalized,
+        ..common::state()
+    };
+    let config = CompetitionConfig::new(common::config_args()).unwrap();
+
+    let mut ledger = Ledger::new();
+    ledger.insert(TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)));
+    ledger.insert(TestAccount::program(CONFIG, &config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN));
+    ledger.insert(TestAccount::signer(ADMIN, 0));
+    ledger
+}
+
+fn update(ledger: &mut Ledger, admin: Pubkey, formula: ScoreFormula) -> ProgramResult {
+    ledger.process(
+        instruction::UpdateScoreFormula { formula },
+        accounts::UpdateScoreFormula {
+            state: STATE,
+            config: CONFIG,
+            admin,
+        },
+    )
+}
+
+#[test]
+fn update_applies_to_later_submissions() {
+    let mut ledger = ledger(false);
+    let state = ledger.read::<State>(&STATE);
+    let side_bettor = player(2.0, 20_000);
+    let score = |ledger: &Ledger| {
+        calculate_total_score(&side_bettor, &state, &ledger.read::<CompetitionConfig>(&CONFIG), 0).unwrap()
+    };
+    assert_eq!(score(&ledger), 20_000);
+
+    assert_eq!(update(&mut ledger, ADMIN, EQUAL), Ok(()));
+    assert_eq!(score(&ledger), 8_000);
+}
+
+#[test]
+fn invalid_formula_is_refused() {
+    let mut ledger = ledger(false);
+    let formula = ScoreFormula {
+        side_bet_weight: 1,
+        ..MULTIPLIER_ONLY
+    };
+
+    assert_eq!(update(&mut ledger, ADMIN, formula), failure(ErrorCode::InvalidScoreFormula));
+}
+
+#[test]
+fn formula_is_fixed_once_finalized() {
+    let mut ledger = ledger(true);
+
+    assert_eq!(update(&mut ledger, ADMIN, EQUAL), failure(ErrorCode::AlreadyFinalized));
+}
+
+#[test]
+fn only_the_admin_updates_the_formula() {
+    let mut ledger = ledger(false);
+    let stranger = Pubkey::new_unique();
+    ledger.insert(TestAccount::signer(stranger, 0));
+
+    assert_eq!(update(&mut ledger, stranger, EQUAL), failure(ErrorCode::Unauthorized));
+}
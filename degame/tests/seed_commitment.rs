@@ -0,0 +1,21 @@
+use anchor_lang::solana_program::keccak;
+use degame::*;
+
+#[test]
+fn revealed_seed_must_match_commitment() {
+    let seed_hash = keccak::hash(&42u64.to_le_bytes()).to_bytes();
+
+    assert!(verify_seed_commitment(42, Some(seed_hash)).is_ok());
+    assert_eq!(
+        verify_seed_commitment(43, Some(seed_hash)).unwrap_err(),
+        ErrorCode::SeedCommitmentMismatch.into()
+    );
+}
+
+#[test]
+fn seed_must_be_committed_first() {
+    assert_eq!(
+        verify_seed_commitment(42, None).unwrap_err(),
+        ErrorCode::SeedNotCommitted.into()
+    );
+}
@@ -0,0 +1,49 @@
+use degame::*;
+
+mod common;
+
+fn player() -> VersionedAccount<Player> {
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    player
+}
+
+/// A red even card showing, with a black odd card coming up.
+fn deck() -> Vec<Card> {
+    vec![
+        Card { suit: "Hearts".to_string(), value: 4 },
+        Card { suit: "Spades".to_string(), value: 9 },
+        Card { suit: "Clubs".to_string(), value: 2 },
+    ]
+}
+
+fn side_bet_result(side_bet: SideBetType, side_bet_card: SideBetCard) -> Option<i64> {
+    let mut player = player();
+    resolve_bet(&mut player, &deck(), &BetType::High, Some(side_bet), false, side_bet_card)
+        .unwrap()
+        .side_bet_result
+}
+
+#[test]
+fn current_mode_reads_the_card_showing() {
+    assert_eq!(side_bet_result(SideBetType::Color { red: true }, SideBetCard::Current), Some(1));
+    assert_eq!(side_bet_result(SideBetType::Parity { even: true }, SideBetCard::Current), Some(1));
+}
+
+#[test]
+fn next_mode_reads_the_card_the_bet_reveals() {
+    assert_eq!(side_bet_result(SideBetType::Color { red: true }, SideBetCard::Next), Some(-1));
+    assert_eq!(side_bet_result(SideBetType::Parity { even: false }, SideBetCard::Next), Some(1));
+}
+
+#[test]
+fn main_bet_is_the_same_in_both_modes() {
+    for side_bet_card in [SideBetCard::Current, SideBetCard::Next] {
+        let mut player = player();
+        let outcome =
+            resolve_bet(&mut player, &deck(), &BetType::High, None, false, side_bet_card).unwrap();
+
+        assert!(outcome.correct);
+        assert_eq!(player.cards_dealt, 1);
+    }
+}
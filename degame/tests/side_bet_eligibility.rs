@@ -0,0 +1,33 @@
+use degame::*;
+
+mod common;
+
+fn config(min: u64, max: u64) -> CompetitionConfig {
+    let mut config = common::config();
+    config.side_bet_min_multiplier_bps = min;
+    config.side_bet_max_multiplier_bps = max;
+    config
+}
+
+#[test]
+fn side_bets_are_taken_inside_the_band() {
+    let config = config(10_000, 50_000);
+
+    assert!(side_bet_eligible(1.0, &config));
+    assert!(side_bet_eligible(3.2, &config));
+    assert!(side_bet_eligible(5.0, &config));
+}
+
+#[test]
+fn side_bets_are_dropped_outside_the_band() {
+    let config = config(10_000, 50_000);
+
+    assert!(!side_bet_eligible(0.5, &config));
+    assert!(!side_bet_eligible(5.5, &config));
+}
+
+#[test]
+fn zero_maximum_leaves_the_band_open() {
+    assert!(side_bet_eligible(1_000.0, &config(0, 0)));
+    assert!(side_bet_eligible(0.0, &config(0, 0)));
+}
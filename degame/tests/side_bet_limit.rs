@@ -0,0 +1,48 @@
+use degame::*;
+
+mod common;
+
+use common::game::*;
+use common::program::failure;
+
+const COLOR: Option<SideBetType> = Some(SideBetType::Color { red: true });
+
+fn config(max_side_bets_per_game: u8) -> CompetitionConfig {
+    CompetitionConfig {
+        max_side_bets_per_game,
+        ..common::game::config()
+    }
+}
+
+fn player(side_bets_placed: u8) -> Player {
+    Player {
+        side_bets_placed,
+        ..dealt_player()
+    }
+}
+
+#[test]
+fn side_bets_count_up_to_the_limit() {
+    let (result, player, _) = place(player(2), &config(3), high(COLOR), BET_SLOT);
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(player.side_bets_placed, 3);
+    assert!(player.pending_bet.unwrap().side_bet.is_some());
+}
+
+#[test]
+fn side_bet_past_the_limit_is_rejected() {
+    let (result, player, _) = place(player(3), &config(3), high(COLOR), BET_SLOT);
+
+    assert_eq!(result, failure(ErrorCode::SideBetLimitReached));
+    assert_eq!(player.side_bets_placed, 3);
+    assert!(player.pending_bet.is_none());
+}
+
+#[test]
+fn main_bets_go_on_past_the_limit() {
+    let (result, player, _) = place(player(3), &config(3), high(None), BET_SLOT);
+
+    assert_eq!(result, Ok(()));
+    assert!(player.pending_bet.is_some());
+}
@@ -0,0 +1,45 @@
+use degame::*;
+
+mod common;
+
+/// Rising hearts, so High and a red Color side bet always win.
+fn deck() -> Vec<Card> {
+    (2..=12).map(|value| Card { suit: "Hearts".to_string(), value }).collect()
+}
+
+fn player() -> VersionedAccount<Player> {
+    common::player()
+}
+
+fn bet(player: &mut Player, side_bet: Option<SideBetType>) -> BetOutcome {
+    let outcome =
+        resolve_bet(player, &deck(), &BetType::High, side_bet, false, SideBetCard::Current).unwrap();
+    assert!(outcome.correct);
+    outcome
+}
+
+#[test]
+fn streak_continues_through_bets_without_side_bets() {
+    let mut player = player();
+    let red = || Some(SideBetType::Color { red: true });
+
+    let milestones: Vec<_> = [red(), None, red(), None, None, red()]
+        .into_iter()
+        .map(|side_bet| bet(&mut player, side_bet).side_bet_streak_milestone)
+        .collect();
+
+    assert_eq!(milestones, [None, None, None, None, None, Some(3)]);
+    assert_eq!(player.side_bet_streak, 3);
+}
+
+#[test]
+fn wrong_side_bet_resets_the_streak() {
+    let mut player = player();
+    bet(&mut player, Some(SideBetType::Color { red: true }));
+    bet(&mut player, Some(SideBetType::Color { red: true }));
+
+    let outcome = bet(&mut player, Some(SideBetType::Color { red: false }));
+
+    assert_eq!(outcome.side_bet_streak_milestone, None);
+    assert_eq!(player.side_bet_streak, 0);
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::Pubkey;
+use degame::*;
+
+mod common;
+
+const RECIPIENT: Pubkey = Pubkey::new_from_array([5; 32]);
+
+fn state(pool: u64, amount: u64) -> VersionedAccount<State> {
+    let mut state = common::versioned_state();
+    state.pool = pool;
+    state.special_prize = Some(SpecialPrize {
+        recipient: RECIPIENT,
+        amount,
+        reason: "best side bets".to_string(),
+    });
+    state
+}
+
+#[test]
+fn special_prize_comes_off_the_pool_before_the_split() {
+    let mut config = common::config();
+    config.prize_schedule = PrizeSchedule::STANDARD;
+    let mut state = state(10_000, 1_000);
+
+    let prize = state.reserve_special_prize().unwrap().unwrap();
+
+    assert_eq!((prize.recipient, prize.amount), (RECIPIENT, 1_000));
+    assert_eq!(state.pool, 9_000);
+    assert_eq!(calculate_prize(&state, &config, 0).unwrap(), (4_500, 4_500));
+    assert_eq!(calculate_prize(&state, &config, 2).unwrap(), (1_800, 1_800));
+}
+
+#[test]
+fn no_special_prize_leaves_the_pool_alone() {
+    let mut state = state(10_000, 0);
+    state.special_prize = None;
+
+    assert!(state.reserve_special_prize().unwrap().is_none());
+    assert_eq!(state.pool, 10_000);
+}
+
+#[test]
+fn special_prize_cannot_exceed_the_pool() {
+    let mut state = state(500, 1_000);
+
+    assert_eq!(
+        state.reserve_special_prize().err(),
+        Some(ErrorCode::SpecialPrizeExceedsPool.into())
+    );
+}
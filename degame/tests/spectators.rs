@@ -0,0 +1,18 @@
+use degame::*;
+
+mod common;
+
+fn player() -> VersionedAccount<Player> {
+    common::player()
+}
+
+#[test]
+fn third_spectator_exceeds_the_limit() {
+    let mut player = player();
+    player.add_spectator(2).unwrap();
+    player.add_spectator(2).unwrap();
+
+    assert_eq!(player.spectator_count, 2);
+    assert_eq!(player.add_spectator(2).unwrap_err(), ErrorCode::MaxSpectatorsReached.into());
+    assert_eq!(player.spectator_count, 2);
+}
@@ -0,0 +1,348 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+use degame::*;
+
+mod common;
+
+use common::program::{failure, process, TestAccount};
+
+const NOW: i64 = 1_700_000_000;
+const STATE: Pubkey = Pubkey::new_from_array([1; 32]);
+const CONFIG: Pubkey = Pubkey::new_from_array([2; 32]);
+const PLAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+const AUTHORITY: Pubkey = Pubkey::new_from_array([4; 32]);
+const ENTRY_FEE: u64 = 1_000;
+const WALLET_BALANCE: u64 = 1_000_000;
+
+fn ban_list_key() -> Pubkey {
+    Pubkey::find_program_address(&[b"banlist", STATE.as_ref()], &degame::ID).0
+}
+
+fn blacklist_key(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"blacklist", STATE.as_ref(), wallet.as_ref()],
+        &degame::ID,
+    )
+    .0
+}
+
+/// State and config of a competition whose pool holds `pool` lamports out of
+/// at most `max_pool_size`.
+fn competition(pool: u64, max_pool_size: u64) -> [TestAccount; 2] {
+    let state = State {
+        config: CONFIG,
+        pool,
+        ..common::state()
+    };
+    let mut config = common::config();
+    config.entry_fee = ENTRY_FEE;
+    config.max_pool_size = max_pool_size;
+    config.starting_multiplier_bps = BPS_DENOMINATOR;
+
+    [
+        TestAccount::program(STATE, &state, state_space(MAX_LEADERBOARD_SIZE as u8)),
+        TestAccount::program(
+            CONFIG,
+            &config,
+            VERSIONED_HEADER_LEN + CompetitionConfig::LEN,
+        ),
+    ]
+}
+
+/// Accounts for `start_game` by `signer` on the player bound to `AUTHORITY`
+/// in an uncapped competition whose ban list lists `banned`.
+fn accounts(signer: Pubkey, banned: Vec<Pubkey>) -> Vec<TestAccount> {
+    let mut player = common::player();
+    player.authority = AUTHORITY;
+
+    let [state, config] = competition(0, 0);
+    vec![
+        state,
+        config,
+        TestAccount::program(PLAYER, &*player, player_space()),
+        TestAccount::signer(signer, WALLET_BALANCE),
+        TestAccount::missing(blacklist_key(&AUTHORITY)),
+        TestAccount::program(
+            ban_list_key(),
+            &BanList {
+                banned_players: banned,
+            },
+            VERSIONED_HEADER_LEN + BanList::LEN,
+        ),
+        TestAccount::none(),
+        TestAccount::system_program(),
+    ]
+}
+
+fn start_game(accounts: &mut [TestAccount]) -> ProgramResult {
+    process(
+        instruction::StartGame {
+            game_id: 1,
+            deck_offset: 0,
+        },
+        accounts,
+        NOW,
+    )
+}
+
+#[test]
+fn bound_authority_starts_a_game() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+    let player = accounts[2].read::<Player>();
+    assert_eq!(player.game_id, 1);
+    assert_eq!(player.start_time, NOW);
+}
+
+#[test]
+fn other_signers_cannot_start_the_players_game() {
+    let mut accounts = accounts(Pubkey::new_unique(), vec![]);
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::Unauthorized));
+}
+
+#[test]
+fn banned_authority_cannot_start_a_game() {
+    let mut accounts = accounts(AUTHORITY, vec![AUTHORITY]);
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::PlayerBanned));
+}
+
+#[test]
+fn ban_list_cannot_be_swapped_for_another() {
+    let mut accounts = accounts(AUTHORITY, vec![AUTHORITY]);
+    let ban_list = BanList { banned_players: vec![] };
+    accounts[5] = TestAccount::program(Pubkey::new_unique(), &ban_list, VERSIONED_HEADER_LEN + BanList::LEN);
+
+    assert_eq!(
+        start_game(&mut accounts),
+        Err(ProgramError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into()))
+    );
+}
+
+#[test]
+fn competition_without_a_ban_list_bans_nobody() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    accounts[5] = TestAccount::missing(ban_list_key());
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+}
+
+#[test]
+fn blacklisted_authority_cannot_start_a_game() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let entry = BlacklistEntry { wallet: AUTHORITY };
+    accounts[4] = TestAccount::program(
+        blacklist_key(&AUTHORITY),
+        &entry,
+        VERSIONED_HEADER_LEN + BlacklistEntry::LEN,
+    );
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::Blacklisted));
+}
+
+#[test]
+fn entry_fee_moves_into_the_pool() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let state_lamports = accounts[0].lamports;
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+    assert_eq!(accounts[0].read::<State>().pool, ENTRY_FEE);
+    assert_eq!(accounts[0].lamports, state_lamports + ENTRY_FEE);
+    assert_eq!(accounts[3].lamports, WALLET_BALANCE - ENTRY_FEE);
+}
+
+#[test]
+fn entry_fees_accrue_a_share_to_the_jackpot() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let mut config = accounts[1].read::<CompetitionConfig>();
+    config.jackpot_contribution_bps = 2_500;
+    accounts[1] = TestAccount::program(CONFIG, &*config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN);
+    let state_lamports = accounts[0].lamports;
+
+    for game_id in 1..=2 {
+        assert_eq!(
+            process(instruction::StartGame { game_id, deck_offset: 0 }, &mut accounts, NOW),
+            Ok(())
+        );
+    }
+    let state = accounts[0].read::<State>();
+    assert_eq!(state.pool, 2 * ENTRY_FEE * 3 / 4);
+    assert_eq!(state.jackpot, 2 * ENTRY_FEE / 4);
+    assert_eq!(accounts[0].lamports, state_lamports + 2 * ENTRY_FEE);
+}
+
+#[test]
+fn last_entry_fills_the_pool_to_its_cap() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    accounts.splice(0..2, competition(2 * ENTRY_FEE, 3 * ENTRY_FEE));
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+    assert_eq!(accounts[0].read::<State>().pool, 3 * ENTRY_FEE);
+}
+
+#[test]
+fn entry_past_the_cap_is_refused_and_not_charged() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    accounts.splice(0..2, competition(3 * ENTRY_FEE, 3 * ENTRY_FEE));
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::PoolFull));
+    assert_eq!(accounts[3].lamports, WALLET_BALANCE);
+}
+
+/// `start_game` accounts for a player owed `pending_winnings` by the
+/// competition `won_in`.
+fn with_winnings(won_in: Pubkey, pending_winnings: u64) -> Vec<TestAccount> {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let mut player = accounts[2].read::<Player>();
+    player.state = won_in;
+    player.pending_winnings = pending_winnings;
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+    accounts
+}
+
+#[test]
+fn unpaid_winnings_keep_the_player_in_their_competition() {
+    let mut accounts = with_winnings(Pubkey::new_unique(), 2_500);
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::WinningsUnpaid));
+}
+
+#[test]
+fn player_with_winnings_plays_on_in_the_same_competition() {
+    for (won_in, pending_winnings) in [(STATE, 2_500), (Pubkey::new_unique(), 0)] {
+        let mut accounts = with_winnings(won_in, pending_winnings);
+
+        assert_eq!(start_game(&mut accounts), Ok(()));
+        assert_eq!(accounts[2].read::<Player>().state, STATE);
+    }
+}
+
+const WON_IN: u32 = 5;
+const COOLDOWN: u8 = 2;
+
+/// `start_game` accounts in competition `competition_index` for a player who
+/// won competition `WON_IN`, under a two-competition cooldown.
+fn after_win(competition_index: u32) -> Vec<TestAccount> {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let mut state = accounts[0].read::<State>();
+    state.competition_index = competition_index;
+    let mut config = accounts[1].read::<CompetitionConfig>();
+    config.post_win_cooldown_competitions = COOLDOWN;
+    let mut player = accounts[2].read::<Player>();
+    player.last_win_competition_index = Some(WON_IN);
+
+    accounts[0] = TestAccount::program(STATE, &*state, state_space(MAX_LEADERBOARD_SIZE as u8));
+    accounts[1] = TestAccount::program(CONFIG, &*config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN);
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+    accounts
+}
+
+#[test]
+fn winner_sits_out_the_cooldown() {
+    for competition_index in [WON_IN + 1, WON_IN + u32::from(COOLDOWN)] {
+        let mut accounts = after_win(competition_index);
+
+        assert_eq!(start_game(&mut accounts), failure(ErrorCode::PlayerInCooldown));
+    }
+}
+
+#[test]
+fn winner_returns_once_the_cooldown_passes() {
+    let mut accounts = after_win(WON_IN + u32::from(COOLDOWN) + 1);
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+}
+
+#[test]
+fn exempt_winner_skips_the_cooldown() {
+    let admin = Pubkey::new_unique();
+    let mut accounts = after_win(WON_IN + 1);
+    let mut state = accounts[0].read::<State>();
+    state.admin = admin;
+    accounts[0] = TestAccount::program(STATE, &*state, state_space(MAX_LEADERBOARD_SIZE as u8));
+
+    let mut exempt = [accounts[0].clone(), accounts[2].clone(), TestAccount::signer(admin, 0)];
+    assert_eq!(
+        process(instruction::ExemptFromCooldown { player: PLAYER }, &mut exempt, NOW),
+        Ok(())
+    );
+    accounts[2] = exempt[1].clone();
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+}
+
+#[test]
+fn waiver_applies_only_to_the_competition_that_granted_it() {
+    let admin = Pubkey::new_unique();
+    let mut accounts = after_win(WON_IN + 1);
+    let mut own_state = accounts[0].read::<State>();
+    own_state.admin = admin;
+    let own_state = TestAccount::program(Pubkey::new_unique(), &*own_state, state_space(MAX_LEADERBOARD_SIZE as u8));
+
+    let mut exempt = [own_state, accounts[2].clone(), TestAccount::signer(admin, 0)];
+    assert_eq!(
+        process(instruction::ExemptFromCooldown { player: PLAYER }, &mut exempt, NOW),
+        Ok(())
+    );
+    accounts[2] = exempt[1].clone();
+
+    assert_eq!(start_game(&mut accounts), failure(ErrorCode::PlayerInCooldown));
+}
+
+#[test]
+fn only_the_admin_waives_a_cooldown() {
+    let accounts = after_win(WON_IN + 1);
+    let mut exempt = [accounts[0].clone(), accounts[2].clone(), TestAccount::signer(AUTHORITY, 0)];
+
+    assert_eq!(
+        process(instruction::ExemptFromCooldown { player: PLAYER }, &mut exempt, NOW),
+        failure(ErrorCode::Unauthorized)
+    );
+}
+
+const STARTING_MULTIPLIER_BPS: u64 = 15_000;
+
+#[test]
+fn game_starts_at_the_configured_multiplier() {
+    let mut accounts = accounts(AUTHORITY, vec![]);
+    let mut config = accounts[1].read::<CompetitionConfig>();
+    config.starting_multiplier_bps = STARTING_MULTIPLIER_BPS;
+    accounts[1] = TestAccount::program(CONFIG, &*config, VERSIONED_HEADER_LEN + CompetitionConfig::LEN);
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+    assert_eq!(accounts[2].read::<Player>().multiplier, 1.5);
+}
+
+#[test]
+fn first_gain_compounds_from_the_starting_multiplier() {
+    use common::game::{config, dealt_player, high, place, settle_on, winning_hash, BET_SLOT};
+
+    let config = config();
+    let win_from = |multiplier: f64| {
+        let player = Player { multiplier, ..dealt_player() };
+        let (result, player, _) = place(player, &config, high(None), BET_SLOT);
+        assert_eq!(result, Ok(()));
+        let hash = winning_hash(&player, &config);
+        let (result, player) = settle_on(player, &config, hash);
+        assert_eq!(result, Ok(()));
+        player.multiplier
+    };
+
+    let gain = win_from(1.0);
+    assert!(gain > 1.0);
+    assert_eq!(win_from(1.5), 1.5 * gain);
+}
+
+#[test]
+fn games_count_towards_the_competition_they_start_in() {
+    let mut accounts = with_winnings(Pubkey::new_unique(), 0);
+    let mut player = accounts[2].read::<Player>();
+    player.competition_games = 7;
+    accounts[2] = TestAccount::program(PLAYER, &*player, player_space());
+
+    assert_eq!(start_game(&mut accounts), Ok(()));
+    assert_eq!(accounts[2].read::<Player>().competition_games, 1);
+}
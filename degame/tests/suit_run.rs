@@ -0,0 +1,105 @@
+use anchor_lang::error::Error;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use degame::*;
+
+mod common;
+
+use common::program::failure;
+
+fn card(suit: &str, value: u8) -> Card {
+    Card { suit: suit.to_string(), value }
+}
+
+/// A low card showing, then four hearts and a spade.
+fn deck() -> Vec<Card> {
+    vec![
+        card("Clubs", 2),
+        card("Hearts", 9),
+        card("Hearts", 5),
+        card("Hearts", 13),
+        card("Hearts", 7),
+        card("Spades", 3),
+    ]
+}
+
+fn suit_run(length: u8, deck: &[Card]) -> Result<Option<i64>, Error> {
+    let mut player = common::player();
+    player.multiplier = 1.0;
+    let side_bet = SideBetType::SuitRun { length };
+    resolve_bet(&mut player, deck, &BetType::High, Some(side_bet), false, SideBetCard::Current)
+        .map(|outcome| outcome.side_bet_result)
+}
+
+#[test]
+fn run_of_one_suit_pays_by_length() {
+    assert_eq!(suit_run(2, &deck()).unwrap(), Some(3));
+    assert_eq!(suit_run(3, &deck()).unwrap(), Some(12));
+    assert_eq!(suit_run(4, &deck()).unwrap(), Some(45));
+}
+
+#[test]
+fn broken_run_loses() {
+    let mut deck = deck();
+    deck[3] = card("Diamonds", 13);
+
+    assert_eq!(suit_run(2, &deck).unwrap(), Some(3));
+    assert_eq!(suit_run(3, &deck).unwrap(), Some(-1));
+}
+
+#[test]
+fn run_starts_after_the_card_showing() {
+    let mut deck = deck();
+    deck[0] = card("Hearts", 2);
+    deck[4] = card("Spades", 7);
+
+    assert_eq!(suit_run(3, &deck).unwrap(), Some(12));
+    assert_eq!(suit_run(4, &deck).unwrap(), Some(-1));
+}
+
+#[test]
+fn run_length_outside_two_to_four_is_rejected() {
+    for length in [0, 1, 5] {
+        assert_eq!(suit_run(length, &deck()).unwrap_err(), ErrorCode::InvalidSideBet.into());
+    }
+}
+
+#[test]
+fn run_past_the_end_of_the_deck_is_rejected() {
+    assert_eq!(
+        suit_run(4, &deck()[..4]).unwrap_err(),
+        ErrorCode::InsufficientDeckForSideBet.into()
+    );
+}
+
+/// Places a high bet with a suit run of `length` on a player `cards_dealt`
+/// cards into the deck.
+fn place_suit_run(length: u8, cards_dealt: u8) -> ProgramResult {
+    use common::game::{config, dealt_player, high, place, BET_SLOT};
+
+    let config = CompetitionConfig {
+        max_side_bets_per_game: STANDARD_DECK_SIZE,
+        ..config()
+    };
+    let player = Player {
+        cards_dealt,
+        ..dealt_player()
+    };
+    let (result, player, _) = place(player, &config, high(Some(SideBetType::SuitRun { length })), BET_SLOT);
+    assert_eq!(player.pending_bet.is_some(), result.is_ok());
+    result
+}
+
+#[test]
+fn bet_with_an_invalid_run_length_is_refused() {
+    assert_eq!(place_suit_run(2, 0), Ok(()));
+    assert_eq!(place_suit_run(5, 0), failure(ErrorCode::InvalidSideBet));
+}
+
+#[test]
+fn bet_on_a_run_past_the_deck_is_refused() {
+    // Two cards follow the one showing.
+    let cards_dealt = STANDARD_DECK_SIZE - 3;
+
+    assert_eq!(place_suit_run(2, cards_dealt), Ok(()));
+    assert_eq!(place_suit_run(3, cards_dealt), failure(ErrorCode::InsufficientDeckForSideBet));
+}
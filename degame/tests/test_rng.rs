@@ -0,0 +1,50 @@
+//! Run with `cargo test --features test-rng`.
+#![cfg(feature = "test-rng")]
+
+use degame::*;
+
+mod common;
+
+const SEED: u64 = 42;
+
+fn player() -> VersionedAccount<Player> {
+    common::player()
+}
+
+#[test]
+fn injected_seed_commits_to_its_deck() {
+    let mut player = player();
+    inject_test_seed(&mut player, SEED, 100).unwrap();
+
+    assert_eq!(player.deck_commitment, Some(commit_seed(SEED)));
+    assert_eq!(player.last_randomness_at, 100);
+    assert_eq!(
+        inject_test_seed(&mut player, SEED, 100).unwrap_err(),
+        ErrorCode::RandomnessAlreadyReceived.into()
+    );
+}
+
+#[test]
+fn scripted_game_wins_every_bet_on_a_fixed_deck() {
+    let mut player = player();
+    inject_test_seed(&mut player, SEED, 0).unwrap();
+    let deck = shuffle_deck(SEED, 0);
+    let values = |deck: &[Card]| deck.iter().map(|card| card.value).collect::<Vec<_>>();
+    assert_eq!(values(&deck), values(&shuffle_deck(SEED, 0)));
+
+    // Knowing the deck, always bet towards the next card; equal values lose
+    // either way and end the script.
+    while player.cards_dealt as usize + 2 <= deck.len() {
+        let dealt = player.cards_dealt as usize;
+        let (current, next) = (deck[dealt].value, deck[dealt + 1].value);
+        if current == next {
+            break;
+        }
+        let bet = if next > current { BetType::High } else { BetType::Low };
+
+        let outcome = resolve_bet(&mut player, &deck, &bet, None, false, SideBetCard::Current);
+        assert!(outcome.unwrap().correct);
+        assert_eq!(player.cards_dealt as usize, dealt + 1);
+    }
+    assert!(player.cards_dealt > 0);
+}
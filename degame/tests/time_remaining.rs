@@ -0,0 +1,51 @@
+use degame::*;
+
+mod common;
+
+const START: i64 = 1_700_000_000;
+const END: i64 = START + 86_400;
+
+fn state() -> State {
+    State {
+        start_time: START,
+        end_time: END,
+        leaderboard_size: 3,
+        ..common::state()
+    }
+}
+
+fn config() -> CompetitionConfig {
+    let mut config = common::config();
+    config.prize_claim_window_hours = 24;
+    config
+}
+
+#[test]
+fn countdowns_before_the_start() {
+    let info = time_remaining(&state(), &config(), START - 60);
+
+    assert_eq!(
+        info,
+        TimeInfo { until_start: 60, until_end: 86_460, until_claim_deadline: None }
+    );
+}
+
+#[test]
+fn passed_milestones_go_negative() {
+    let info = time_remaining(&state(), &config(), START + 100);
+
+    assert_eq!(info.until_start, -100);
+    assert_eq!(info.until_end, 86_300);
+}
+
+#[test]
+fn claim_deadline_is_known_once_finalized() {
+    let mut state = state();
+    state.finalized = true;
+    state.finalized_timestamp = END + 10;
+
+    let info = time_remaining(&state, &config(), END + 3_610);
+
+    assert_eq!(info.until_end, -3_610);
+    assert_eq!(info.until_claim_deadline, Some(86_400 - 3_600));
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AnchorDeserialize;
+use degame::*;
+
+const WINNER: Pubkey = Pubkey::new_from_array([1; 32]);
+const MINT: Pubkey = Pubkey::new_from_array([2; 32]);
+const METADATA: Pubkey = Pubkey::new_from_array([3; 32]);
+const URI_BASE: &str = "https://degame.gg/trophies/";
+
+/// Stand-in for Token Metadata: accepts only `CreateMetadataAccountV3` for
+/// `MINT` signed by `WINNER`, and returns the metadata it would store.
+fn mock_metaplex(instruction: &Instruction) -> Option<WinnerNftMetadata> {
+    let accounts = &instruction.accounts;
+    if instruction.program_id != TOKEN_METADATA_PROGRAM_ID
+        || instruction.data[0] != CREATE_METADATA_ACCOUNT_V3_TAG
+        || accounts[0].pubkey != METADATA
+        || accounts[1].pubkey != MINT
+        || !(accounts[2].pubkey == WINNER && accounts[2].is_signer)
+    {
+        return None;
+    }
+
+    let mut data = &instruction.data[1..];
+    let metadata = WinnerNftMetadata::deserialize(&mut data).ok()?;
+    // No royalties, creators, collection or uses; immutable.
+    if data != [0, 0, 0, 0, 0, 0, 0] {
+        return None;
+    }
+    Some(metadata)
+}
+
+fn minted_uri(position: u8) -> String {
+    let position = LeaderboardPosition::new(position, MAX_LEADERBOARD_SIZE).unwrap();
+    let metadata = WinnerNftMetadata {
+        name: "degame #1 place 1".to_string(),
+        symbol: WINNER_NFT_SYMBOL.to_string(),
+        uri: winner_nft_uri(URI_BASE, position),
+    };
+    let instruction = build_create_metadata_instruction(
+        TOKEN_METADATA_PROGRAM_ID,
+        METADATA,
+        MINT,
+        WINNER,
+        &metadata,
+    )
+    .unwrap();
+
+    mock_metaplex(&instruction).unwrap().uri
+}
+
+#[test]
+fn each_winner_gets_their_position_uri() {
+    assert_eq!(minted_uri(0), "https://degame.gg/trophies/0.json");
+    assert_eq!(minted_uri(1), "https://degame.gg/trophies/1.json");
+    assert_eq!(minted_uri(2), "https://degame.gg/trophies/2.json");
+}
+
+#[test]
+fn metadata_is_created_with_the_nft_fields() {
+    let metadata = WinnerNftMetadata {
+        name: "degame #4 place 2".to_string(),
+        symbol: WINNER_NFT_SYMBOL.to_string(),
+        uri: format!("{URI_BASE}1.json"),
+    };
+    let instruction =
+        build_create_metadata_instruction(TOKEN_METADATA_PROGRAM_ID, METADATA, MINT, WINNER, &metadata)
+            .unwrap();
+    let stored = mock_metaplex(&instruction).unwrap();
+
+    assert_eq!(stored.name, "degame #4 place 2");
+    assert_eq!(stored.symbol, "DEGAME");
+    assert!(instruction.accounts[3].is_writable && instruction.accounts[3].is_signer);
+}